@@ -3,9 +3,21 @@ use std::{path::PathBuf, str::FromStr};
 use clap::{ArgAction, Parser, Subcommand};
 use mojave_node_lib::{
     initializers::get_signer,
-    types::{Node, SyncMode},
+    reorg_guard::DEFAULT_MAX_REORG_DEPTH,
+    rpc::READ_ONLY_DENIED_METHODS,
+    types::{
+        DEFAULT_FILTER_CLEANUP_INTERVAL_SECS, DEFAULT_FILTER_TTL_SECS,
+        DEFAULT_PENDING_BLOCKS_LIMIT, DEFAULT_SYNC_PIPELINE_DEPTH, Node, PendingBlockEviction,
+        SyncMode,
+    },
+    utils::{remove_db, resolve_data_dir},
+};
+use mojave_signature::VerifyingKey;
+use mojave_utils::{
+    daemon::stop_daemonized,
+    network::{GenesisOverrides, Network},
+    p2p::public_key_from_signing_key,
 };
-use mojave_utils::{daemon::stop_daemonized, network::Network, p2p::public_key_from_signing_key};
 use std::net::ToSocketAddrs;
 use tracing::Level;
 
@@ -74,7 +86,7 @@ pub struct Options {
         help = "If the datadir is the word `memory`, ethrex will use the InMemory Engine",
         default_value = ".mojave/node",
         help = "Receives the name of the directory where the Database is located.",
-        long_help = "If the datadir is the word `memory`, ethrex will use the `InMemory Engine`.",
+        long_help = "If the datadir is the word `memory`, ethrex will use the `InMemory Engine`. Relative paths are resolved against the current working directory.",
         help_heading = "Node options",
         env = "ETHREX_DATADIR",
         global = true
@@ -113,6 +125,24 @@ pub struct Options {
     )]
     pub network: Network,
 
+    #[arg(
+        long = "dev.chain-id",
+        value_name = "CHAIN_ID",
+        help = "Override the chain id of the default network genesis.",
+        long_help = "Only applies when `--network` is `default`. Useful for spinning up isolated dev chains without writing a genesis file.",
+        help_heading = "Dev options"
+    )]
+    pub dev_chain_id: Option<u64>,
+
+    #[arg(
+        long = "dev.genesis-time",
+        value_name = "UNIX_TIMESTAMP",
+        help = "Override the timestamp of the default network genesis.",
+        long_help = "Only applies when `--network` is `default`.",
+        help_heading = "Dev options"
+    )]
+    pub dev_genesis_time: Option<u64>,
+
     #[arg(
         long = "bootnodes",
         value_parser = clap::value_parser!(DNSNode),
@@ -124,6 +154,24 @@ pub struct Options {
     )]
     pub bootnodes: Vec<DNSNode>,
 
+    #[arg(
+        long = "bootnodes.url",
+        value_name = "URL",
+        help = "URL serving a JSON array of enode URLs to add to the bootnodes.",
+        long_help = "Fetched once at startup and merged with the `--bootnodes` list and the network's preset bootnodes, so the published list can change without redeploying.",
+        help_heading = "P2P options"
+    )]
+    pub bootnodes_url: Option<String>,
+
+    #[arg(
+        long = "bootnodes.strict",
+        help = "Fail startup when the bootnodes cannot be loaded.",
+        long_help = "Without this flag, a preset bootnodes file that cannot be parsed or a `--bootnodes.url` that cannot be fetched is logged and ignored, leaving the node without those bootnodes.",
+        action = ArgAction::SetTrue,
+        help_heading = "P2P options"
+    )]
+    pub strict_bootnodes: bool,
+
     #[arg(
         long = "syncmode",
         value_enum,
@@ -134,6 +182,49 @@ pub struct Options {
     )]
     pub syncmode: Option<SyncMode>,
 
+    #[arg(
+        long = "sync.full-node-urls",
+        value_name = "URL_LIST",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Comma separated RPC URLs of full nodes to fetch missing blocks from.",
+        long_help = "When set, blocks missing between the local head and the blocks received from the sequencer are fetched from these full nodes.",
+        help_heading = "P2P options"
+    )]
+    pub sync_full_node_urls: Vec<String>,
+
+    #[arg(
+        long = "sync.pipeline-depth",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_SYNC_PIPELINE_DEPTH,
+        value_parser = clap::value_parser!(usize).range(1..),
+        help = "How many missing blocks are fetched concurrently while catching up.",
+        long_help = "Blocks are still applied strictly in order; a deeper pipeline only overlaps the fetches.",
+        help_heading = "P2P options"
+    )]
+    pub sync_pipeline_depth: usize,
+
+    #[arg(
+        long = "sync.pending-blocks-limit",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_PENDING_BLOCKS_LIMIT,
+        value_parser = clap::value_parser!(usize).range(1..),
+        help = "Most signed blocks held while waiting for their predecessors.",
+        help_heading = "P2P options"
+    )]
+    pub pending_blocks_limit: usize,
+
+    #[arg(
+        long = "sync.pending-blocks-eviction",
+        value_enum,
+        value_name = "POLICY",
+        default_value_t = PendingBlockEviction::default(),
+        help = "Which pending block is dropped once the limit is reached.",
+        long_help = "Can be either \"highest\" (the block furthest ahead of the head) or \"oldest\" (the block waiting the longest).",
+        help_heading = "P2P options"
+    )]
+    pub pending_blocks_eviction: PendingBlockEviction,
+
     #[arg(
         long = "sponsorable-addresses",
         value_name = "SPONSORABLE_ADDRESSES_PATH",
@@ -142,10 +233,31 @@ pub struct Options {
     )]
     pub sponsorable_addresses_file_path: Option<String>,
 
+    #[arg(
+        long = "sequencer.verifying-key",
+        value_name = "PUBLIC_KEY",
+        value_parser = clap::value_parser!(VerifyingKey),
+        help = "Public key of the sequencer whose blocks are accepted.",
+        long_help = "When set, blocks pushed to this node are rejected unless they are signed by this key.",
+        help_heading = "L2 options",
+        env = "MOJAVE_SEQUENCER_VERIFYING_KEY"
+    )]
+    pub sequencer_verifying_key: Option<VerifyingKey>,
+
+    #[arg(
+        long = "max-reorg-depth",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_MAX_REORG_DEPTH,
+        help = "Deepest reorg the node follows automatically.",
+        long_help = "A reorg deeper than this many blocks halts block ingestion with a critical error instead of rewriting history.",
+        help_heading = "L2 options"
+    )]
+    pub max_reorg_depth: u64,
+
     #[arg(
         long = "force",
         help = "Force remove the database",
-        long_help = "Delete the database without confirmation.",
+        long_help = "Delete the database without confirmation before starting. Refuses to delete directories that don't look like a mojave datadir.",
         action = clap::ArgAction::SetTrue,
         help_heading = "Node options"
     )]
@@ -176,6 +288,15 @@ pub struct Options {
     )]
     pub metrics_enabled: bool,
 
+    #[arg(
+        long = "metrics.namespace",
+        value_name = "NAMESPACE",
+        help = "Prefix for all emitted metric names.",
+        long_help = "Defaults to the binary name, e.g. `mojave_node`, so processes sharing a host don't collide. Pass an empty string to disable the prefix.",
+        help_heading = "Node options"
+    )]
+    pub metrics_namespace: Option<String>,
+
     #[arg(
         long = "http.addr",
         default_value = "0.0.0.0",
@@ -196,6 +317,47 @@ pub struct Options {
     )]
     pub http_port: String,
 
+    #[arg(
+        long = "rpc.read-only",
+        help = "Refuse RPC methods that submit transactions.",
+        long_help = "Serve queries only: the methods in --rpc.read-only.denied-methods, eth_sendRawTransaction and eth_sendTransaction by default, are rejected with a method not found error. Use this for untrusted-facing query nodes.",
+        action = ArgAction::SetTrue,
+        help_heading = "RPC options"
+    )]
+    pub rpc_read_only: bool,
+
+    #[arg(
+        long = "rpc.read-only.denied-methods",
+        value_name = "METHOD_LIST",
+        value_delimiter = ',',
+        num_args = 1..,
+        default_values_t = READ_ONLY_DENIED_METHODS.iter().map(|method| method.to_string()),
+        requires = "rpc_read_only",
+        help = "Comma separated RPC methods refused in read-only mode.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_read_only_denied_methods: Vec<String>,
+
+    #[arg(
+        long = "rpc.filter-ttl",
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_FILTER_TTL_SECS,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds a filter created with eth_newFilter lives before it is dropped.",
+        help_heading = "RPC options"
+    )]
+    pub filter_ttl: u64,
+
+    #[arg(
+        long = "rpc.filter-cleanup-interval",
+        value_name = "SECONDS",
+        default_value_t = DEFAULT_FILTER_CLEANUP_INTERVAL_SECS,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds between purges of expired filters.",
+        help_heading = "RPC options"
+    )]
+    pub filter_cleanup_interval: u64,
+
     #[arg(
         long = "authrpc.addr",
         default_value = "localhost",
@@ -282,12 +444,18 @@ impl From<&Options> for mojave_node_lib::types::NodeOptions {
             discovery_addr: options.discovery_addr.clone(),
             discovery_port: options.discovery_port.clone(),
             network: options.network.clone(),
+            genesis_overrides: GenesisOverrides {
+                chain_id: options.dev_chain_id,
+                genesis_time: options.dev_genesis_time,
+            },
             bootnodes: options
                 .bootnodes
                 .iter()
                 .cloned()
                 .map(|dn| dn.into())
                 .collect(),
+            bootnodes_url: options.bootnodes_url.clone(),
+            strict_bootnodes: options.strict_bootnodes,
             datadir: options.datadir.clone(),
             syncmode: options.syncmode.unwrap_or(SyncMode::Full),
             sponsorable_addresses_file_path: options.sponsorable_addresses_file_path.clone(),
@@ -297,6 +465,16 @@ impl From<&Options> for mojave_node_lib::types::NodeOptions {
             force: options.force,
             health_addr: options.health_addr.clone(),
             health_port: options.health_port.clone(),
+            sequencer_verifying_key: options.sequencer_verifying_key.clone(),
+            max_reorg_depth: options.max_reorg_depth,
+            sync_full_node_urls: options.sync_full_node_urls.clone(),
+            sync_pipeline_depth: options.sync_pipeline_depth,
+            pending_blocks_limit: options.pending_blocks_limit,
+            pending_blocks_eviction: options.pending_blocks_eviction,
+            rpc_read_only: options.rpc_read_only,
+            rpc_read_only_denied_methods: options.rpc_read_only_denied_methods.clone(),
+            filter_ttl: options.filter_ttl,
+            filter_cleanup_interval: options.filter_cleanup_interval,
         }
     }
 }
@@ -329,6 +507,15 @@ pub enum Command {
     Stop,
     #[command(name = "get-pub-key", about = "Display the public key of the node")]
     GetPubKey,
+    #[command(name = "remove-db", about = "Remove the database in the datadir")]
+    RemoveDb {
+        #[arg(
+            long = "force",
+            help = "Skip the confirmation prompt",
+            action = clap::ArgAction::SetTrue
+        )]
+        force: bool,
+    },
 }
 
 impl Command {
@@ -342,6 +529,12 @@ impl Command {
                 println!("{public_key}");
                 Ok(())
             }
+            Command::RemoveDb { force } => {
+                let (data_dir, _) = resolve_data_dir(&datadir).await?;
+                remove_db(&data_dir, force)
+                    .await
+                    .map_err(anyhow::Error::from)
+            }
         }
     }
 }
@@ -378,6 +571,8 @@ mod tests {
 
         assert!(matches!(options.network, Network::DefaultNet));
         assert!(options.bootnodes.is_empty());
+        assert!(options.bootnodes_url.is_none());
+        assert!(!options.strict_bootnodes);
         assert!(options.syncmode.is_none());
         assert!(options.sponsorable_addresses_file_path.is_none());
         assert!(!options.force);
@@ -400,6 +595,7 @@ mod tests {
         assert_eq!(options.metrics_addr, "0.0.0.0");
         assert_eq!(options.metrics_port, "9090");
         assert!(!options.metrics_enabled);
+        assert!(options.metrics_namespace.is_none());
         assert!(!options.force);
         assert_eq!(options.datadir, ".mojave/node");
 
@@ -428,7 +624,160 @@ mod tests {
         assert_eq!(node_opts.metrics_port, options.metrics_port);
         assert_eq!(node_opts.metrics_enabled, options.metrics_enabled);
         assert_eq!(node_opts.force, options.force);
+        assert!(node_opts.genesis_overrides.is_empty());
         assert_eq!(node_opts.datadir, ".mojave/node".to_string());
+        assert!(node_opts.sequencer_verifying_key.is_none());
+        assert_eq!(node_opts.max_reorg_depth, DEFAULT_MAX_REORG_DEPTH);
+        assert!(node_opts.sync_full_node_urls.is_empty());
+        assert_eq!(node_opts.sync_pipeline_depth, DEFAULT_SYNC_PIPELINE_DEPTH);
+        assert_eq!(node_opts.pending_blocks_limit, DEFAULT_PENDING_BLOCKS_LIMIT);
+        assert_eq!(
+            node_opts.pending_blocks_eviction,
+            PendingBlockEviction::Highest
+        );
+        assert!(!node_opts.rpc_read_only);
+        assert_eq!(node_opts.filter_ttl, DEFAULT_FILTER_TTL_SECS);
+        assert_eq!(
+            node_opts.filter_cleanup_interval,
+            DEFAULT_FILTER_CLEANUP_INTERVAL_SECS
+        );
+    }
+
+    #[test]
+    fn parse_bootnodes_url_options() {
+        let Cli { options, .. } = Cli::try_parse_from([
+            "mojave-node",
+            "--bootnodes.url",
+            "https://example.com/bootnodes.json",
+            "--bootnodes.strict",
+        ])
+        .unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(
+            node_opts.bootnodes_url.as_deref(),
+            Some("https://example.com/bootnodes.json")
+        );
+        assert!(node_opts.strict_bootnodes);
+    }
+
+    #[test]
+    fn parse_rpc_read_only() {
+        let Cli { options, .. } = Cli::try_parse_from(["mojave-node", "--rpc.read-only"]).unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert!(node_opts.rpc_read_only);
+        assert_eq!(
+            node_opts.rpc_read_only_denied_methods,
+            READ_ONLY_DENIED_METHODS
+        );
+
+        let Cli { options, .. } = Cli::try_parse_from([
+            "mojave-node",
+            "--rpc.read-only",
+            "--rpc.read-only.denied-methods",
+            "eth_sendRawTransaction,eth_newFilter",
+        ])
+        .unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(
+            node_opts.rpc_read_only_denied_methods,
+            ["eth_sendRawTransaction", "eth_newFilter"]
+        );
+
+        // The list only means something in read-only mode.
+        assert!(
+            Cli::try_parse_from([
+                "mojave-node",
+                "--rpc.read-only.denied-methods",
+                "eth_newFilter"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_filter_cleanup_options() {
+        let Cli { options, .. } = Cli::try_parse_from([
+            "mojave-node",
+            "--rpc.filter-ttl",
+            "30",
+            "--rpc.filter-cleanup-interval",
+            "5",
+        ])
+        .unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(node_opts.filter_ttl, 30);
+        assert_eq!(node_opts.filter_cleanup_interval, 5);
+
+        assert!(Cli::try_parse_from(["mojave-node", "--rpc.filter-ttl", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_sync_pipeline_options() {
+        let Cli { options, .. } = Cli::try_parse_from([
+            "mojave-node",
+            "--sync.full-node-urls",
+            "http://10.0.0.1:8545,http://10.0.0.2:8545",
+            "--sync.pipeline-depth",
+            "32",
+        ])
+        .unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(
+            node_opts.sync_full_node_urls,
+            vec!["http://10.0.0.1:8545", "http://10.0.0.2:8545"]
+        );
+        assert_eq!(node_opts.sync_pipeline_depth, 32);
+
+        assert!(Cli::try_parse_from(["mojave-node", "--sync.pipeline-depth", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_pending_blocks_options() {
+        let Cli { options, .. } = Cli::try_parse_from([
+            "mojave-node",
+            "--sync.pending-blocks-limit",
+            "128",
+            "--sync.pending-blocks-eviction",
+            "oldest",
+        ])
+        .unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(node_opts.pending_blocks_limit, 128);
+        assert_eq!(
+            node_opts.pending_blocks_eviction,
+            PendingBlockEviction::Oldest
+        );
+
+        assert!(Cli::try_parse_from(["mojave-node", "--sync.pending-blocks-limit", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_metrics_namespace() {
+        let Cli { options, .. } =
+            Cli::try_parse_from(["mojave-node", "--metrics.namespace", "mojave_node_a"]).unwrap();
+        assert_eq!(options.metrics_namespace.as_deref(), Some("mojave_node_a"));
+    }
+
+    #[test]
+    fn parse_sequencer_verifying_key() {
+        let key = mojave_signature::SigningKey::from_str(
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap()
+        .verifying_key();
+        let encoded = String::from(key);
+
+        let Cli { options, .. } =
+            Cli::try_parse_from(["mojave-node", "--sequencer.verifying-key", &encoded]).unwrap();
+        let node_opts: NodeOptions = (&options).into();
+        assert_eq!(
+            node_opts.sequencer_verifying_key.map(String::from),
+            Some(encoded)
+        );
+
+        assert!(
+            Cli::try_parse_from(["mojave-node", "--sequencer.verifying-key", "not-a-key"]).is_err()
+        );
     }
 
     #[test]
@@ -490,6 +839,25 @@ mod tests {
         assert!(options.no_daemon);
     }
 
+    #[test]
+    fn parse_dev_genesis_overrides() {
+        let cli = Cli::try_parse_from([
+            "mojave-node",
+            "--dev.chain-id",
+            "4242",
+            "--dev.genesis-time",
+            "1700000000",
+        ])
+        .unwrap();
+
+        let node_opts: NodeOptions = (&cli.options).into();
+        assert_eq!(node_opts.genesis_overrides.chain_id, Some(4242));
+        assert_eq!(
+            node_opts.genesis_overrides.genesis_time,
+            Some(1_700_000_000)
+        );
+    }
+
     #[test]
     fn parse_stop_and_get_pub_key() {
         let cli = Cli::try_parse_from(["mojave-node", "stop"]).unwrap();
@@ -497,6 +865,12 @@ mod tests {
 
         let cli = Cli::try_parse_from(["mojave-node", "get-pub-key"]).unwrap();
         assert!(matches!(cli.command, Some(Command::GetPubKey)));
+
+        let cli = Cli::try_parse_from(["mojave-node", "remove-db", "--force"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::RemoveDb { force: true })
+        ));
     }
 
     #[test]
@@ -4,7 +4,9 @@ use anyhow::{Context, Result};
 use mojave_node_lib::{rpc::context::RpcApiContext, types::MojaveNode};
 use mojave_rpc_core::types::Namespace;
 use mojave_rpc_server::RpcRegistry;
-use mojave_utils::daemon::{DaemonOptions, run_daemonized};
+use mojave_utils::daemon::{
+    DEFAULT_MAX_LOG_BACKUPS, DEFAULT_MAX_LOG_BYTES, DaemonOptions, run_daemonized,
+};
 use std::path::PathBuf;
 use tracing::{error, info};
 
@@ -15,6 +17,10 @@ fn main() -> Result<()> {
     let cli::Cli { command, options } = cli::Cli::run();
 
     mojave_utils::logging::init(options.log_level);
+    mojave_utils::metrics::init_namespace(
+        options.metrics_namespace.as_deref(),
+        env!("CARGO_PKG_NAME"),
+    );
 
     let rt = build_runtime()?;
 
@@ -77,12 +83,18 @@ fn build_daemon_options(datadir: &str, no_daemon: bool) -> DaemonOptions {
         no_daemon,
         pid_file_path: PathBuf::from(datadir).join(PID_FILE_NAME),
         log_file_path: PathBuf::from(datadir).join(LOG_FILE_NAME),
+        max_log_bytes: Some(DEFAULT_MAX_LOG_BYTES),
+        max_log_backups: DEFAULT_MAX_LOG_BACKUPS,
     }
 }
 
 fn build_registry() -> RpcRegistry<RpcApiContext> {
     RpcRegistry::new().with_fallback(Namespace::Eth, |req, ctx: RpcApiContext| {
-        Box::pin(ethrex_rpc::map_eth_requests(req, ctx.l1_context))
+        Box::pin(async move {
+            ethrex_rpc::map_eth_requests(req, ctx.l1_context)
+                .await
+                .map_err(Into::into)
+        })
     })
 }
 
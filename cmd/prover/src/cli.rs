@@ -1,4 +1,5 @@
 use clap::{Parser, Subcommand};
+use mojave_prover_lib::backend::BackendKind;
 use std::fmt;
 use tracing::Level;
 
@@ -25,12 +26,21 @@ pub struct Cli {
         value_name = "DATA_DIRECTORY",
         default_value = ".mojave/prover",
         help = "Directory for storing prover data.",
-        long_help = "Specifies the directory where the prover will store its data.",
+        long_help = "Specifies the directory where the prover will store its data. Relative paths are resolved against the current working directory.",
         help_heading = "Prover options",
         env = "ETHREX_DATADIR",
         global = true
     )]
     pub datadir: String,
+    #[arg(
+        long = "metrics.namespace",
+        value_name = "NAMESPACE",
+        help = "Prefix for all emitted metric names.",
+        long_help = "Defaults to the binary name, `mojave_prover`, so processes sharing a host don't collide. Pass an empty string to disable the prefix. Metrics are served at /metrics on the prover's HTTP port.",
+        help_heading = "Prover options",
+        global = true
+    )]
+    pub metrics_namespace: Option<String>,
     #[command(subcommand)]
     pub command: Command,
 }
@@ -68,13 +78,29 @@ pub struct ProverOptions {
     )]
     pub queue_capacity: usize,
 
+    #[arg(
+        long = "prover.backend",
+        default_value = "exec",
+        value_name = "BACKEND",
+        help = "Proving backend: exec, risc0, sp1, aligned or mock",
+        help_heading = "Prover Options"
+    )]
+    pub backend: BackendKind,
+
     #[arg(
         long = "prover.aligned-mode",
-        help = "Enable aligned mode for proof generation",
+        help = "Deprecated: use --prover.backend aligned",
         help_heading = "Prover Options"
     )]
     pub aligned_mode: bool,
 
+    #[arg(
+        long = "prover.persist-jobs",
+        help = "Persist proof jobs under the data directory so finished proofs survive restarts",
+        help_heading = "Prover Options"
+    )]
+    pub persist_jobs: bool,
+
     #[arg(
         long = "prover.private_key",
         help = "Private key used for signing proofs",
@@ -82,6 +108,15 @@ pub struct ProverOptions {
     )]
     pub private_key: String,
 
+    #[arg(
+        long = "prover.trusted-keys",
+        value_name = "PUBLIC_KEYS",
+        value_delimiter = ',',
+        help = "Comma-separated verifying keys allowed to sign proof inputs. When set, unsigned or otherwise signed inputs are rejected.",
+        help_heading = "Prover Options"
+    )]
+    pub trusted_keys: Vec<String>,
+
     #[arg(
         long = "no-daemon",
         help = "If set, the prover will run in the foreground (not as a daemon). By default, the prover runs as a daemon.",
@@ -91,14 +126,28 @@ pub struct ProverOptions {
     pub no_daemon: bool,
 }
 
+impl ProverOptions {
+    /// Backend to prove with, honouring the deprecated `--prover.aligned-mode`.
+    pub fn backend(&self) -> BackendKind {
+        if self.aligned_mode {
+            BackendKind::Aligned
+        } else {
+            self.backend
+        }
+    }
+}
+
 impl fmt::Debug for ProverOptions {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("ProverOpts")
             .field("prover_port", &self.prover_port)
             .field("prover_host", &self.prover_host)
             .field("queue_capacity", &self.queue_capacity)
+            .field("backend", &self.backend)
             .field("aligned_mode", &self.aligned_mode)
+            .field("persist_jobs", &self.persist_jobs)
             .field("private_key", &"[REDACTED]")
+            .field("trusted_keys", &self.trusted_keys)
             .field("no_daemon", &self.no_daemon)
             .finish()
     }
@@ -144,6 +193,7 @@ mod tests {
 
         assert_eq!(cli.datadir, ".mojave/prover");
         assert!(cli.log_level.is_none());
+        assert!(cli.metrics_namespace.is_none());
 
         let Command::Start { ref prover_options } = cli.command else {
             panic!("expected start");
@@ -152,8 +202,11 @@ mod tests {
         assert_eq!(prover_options.prover_port, 3900);
         assert_eq!(prover_options.prover_host, "0.0.0.0");
         assert_eq!(prover_options.queue_capacity, 100);
+        assert_eq!(prover_options.backend(), BackendKind::Exec);
         assert!(!prover_options.aligned_mode);
+        assert!(!prover_options.persist_jobs);
         assert_eq!(prover_options.private_key, "0xabc");
+        assert!(prover_options.trusted_keys.is_empty());
         assert!(!prover_options.no_daemon);
     }
 
@@ -173,8 +226,11 @@ mod tests {
             "--prover.queue-capacity",
             "7",
             "--prover.aligned-mode",
+            "--prover.persist-jobs",
             "--prover.private_key",
             "0xmojave",
+            "--prover.trusted-keys",
+            "02aa,03bb",
             "--no-daemon",
         ])
         .unwrap();
@@ -187,7 +243,10 @@ mod tests {
                 assert_eq!(prover_options.prover_host, "127.0.0.1");
                 assert_eq!(prover_options.queue_capacity, 7);
                 assert!(prover_options.aligned_mode);
+                assert_eq!(prover_options.backend(), BackendKind::Aligned);
+                assert!(prover_options.persist_jobs);
                 assert_eq!(prover_options.private_key, "0xmojave");
+                assert_eq!(prover_options.trusted_keys, ["02aa", "03bb"]);
                 assert!(prover_options.no_daemon);
             }
             _ => panic!("expected start"),
@@ -200,8 +259,11 @@ mod tests {
             prover_port: 3900,
             prover_host: "0.0.0.0".into(),
             queue_capacity: 7,
+            backend: BackendKind::Exec,
             aligned_mode: false,
+            persist_jobs: false,
             private_key: "0xabc".into(),
+            trusted_keys: Vec::new(),
             no_daemon: true,
         };
         let dbg = format!("{opts:?}");
@@ -211,6 +273,35 @@ mod tests {
         assert!(!dbg.contains("0xabc"));
     }
 
+    #[test]
+    fn parse_backend() {
+        let cli = Cli::try_parse_from([
+            "mojave-prover",
+            "init",
+            "--prover.backend",
+            "mock",
+            "--prover.private_key",
+            "0xabc",
+        ])
+        .unwrap();
+        let Command::Start { prover_options } = cli.command else {
+            panic!("expected start");
+        };
+        assert_eq!(prover_options.backend(), BackendKind::Mock);
+
+        assert!(
+            Cli::try_parse_from([
+                "mojave-prover",
+                "init",
+                "--prover.backend",
+                "groth16",
+                "--prover.private_key",
+                "0xabc",
+            ])
+            .is_err()
+        );
+    }
+
     #[test]
     fn parse_stop() {
         let cli = Cli::try_parse_from(["mojave-prover", "stop"]).unwrap();
@@ -231,4 +322,19 @@ mod tests {
 
         assert!(cli.log_level.is_some());
     }
+
+    #[test]
+    fn parse_metrics_namespace() {
+        let cli = Cli::try_parse_from([
+            "mojave-prover",
+            "init",
+            "--prover.private_key",
+            "0xabc",
+            "--metrics.namespace",
+            "mojave_prover_a",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.metrics_namespace.as_deref(), Some("mojave_prover_a"));
+    }
 }
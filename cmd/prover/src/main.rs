@@ -3,16 +3,21 @@ pub mod cli;
 use crate::cli::Command;
 use anyhow::Result;
 use mojave_prover_lib::start_api;
-use mojave_utils::daemon::{DaemonOptions, run_daemonized, stop_daemonized};
+use mojave_utils::daemon::{
+    DEFAULT_MAX_LOG_BACKUPS, DEFAULT_MAX_LOG_BYTES, DaemonOptions, run_daemonized, stop_daemonized,
+};
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 
 const PID_FILE_NAME: &str = "prover.pid";
 const LOG_FILE_NAME: &str = "prover.log";
+const JOBS_DIR_NAME: &str = "jobs";
 
 fn main() -> Result<()> {
     let cli = cli::Cli::run();
 
     mojave_utils::logging::init(cli.log_level);
+    mojave_utils::metrics::init_namespace(cli.metrics_namespace.as_deref(), env!("CARGO_PKG_NAME"));
 
     match cli.command {
         Command::Start { prover_options } => {
@@ -21,18 +26,44 @@ fn main() -> Result<()> {
                 prover_options.prover_host, prover_options.prover_port
             );
 
+            if prover_options.aligned_mode {
+                tracing::warn!(
+                    "--prover.aligned-mode is deprecated, use --prover.backend aligned instead"
+                );
+            }
+            let backend = prover_options.backend();
+            let jobs_dir = prover_options
+                .persist_jobs
+                .then(|| PathBuf::from(&cli.datadir).join(JOBS_DIR_NAME));
+
             let daemon_opts = DaemonOptions {
                 no_daemon: prover_options.no_daemon,
                 pid_file_path: PathBuf::from(cli.datadir.clone()).join(PID_FILE_NAME),
                 log_file_path: PathBuf::from(cli.datadir).join(LOG_FILE_NAME),
+                max_log_bytes: Some(DEFAULT_MAX_LOG_BYTES),
+                max_log_backups: DEFAULT_MAX_LOG_BACKUPS,
             };
 
             run_daemonized(daemon_opts, || async move {
+                let shutdown = CancellationToken::new();
+                let signal_shutdown = shutdown.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = mojave_utils::signal::wait_for_shutdown_signal().await {
+                        tracing::error!(%error, "Failed to listen for shutdown signals");
+                        return;
+                    }
+                    tracing::info!("Shutting down the prover..");
+                    signal_shutdown.cancel();
+                });
+
                 start_api(
-                    prover_options.aligned_mode,
+                    backend,
                     &bind_addr,
                     &prover_options.private_key,
                     prover_options.queue_capacity,
+                    jobs_dir,
+                    &prover_options.trusted_keys,
+                    Some(shutdown),
                 )
                 .await
                 .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
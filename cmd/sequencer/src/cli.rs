@@ -1,13 +1,27 @@
 use std::path::PathBuf;
 
 use clap::{ArgAction, ArgGroup, Parser, Subcommand};
+use mojave_batch_producer::types::BatchProducerOptions;
 use mojave_block_producer::types::BlockProducerOptions;
 use mojave_node_lib::{
     initializers::get_signer,
-    types::{Node, SyncMode},
+    reorg_guard::DEFAULT_MAX_REORG_DEPTH,
+    types::{
+        DEFAULT_FILTER_CLEANUP_INTERVAL_SECS, DEFAULT_FILTER_TTL_SECS,
+        DEFAULT_PENDING_BLOCKS_LIMIT, DEFAULT_SYNC_PIPELINE_DEPTH, Node, PendingBlockEviction,
+        SyncMode,
+    },
+    utils::{remove_db, resolve_data_dir},
+};
+use mojave_proof_coordinator::{
+    list_dead_lettered,
+    types::{DEFAULT_MAX_PROOF_ATTEMPTS, ProofCoordinatorOptions},
+};
+use mojave_utils::{
+    daemon::stop_daemonized,
+    network::{GenesisOverrides, Network},
+    p2p::public_key_from_signing_key,
 };
-use mojave_proof_coordinator::types::ProofCoordinatorOptions;
-use mojave_utils::{daemon::stop_daemonized, network::Network, p2p::public_key_from_signing_key};
 use tracing::Level;
 
 use crate::PID_FILE_NAME;
@@ -30,7 +44,7 @@ pub struct Options {
         help = "If the datadir is the word `memory`, ethrex will use the InMemory Engine",
         default_value = ".mojave/sequencer",
         help = "Receives the name of the directory where the Database is located.",
-        long_help = "If the datadir is the word `memory`, ethrex will use the `InMemory Engine`.",
+        long_help = "If the datadir is the word `memory`, ethrex will use the `InMemory Engine`. Relative paths are resolved against the current working directory.",
         help_heading = "Node options",
         env = "ETHREX_DATADIR",
         global = true
@@ -69,6 +83,24 @@ pub struct Options {
     )]
     pub network: Network,
 
+    #[arg(
+        long = "dev.chain-id",
+        value_name = "CHAIN_ID",
+        help = "Override the chain id of the default network genesis.",
+        long_help = "Only applies when `--network` is `default`. Useful for spinning up isolated dev chains without writing a genesis file.",
+        help_heading = "Dev options"
+    )]
+    pub dev_chain_id: Option<u64>,
+
+    #[arg(
+        long = "dev.genesis-time",
+        value_name = "UNIX_TIMESTAMP",
+        help = "Override the timestamp of the default network genesis.",
+        long_help = "Only applies when `--network` is `default`.",
+        help_heading = "Dev options"
+    )]
+    pub dev_genesis_time: Option<u64>,
+
     #[arg(
         long = "bootnodes",
         value_parser = clap::value_parser!(Node),
@@ -102,7 +134,7 @@ pub struct Options {
     #[arg(
         long = "force",
         help = "Force remove the database",
-        long_help = "Delete the database without confirmation.",
+        long_help = "Delete the database without confirmation before starting. Refuses to delete directories that don't look like a mojave datadir.",
         action = clap::ArgAction::SetTrue,
         help_heading = "Node options"
     )]
@@ -133,6 +165,15 @@ pub struct Options {
     )]
     pub metrics_enabled: bool,
 
+    #[arg(
+        long = "metrics.namespace",
+        value_name = "NAMESPACE",
+        help = "Prefix for all emitted metric names.",
+        long_help = "Defaults to the binary name, e.g. `mojave_sequencer`, so processes sharing a host don't collide. Pass an empty string to disable the prefix.",
+        help_heading = "Node options"
+    )]
+    pub metrics_namespace: Option<String>,
+
     #[arg(
         long = "p2p.enabled",
         default_value = "true",
@@ -199,7 +240,13 @@ impl From<&Options> for mojave_node_lib::types::NodeOptions {
             discovery_addr: options.discovery_addr.clone(),
             discovery_port: options.discovery_port.clone(),
             network: options.network.clone(),
+            genesis_overrides: GenesisOverrides {
+                chain_id: options.dev_chain_id,
+                genesis_time: options.dev_genesis_time,
+            },
             bootnodes: options.bootnodes.clone(),
+            bootnodes_url: None,
+            strict_bootnodes: false,
             datadir: options.datadir.clone(),
             syncmode: options.syncmode.unwrap_or(SyncMode::Full),
             sponsorable_addresses_file_path: options.sponsorable_addresses_file_path.clone(),
@@ -209,6 +256,16 @@ impl From<&Options> for mojave_node_lib::types::NodeOptions {
             force: options.force,
             health_addr: options.health_addr.clone(),
             health_port: options.health_port.clone(),
+            sequencer_verifying_key: None,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+            sync_full_node_urls: Vec::new(),
+            sync_pipeline_depth: DEFAULT_SYNC_PIPELINE_DEPTH,
+            pending_blocks_limit: DEFAULT_PENDING_BLOCKS_LIMIT,
+            pending_blocks_eviction: PendingBlockEviction::default(),
+            rpc_read_only: false,
+            rpc_read_only_denied_methods: Vec::new(),
+            filter_ttl: DEFAULT_FILTER_TTL_SECS,
+            filter_cleanup_interval: DEFAULT_FILTER_CLEANUP_INTERVAL_SECS,
         }
     }
 }
@@ -220,7 +277,8 @@ impl From<&Options> for mojave_node_lib::types::NodeOptions {
     author,
     version,
     about = "Mojave is a blockchain node implementation for the Mojave network",
-    arg_required_else_help = true
+    arg_required_else_help = true,
+    subcommand_negates_reqs = true
 )]
 pub struct Cli {
     #[command(flatten)]
@@ -244,6 +302,20 @@ pub enum Command {
     Stop,
     #[command(name = "get-pub-key", about = "Display the public key of the node")]
     GetPubKey,
+    #[command(name = "remove-db", about = "Remove the database in the datadir")]
+    RemoveDb {
+        #[arg(
+            long = "force",
+            help = "Skip the confirmation prompt",
+            action = clap::ArgAction::SetTrue
+        )]
+        force: bool,
+    },
+    #[command(
+        name = "dead-letters",
+        about = "List the batches moved to the dead-letter queue after failing to prove"
+    )]
+    DeadLetters,
 }
 
 impl Command {
@@ -257,6 +329,22 @@ impl Command {
                 println!("{public_key}");
                 Ok(())
             }
+            Command::DeadLetters => {
+                let (data_dir, _) = resolve_data_dir(&datadir).await?;
+                for batch in list_dead_lettered(&data_dir).map_err(anyhow::Error::from)? {
+                    println!(
+                        "batch {} dead-lettered at {} after {} attempts: {}",
+                        batch.batch_number, batch.dead_lettered_at, batch.attempts, batch.reason
+                    );
+                }
+                Ok(())
+            }
+            Command::RemoveDb { force } => {
+                let (data_dir, _) = resolve_data_dir(&datadir).await?;
+                remove_db(&data_dir, force)
+                    .await
+                    .map_err(anyhow::Error::from)
+            }
         }
     }
 }
@@ -271,6 +359,15 @@ pub struct SequencerOptions {
         default_value = "http://0.0.0.0:3900"
     )]
     pub prover_address: String,
+    #[arg(
+        long = "prover.max-attempts",
+        help = "Failed proofs tolerated per batch before it is moved to the dead-letter queue",
+        long_help = "Dead-lettered batches are recorded in dead_letter_batches.json in the datadir and skipped, so one unprovable batch doesn't stall the pipeline.",
+        help_heading = "Prover Options",
+        default_value_t = DEFAULT_MAX_PROOF_ATTEMPTS,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_proof_attempts: u32,
     #[arg(
         long = "block_time",
         help = "Block creation interval in milliseconds",
@@ -280,16 +377,24 @@ pub struct SequencerOptions {
     #[arg(
         long = "private_key",
         help = "Private key used for signing blocks",
-        env = "PRIVATE_KEY",
-        default_value = "0xabc"
+        env = "PRIVATE_KEY"
     )]
     pub private_key: String,
+    #[arg(
+        long = "max_blocks_per_batch",
+        help = "Maximum number of blocks sealed into a single batch",
+        long_help = "Caps batch size independently of blob space, keeping proving time predictable after a backlog. Unlimited when unset.",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub max_blocks_per_batch: Option<u64>,
 }
 
 impl std::fmt::Debug for SequencerOptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SequencerOptions")
+            .field("max_proof_attempts", &self.max_proof_attempts)
             .field("block_time", &self.block_time)
+            .field("max_blocks_per_batch", &self.max_blocks_per_batch)
             .finish()
     }
 }
@@ -303,10 +408,19 @@ impl From<&SequencerOptions> for BlockProducerOptions {
     }
 }
 
+impl From<&SequencerOptions> for BatchProducerOptions {
+    fn from(value: &SequencerOptions) -> Self {
+        Self {
+            max_blocks_per_batch: value.max_blocks_per_batch,
+        }
+    }
+}
+
 impl From<&SequencerOptions> for ProofCoordinatorOptions {
     fn from(value: &SequencerOptions) -> Self {
         Self {
             prover_address: value.prover_address.clone(),
+            max_proof_attempts: value.max_proof_attempts,
         }
     }
 }
@@ -513,17 +627,24 @@ mod tests {
         assert_eq!(bp.block_time, sequencer_options.block_time);
         assert_eq!(bp.private_key, sequencer_options.private_key);
 
+        // SequencerOptions -> BatchProducerOptions
+        let batch: BatchProducerOptions = (&sequencer_options).into();
+        assert_eq!(batch.max_blocks_per_batch, None);
+
         // SequencerOptions -> ProofCoordinatorOptions
         let pc: ProofCoordinatorOptions = (&sequencer_options).into();
         assert_eq!(pc.prover_address, sequencer_options.prover_address);
+        assert_eq!(pc.max_proof_attempts, DEFAULT_MAX_PROOF_ATTEMPTS);
     }
 
     #[test]
     fn sequencer_options_debug_does_not_leak_private_key() {
         let opts = SequencerOptions {
             prover_address: "http://0.0.0.0:3900".into(),
+            max_proof_attempts: DEFAULT_MAX_PROOF_ATTEMPTS,
             block_time: 1000,
             private_key: "0xsecret".into(),
+            max_blocks_per_batch: None,
         };
         let dbg = format!("{opts:?}");
 
@@ -532,6 +653,32 @@ mod tests {
         assert!(!dbg.contains("0xsecret"));
     }
 
+    #[test]
+    fn parse_max_blocks_per_batch() {
+        let parse = |max_blocks: &str| {
+            Cli::try_parse_from([
+                "mojave-sequencer",
+                "--private_key",
+                "0xabc",
+                "--max_blocks_per_batch",
+                max_blocks,
+            ])
+        };
+        let cli = parse("32").unwrap();
+        let batch: BatchProducerOptions = (&cli.sequencer_options).into();
+        assert_eq!(batch.max_blocks_per_batch, Some(32));
+
+        assert!(parse("0").is_err());
+    }
+
+    #[test]
+    fn private_key_is_required_to_start() {
+        let err = Cli::try_parse_from(["mojave-sequencer", "--block_time", "500"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::MissingRequiredArgument);
+    }
+
     #[test]
     fn parse_stop_and_get_pub_key() {
         let cli = Cli::try_parse_from(["mojave-sequencer", "stop"]).unwrap();
@@ -539,11 +686,26 @@ mod tests {
 
         let cli = Cli::try_parse_from(["mojave-sequencer", "get-pub-key"]).unwrap();
         assert!(matches!(cli.command, Some(Command::GetPubKey)));
+
+        let cli = Cli::try_parse_from(["mojave-sequencer", "dead-letters"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::DeadLetters)));
+
+        let cli = Cli::try_parse_from(["mojave-sequencer", "remove-db", "--force"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::RemoveDb { force: true })
+        ));
     }
 
     #[test]
     fn invalid_bootnodes_string_rejected() {
-        let res = Cli::try_parse_from(["mojave-sequencer", "--bootnodes", "not-enode-url"]);
+        let res = Cli::try_parse_from([
+            "mojave-sequencer",
+            "--private_key",
+            "0xabc",
+            "--bootnodes",
+            "not-enode-url",
+        ]);
         assert!(res.is_err());
     }
 
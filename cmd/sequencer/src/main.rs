@@ -2,11 +2,14 @@ pub mod cli;
 
 use anyhow::{Context, Result};
 
+use mojave_batch_producer::types::BatchProducerOptions;
 use mojave_block_producer::types::BlockProducerOptions;
 use mojave_coordination::sequencer::run_sequencer;
 use mojave_node_lib::types::MojaveNode;
 use mojave_proof_coordinator::types::ProofCoordinatorOptions;
-use mojave_utils::daemon::{DaemonOptions, run_daemonized};
+use mojave_utils::daemon::{
+    DEFAULT_MAX_LOG_BACKUPS, DEFAULT_MAX_LOG_BYTES, DaemonOptions, run_daemonized,
+};
 use std::path::PathBuf;
 use tracing::{error, info};
 
@@ -21,6 +24,10 @@ fn main() -> Result<()> {
     } = cli::Cli::run();
 
     mojave_utils::logging::init(options.log_level);
+    mojave_utils::metrics::init_namespace(
+        options.metrics_namespace.as_deref(),
+        env!("CARGO_PKG_NAME"),
+    );
 
     let rt = build_runtime()?;
 
@@ -38,6 +45,7 @@ fn main() -> Result<()> {
     info!("Starting Sequencer...");
 
     let block_producer_options: BlockProducerOptions = (&sequencer_options).into();
+    let batch_producer_options: BatchProducerOptions = (&sequencer_options).into();
     let proof_coordinator_options: ProofCoordinatorOptions = (&sequencer_options).into();
     let daemon_opts = build_daemon_options(&options.datadir, options.no_daemon);
 
@@ -51,6 +59,7 @@ fn main() -> Result<()> {
             node,
             &node_options,
             &block_producer_options,
+            &batch_producer_options,
             &proof_coordinator_options,
         )
         .await
@@ -89,6 +98,8 @@ fn build_daemon_options(datadir: &str, no_daemon: bool) -> DaemonOptions {
         no_daemon,
         pid_file_path: PathBuf::from(datadir).join(PID_FILE_NAME),
         log_file_path: PathBuf::from(datadir).join(LOG_FILE_NAME),
+        max_log_bytes: Some(DEFAULT_MAX_LOG_BYTES),
+        max_log_backups: DEFAULT_MAX_LOG_BACKUPS,
     }
 }
 
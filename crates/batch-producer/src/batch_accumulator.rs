@@ -4,7 +4,13 @@ use ethrex_common::{
     Address, H256,
     types::{AccountUpdate, PrivilegedL2Transaction},
 };
-use ethrex_l2_common::l1_messages::{L1Message, get_l1_message_hash};
+use ethrex_l2_common::{
+    l1_messages::{L1Message, get_l1_message_hash},
+    state_diff::{BLOCK_HEADER_LEN, L1MESSAGE_LOG_LEN, PRIVILEGED_TX_LOG_LEN},
+};
+
+/// State diff version byte, last block header and the three list lengths.
+const STATE_DIFF_BASE_LEN: u64 = 1 + BLOCK_HEADER_LEN + 2 + 2 + 2;
 
 #[derive(Default)]
 pub(crate) struct BatchAccumulator {
@@ -46,4 +52,150 @@ impl BatchAccumulator {
     pub(crate) fn get_account_updates_vec(&self) -> Vec<AccountUpdate> {
         self.account_updates.values().cloned().collect()
     }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+            && self.privileged_txs.is_empty()
+            && self.account_updates.is_empty()
+    }
+
+    pub(crate) fn message_count(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub(crate) fn privileged_tx_count(&self) -> usize {
+        self.privileged_txs.len()
+    }
+
+    pub(crate) fn account_update_count(&self) -> usize {
+        self.account_updates.len()
+    }
+
+    /// Upper-bound estimate of the encoded state diff for everything gathered so
+    /// far. Cheap enough to call after every block; the blob bundle generated
+    /// from the real state diff remains the authoritative size check.
+    pub(crate) fn estimated_state_diff_size(&self) -> u64 {
+        let messages = self.messages.len() as u64 * L1MESSAGE_LOG_LEN;
+        let privileged_txs = self.privileged_txs.len() as u64 * PRIVILEGED_TX_LOG_LEN;
+        let account_updates: u64 = self
+            .account_updates
+            .values()
+            .map(estimated_account_diff_size)
+            .sum();
+
+        STATE_DIFF_BASE_LEN + messages + privileged_txs + account_updates
+    }
+}
+
+/// Diff type and address, plus balance and nonce diff, storage slots and
+/// bytecode when present.
+fn estimated_account_diff_size(update: &AccountUpdate) -> u64 {
+    let mut size = 1 + 20;
+    if update.info.is_some() {
+        size += 32 + 2;
+    }
+    if !update.added_storage.is_empty() {
+        size += 2 + 64 * update.added_storage.len() as u64;
+    }
+    if let Some(code) = &update.code {
+        size += 2 + code.len() as u64;
+    }
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::{U256, types::AccountInfo};
+
+    fn message(id: u64) -> L1Message {
+        L1Message {
+            from: Address::repeat_byte(1),
+            data_hash: H256::repeat_byte(2),
+            message_id: U256::from(id),
+        }
+    }
+
+    fn account_update(address: Address, slots: u64) -> AccountUpdate {
+        let mut update = AccountUpdate::new(address);
+        update.info = Some(AccountInfo::default());
+        update.added_storage = (0..slots)
+            .map(|slot| (H256::from_low_u64_be(slot), U256::one()))
+            .collect();
+        update
+    }
+
+    #[test]
+    fn is_empty_until_block_data_is_added() {
+        let mut batch = BatchAccumulator::default();
+        assert!(batch.is_empty());
+
+        batch.add_block_data(Vec::new(), Vec::new(), Vec::new());
+        assert!(batch.is_empty());
+
+        batch.add_block_data(
+            Vec::new(),
+            Vec::new(),
+            vec![account_update(Address::repeat_byte(1), 0)],
+        );
+        assert!(!batch.is_empty());
+
+        let mut batch = BatchAccumulator::default();
+        batch.add_block_data(vec![message(1)], Vec::new(), Vec::new());
+        assert!(!batch.is_empty());
+
+        let mut batch = BatchAccumulator::default();
+        batch.add_block_data(
+            Vec::new(),
+            vec![PrivilegedL2Transaction::default()],
+            Vec::new(),
+        );
+        assert!(!batch.is_empty());
+    }
+
+    #[test]
+    fn counts_accumulate_across_blocks_and_merge_accounts() {
+        let mut batch = BatchAccumulator::default();
+        let address = Address::repeat_byte(1);
+
+        batch.add_block_data(
+            vec![message(1)],
+            vec![PrivilegedL2Transaction::default()],
+            vec![account_update(address, 1)],
+        );
+        batch.add_block_data(
+            vec![message(2), message(3)],
+            Vec::new(),
+            vec![
+                account_update(address, 2),
+                account_update(Address::repeat_byte(2), 0),
+            ],
+        );
+
+        assert_eq!(batch.message_count(), 3);
+        assert_eq!(batch.message_hashes.len(), 3);
+        assert_eq!(batch.privileged_tx_count(), 1);
+        // Updates to the same account are merged into one.
+        assert_eq!(batch.account_update_count(), 2);
+        assert_eq!(batch.account_updates[&address].added_storage.len(), 2);
+    }
+
+    #[test]
+    fn estimated_state_diff_size_grows_with_the_batch() {
+        let mut batch = BatchAccumulator::default();
+        assert_eq!(batch.estimated_state_diff_size(), STATE_DIFF_BASE_LEN);
+
+        batch.add_block_data(
+            vec![message(1)],
+            vec![PrivilegedL2Transaction::default()],
+            vec![account_update(Address::repeat_byte(1), 2)],
+        );
+
+        // Type and address, balance and nonce, then two storage slots.
+        let account = 1 + 20 + (32 + 2) + (2 + 2 * 64);
+        assert_eq!(
+            batch.estimated_state_diff_size(),
+            STATE_DIFF_BASE_LEN + L1MESSAGE_LOG_LEN + PRIVILEGED_TX_LOG_LEN + account
+        );
+    }
 }
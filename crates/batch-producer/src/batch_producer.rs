@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::{
     batch_accumulator::BatchAccumulator,
     error::{Error, Result},
-    types::{BatchData, BlockData, Request},
+    types::{BatchData, BatchProducerOptions, BlockData, Request},
     utils::{
         generate_blobs_bundle, get_block_l1_messages, get_privileged_transactions,
         prepare_state_diff,
@@ -27,17 +27,60 @@ use mojave_task::Task;
 use tracing::{debug, info, warn};
 
 const MAX_BATCH_TO_BROADCAST: usize = 16;
+/// Maximum number of privileged (L1 to L2) transactions in a batch.
+///
+/// Privileged transactions are not fetched yet (`get_privileged_transactions`
+/// returns none), so batches don't reach the budget for now.
+const PRIVILEGED_TX_BUDGET: usize = 300;
+
+/// Where a block goes given the privileged transaction budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrivilegedTxBudget {
+    /// The block fits in the batch.
+    Fits,
+    /// The block goes to the next batch.
+    Exceeded,
+    /// The block is over the budget on its own. It can't be split, so it
+    /// makes up a batch by itself.
+    Oversized,
+}
+
+/// Checks the `block_txs` of the next block against the budget, with
+/// `batched` privileged transactions already in the batch.
+fn check_privileged_tx_budget(
+    batched: usize,
+    block_txs: &[PrivilegedL2Transaction],
+    budget: usize,
+) -> PrivilegedTxBudget {
+    if block_txs.len() > budget {
+        if batched == 0 {
+            PrivilegedTxBudget::Oversized
+        } else {
+            PrivilegedTxBudget::Exceeded
+        }
+    } else if batched + block_txs.len() > budget {
+        PrivilegedTxBudget::Exceeded
+    } else {
+        PrivilegedTxBudget::Fits
+    }
+}
+
+/// Whether a batch already holding `batched_blocks` blocks is full. The first
+/// block always fits, so a batch is never sealed empty.
+fn block_limit_reached(batched_blocks: u64, max_blocks_per_batch: Option<u64>) -> bool {
+    max_blocks_per_batch.is_some_and(|max_blocks| batched_blocks >= max_blocks.max(1))
+}
 
 #[derive(Clone)]
 pub struct BatchProducer {
-    // TODO: replace that with a real batch counter (getting the batch counter from the context/l1)
-    // dummy batch counter for the moment
+    /// Number of the last batch sealed in the rollup store.
     batch_counter: u64,
 
     store: Store,
     blockchain: Arc<Blockchain>,
     rollup_store: StoreRollup,
     broadcast: tokio::sync::broadcast::Sender<Batch>,
+    max_blocks_per_batch: Option<u64>,
 }
 
 impl Task for BatchProducer {
@@ -73,32 +116,90 @@ impl Task for BatchProducer {
 }
 
 impl BatchProducer {
-    pub fn new(node: MojaveNode, batch_counter: u64) -> Self {
+    pub fn new(node: MojaveNode, batch_counter: u64, options: &BatchProducerOptions) -> Self {
+        Self::with_stores(
+            node.store,
+            node.blockchain,
+            node.rollup_store,
+            batch_counter,
+            options,
+        )
+    }
+
+    fn with_stores(
+        store: Store,
+        blockchain: Arc<Blockchain>,
+        rollup_store: StoreRollup,
+        batch_counter: u64,
+        options: &BatchProducerOptions,
+    ) -> Self {
         let (broadcast, _) = tokio::sync::broadcast::channel(MAX_BATCH_TO_BROADCAST);
 
         BatchProducer {
             batch_counter,
-            store: node.store.clone(),
-            blockchain: node.blockchain.clone(),
-            rollup_store: node.rollup_store.clone(),
+            store,
+            blockchain,
+            rollup_store,
             broadcast,
+            max_blocks_per_batch: options.max_blocks_per_batch,
         }
     }
 
+    /// Creates a producer that continues after the last batch sealed in the
+    /// node's rollup store.
+    pub async fn from_store(node: MojaveNode, options: &BatchProducerOptions) -> Result<Self> {
+        Self::resume(node.store, node.blockchain, node.rollup_store, options).await
+    }
+
+    async fn resume(
+        store: Store,
+        blockchain: Arc<Blockchain>,
+        rollup_store: StoreRollup,
+        options: &BatchProducerOptions,
+    ) -> Result<Self> {
+        let batch_counter = rollup_store.get_batch_number().await?.unwrap_or(0);
+        info!(
+            last_sealed_batch = batch_counter,
+            "Resuming batch production"
+        );
+        Ok(Self::with_stores(
+            store,
+            blockchain,
+            rollup_store,
+            batch_counter,
+            options,
+        ))
+    }
+
+    /// Number of the batch the next [`Self::build_batch`] call seals.
+    fn next_batch_number(&self) -> u64 {
+        self.batch_counter + 1
+    }
+
     pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<Batch> {
         self.broadcast.subscribe()
     }
 
     pub async fn build_batch(&mut self) -> Result<Option<Batch>> {
-        let batch_number = self.batch_counter + 1;
+        // Batches may have been sealed by another producer instance since the
+        // last run, never rebuild them.
+        if let Some(last_sealed) = self.rollup_store.get_batch_number().await?
+            && last_sealed > self.batch_counter
+        {
+            warn!(
+                batch_counter = self.batch_counter,
+                last_sealed, "Batch counter is behind the rollup store, catching up"
+            );
+            self.batch_counter = last_sealed;
+        }
+
+        let batch_number = self.next_batch_number();
 
         debug!(
             last_commited_batch_number = self.batch_counter,
             batch_number, "Building batch"
         );
 
-        // TODO: add a check if we already have the batch in the rollup_store ?
-
         let last_block = self.get_last_committed_block(self.batch_counter).await?;
         let first_block = last_block + 1;
         let batch_data = self
@@ -232,6 +333,14 @@ impl BatchProducer {
         loop {
             let block_number = current_block;
 
+            if block_limit_reached(block_number - first_block, self.max_blocks_per_batch) {
+                info!(
+                    max_blocks = self.max_blocks_per_batch,
+                    "Batch block limit reached. Any remaining blocks will be processed in the next batch."
+                );
+                break;
+            }
+
             // get body and header of current block we wish to add to the batch
             let Some(block_data) = self.get_block_data(block_number).await? else {
                 debug!("No more blocks available for batch");
@@ -243,17 +352,40 @@ impl BatchProducer {
             let (messages, privileged_txs, account_updates) =
                 self.process_block(&block_data).await?;
 
-            accumulator.add_block_data(messages, privileged_txs, account_updates);
+            let budget = check_privileged_tx_budget(
+                accumulator.privileged_tx_count(),
+                &privileged_txs,
+                PRIVILEGED_TX_BUDGET,
+            );
+            match budget {
+                PrivilegedTxBudget::Fits => {}
+                PrivilegedTxBudget::Exceeded => {
+                    warn!(
+                        privileged_tx_count =
+                            accumulator.privileged_tx_count() + privileged_txs.len(),
+                        budget = PRIVILEGED_TX_BUDGET,
+                        "Privileged transactions budget exceeded. Any remaining blocks will be processed in the next batch."
+                    );
+                    // Break loop before adding the block. Use the previous generated blobs_bundle.
+                    break;
+                }
+                PrivilegedTxBudget::Oversized => warn!(
+                    block_number,
+                    privileged_tx_count = privileged_txs.len(),
+                    budget = PRIVILEGED_TX_BUDGET,
+                    "Block is over the privileged transactions budget; sealing it in a batch of its own"
+                ),
+            }
 
-            // TODO: this is taken from ethrex let check if we need this
-            // let acc_privileged_txs_len: u64 = acc_privileged_txs.len().try_into()?;
-            // if acc_privileged_txs_len > PRIVILEGED_TX_BUDGET {
-            //     warn!(
-            //         "Privileged transactions budget exceeded. Any remaining blocks will be processed in the next batch."
-            //     );
-            //     // Break loop. Use the previous generated blobs_bundle.
-            //     break;
-            // }
+            accumulator.add_block_data(messages, privileged_txs, account_updates);
+            debug!(
+                block_number,
+                messages = accumulator.message_count(),
+                privileged_txs = accumulator.privileged_tx_count(),
+                account_updates = accumulator.account_update_count(),
+                estimated_size = accumulator.estimated_state_diff_size(),
+                "Accumulated block into batch"
+            );
 
             let state_diff = prepare_state_diff(
                 block_data.header,
@@ -280,16 +412,22 @@ impl BatchProducer {
             blobs_bundle = bundle;
             state_root = self.get_block_state_root(&block_data.block)?;
             current_block = block_number + 1;
+
+            if budget == PrivilegedTxBudget::Oversized {
+                break;
+            }
         }
 
         if current_block == last_committed_block {
             return Ok(None);
         }
 
-        info!(
-            privileged_tx_count = accumulator.privileged_tx_hashes.len(),
-            "Added privileged transactions to batch"
-        );
+        if !accumulator.is_empty() {
+            info!(
+                privileged_tx_count = accumulator.privileged_tx_count(),
+                "Added privileged transactions to batch"
+            );
+        }
 
         Ok(Some(BatchData {
             last_block: current_block,
@@ -344,3 +482,106 @@ impl BatchProducer {
         Ok(*last_committed_block)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_storage::EngineType;
+    use ethrex_storage_rollup::EngineTypeRollup;
+
+    fn txs(count: usize) -> Vec<PrivilegedL2Transaction> {
+        vec![PrivilegedL2Transaction::default(); count]
+    }
+
+    #[test]
+    fn privileged_txs_within_the_budget_fit() {
+        assert_eq!(
+            check_privileged_tx_budget(0, &txs(3), 5),
+            PrivilegedTxBudget::Fits
+        );
+        assert_eq!(
+            check_privileged_tx_budget(2, &txs(3), 5),
+            PrivilegedTxBudget::Fits
+        );
+    }
+
+    #[test]
+    fn block_over_the_remaining_budget_goes_to_the_next_batch() {
+        assert_eq!(
+            check_privileged_tx_budget(3, &txs(3), 5),
+            PrivilegedTxBudget::Exceeded
+        );
+    }
+
+    #[test]
+    fn block_over_the_whole_budget_is_batched_alone() {
+        assert_eq!(
+            check_privileged_tx_budget(0, &txs(6), 5),
+            PrivilegedTxBudget::Oversized
+        );
+        // It waits for a batch of its own.
+        assert_eq!(
+            check_privileged_tx_budget(1, &txs(6), 5),
+            PrivilegedTxBudget::Exceeded
+        );
+    }
+
+    #[test]
+    fn batch_is_full_at_max_blocks() {
+        assert!(!block_limit_reached(0, Some(2)));
+        assert!(!block_limit_reached(1, Some(2)));
+        assert!(block_limit_reached(2, Some(2)));
+    }
+
+    #[test]
+    fn batch_without_block_limit_is_never_full() {
+        assert!(!block_limit_reached(0, None));
+        assert!(!block_limit_reached(u64::MAX, None));
+    }
+
+    #[test]
+    fn batch_always_takes_its_first_block() {
+        assert!(!block_limit_reached(0, Some(0)));
+        assert!(block_limit_reached(1, Some(0)));
+    }
+
+    #[tokio::test]
+    async fn from_store_resumes_after_the_last_sealed_batch() {
+        let store = Store::new("", EngineType::InMemory).unwrap();
+        let blockchain = Arc::new(Blockchain::default_with_store(store.clone()));
+        let rollup_store = StoreRollup::new(".", EngineTypeRollup::InMemory).unwrap();
+        rollup_store.init().await.unwrap();
+        let options = BatchProducerOptions::default();
+
+        let producer = BatchProducer::resume(
+            store.clone(),
+            blockchain.clone(),
+            rollup_store.clone(),
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(producer.next_batch_number(), 1);
+
+        let batch = producer
+            .create_batch(
+                producer.next_batch_number(),
+                1,
+                BatchData {
+                    last_block: 3,
+                    state_root: H256::repeat_byte(1),
+                    message_hashes: vec![],
+                    privileged_tx_hashes: vec![],
+                    blobs_bundle: BlobsBundle::default(),
+                },
+            )
+            .unwrap();
+        rollup_store.seal_batch(batch).await.unwrap();
+
+        let producer = BatchProducer::resume(store, blockchain, rollup_store, &options)
+            .await
+            .unwrap();
+        assert_eq!(producer.next_batch_number(), 2);
+        assert_eq!(producer.get_last_committed_block(1).await.unwrap(), 3);
+    }
+}
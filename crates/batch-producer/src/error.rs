@@ -1,5 +1,8 @@
 use ethrex_blockchain::error::ChainError;
-use ethrex_common::types::{BlobsBundleError, batch::Batch};
+use ethrex_common::{
+    Address,
+    types::{BlobsBundleError, batch::Batch},
+};
 use ethrex_l2_common::{
     privileged_transactions::PrivilegedTransactionError, state_diff::StateDiffError,
 };
@@ -32,6 +35,8 @@ pub enum Error {
     FailedToGetInformationFromStorage(String),
     #[error("Failed to generate blobs bundle: {0}")]
     FailedToGenerateBlobsBundle(#[from] BlobsBundleError),
+    #[error("Nonce of account {0:#x} decreased within the batch")]
+    NonceDecreased(Address),
     #[error("Unreachable code reached: {0}")]
     Unreachable(String),
     #[error("Privileged Transaction error: {0}")]
@@ -3,6 +3,12 @@ use ethrex_common::{
     types::{BlobsBundle, Block, BlockHeader, BlockNumber},
 };
 
+#[derive(Debug, Clone, Default)]
+pub struct BatchProducerOptions {
+    /// Seal a batch once it holds this many blocks, even if blob space is left.
+    pub max_blocks_per_batch: Option<u64>,
+}
+
 pub enum Request {
     BuildBatch,
 }
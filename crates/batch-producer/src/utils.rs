@@ -1,20 +1,81 @@
-use ethrex_common::types::{
-    AccountUpdate, BlobsBundle, BlockHeader, PrivilegedL2Transaction, blobs_bundle,
+use std::collections::BTreeMap;
+
+use ethrex_common::{
+    Address,
+    types::{
+        AccountUpdate, BlobsBundle, BlockHeader, PrivilegedL2Transaction, TxKind, blobs_bundle,
+    },
+};
+use ethrex_l2_common::{
+    l1_messages::L1Message,
+    state_diff::{AccountStateDiff, PrivilegedTransactionLog, StateDiff},
 };
-use ethrex_l2_common::{l1_messages::L1Message, state_diff::StateDiff};
 use ethrex_vm::VmDatabase;
 
 use crate::error::{Error, Result};
 
-/// Prepare the state diff for the block.
+/// Prepare the state diff for the batch ending with `last_header`.
+///
+/// `db` holds the state before the batch and is used to turn the new nonces
+/// of `account_updates` into nonce diffs.
 pub(crate) fn prepare_state_diff(
-    _last_header: BlockHeader,
-    _db: &impl VmDatabase,
-    _l1messages: &[L1Message],
-    _privileged_transactions: &[PrivilegedL2Transaction],
-    _account_updates: Vec<AccountUpdate>,
+    last_header: BlockHeader,
+    db: &impl VmDatabase,
+    l1messages: &[L1Message],
+    privileged_transactions: &[PrivilegedL2Transaction],
+    account_updates: Vec<AccountUpdate>,
 ) -> Result<StateDiff> {
-    Ok(StateDiff::default())
+    let mut modified_accounts = BTreeMap::new();
+    for account_update in account_updates {
+        let nonce_diff = get_nonce_diff(&account_update, db)?;
+        modified_accounts.insert(
+            account_update.address,
+            AccountStateDiff {
+                new_balance: account_update.info.as_ref().map(|info| info.balance),
+                nonce_diff,
+                storage: account_update.added_storage.into_iter().collect(),
+                bytecode: account_update.code,
+                bytecode_hash: None,
+            },
+        );
+    }
+
+    let privileged_transactions = privileged_transactions
+        .iter()
+        .map(|tx| PrivilegedTransactionLog {
+            address: match tx.to {
+                TxKind::Call(address) => address,
+                TxKind::Create => Address::zero(),
+            },
+            amount: tx.value,
+            nonce: tx.nonce,
+        })
+        .collect();
+
+    Ok(StateDiff {
+        version: StateDiff::default().version,
+        last_header,
+        modified_accounts,
+        l1_messages: l1messages.to_vec(),
+        privileged_transactions,
+    })
+}
+
+/// Number of nonces the account used in the batch.
+fn get_nonce_diff(account_update: &AccountUpdate, db: &impl VmDatabase) -> Result<u16> {
+    let prev_nonce = db
+        .get_account_info(account_update.address)?
+        .map(|info| info.nonce)
+        .unwrap_or_default();
+    let new_nonce = account_update
+        .info
+        .as_ref()
+        .map_or(prev_nonce, |info| info.nonce);
+
+    let nonce_diff = new_nonce
+        .checked_sub(prev_nonce)
+        .ok_or(Error::NonceDecreased(account_update.address))?;
+    Ok(nonce_diff.try_into()?)
 }
 
 pub(crate) fn get_privileged_transactions() -> Vec<PrivilegedL2Transaction> {
@@ -34,3 +95,56 @@ pub(crate) fn generate_blobs_bundle(state_diff: &StateDiff) -> Result<(BlobsBund
         blob_size,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_blockchain::vm::StoreVmDatabase;
+    use ethrex_common::{
+        H256, U256,
+        types::{AccountInfo, Genesis},
+    };
+    use ethrex_storage::{EngineType, Store};
+
+    const TEST_GENESIS: &str = include_str!("../../../tests/mock-genesis.json");
+
+    #[tokio::test]
+    async fn state_diff_round_trips_through_the_blob() {
+        let store = Store::new("", EngineType::InMemory).unwrap();
+        let genesis: Genesis = serde_json::from_str(TEST_GENESIS).unwrap();
+        store.add_initial_state(genesis.clone()).await.unwrap();
+        let header = genesis.get_block().header;
+        let db = StoreVmDatabase::new(store, header.hash());
+
+        let address = Address::repeat_byte(0x42);
+        let mut account_update = AccountUpdate::new(address);
+        account_update.info = Some(AccountInfo {
+            balance: U256::from(1_000),
+            nonce: 2,
+            ..Default::default()
+        });
+        account_update
+            .added_storage
+            .insert(H256::repeat_byte(1), U256::from(7));
+
+        let state_diff = prepare_state_diff(header, &db, &[], &[], vec![account_update]).unwrap();
+        let (bundle, blob_size) = generate_blobs_bundle(&state_diff).unwrap();
+
+        // Every 32-byte field element of the blob carries 31 bytes of data.
+        let blob_data: Vec<u8> = bundle.blobs[0]
+            .chunks(32)
+            .flat_map(|element| element[1..].to_vec())
+            .take(blob_size)
+            .collect();
+        let decoded = StateDiff::decode(&blob_data).unwrap();
+
+        let account = &decoded.modified_accounts[&address];
+        assert_eq!(account.nonce_diff, 2);
+        assert_eq!(account.new_balance, Some(U256::from(1_000)));
+        assert_eq!(
+            account.storage.get(&H256::repeat_byte(1)),
+            Some(&U256::from(7))
+        );
+        assert_eq!(decoded.encode().unwrap(), state_diff.encode().unwrap());
+    }
+}
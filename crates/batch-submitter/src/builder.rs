@@ -14,27 +14,68 @@ use bitcoin::{
 use bitcoincore_rpc::{Client as BitcoinRPCClient, RpcApi, json::FundRawTransactionOptions};
 use rand::{RngCore, rngs::OsRng};
 use secp256k1::SECP256K1;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 use crate::error::{Error, Result};
 
 const MAX_PUSH_SIZE: usize = 520;
+const BITCOIN_DUST_LIMIT: Amount = Amount::from_sat(546);
+/// Position of the change output requested from `fundrawtransaction`.
+const CHANGE_OUTPUT_INDEX: usize = 1;
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// `RPC_INVALID_ADDRESS_OR_KEY`, returned by Bitcoin Core for unknown txs.
+const RPC_NOT_FOUND: i32 = -5;
+
+/// What to do with commit tx change that falls below the dust limit.
+///
+/// `fundrawtransaction` doesn't create a change output for such amounts and
+/// leaves them to the miners as fee.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DustChangePolicy {
+    /// Let the excess go to the miners as fee.
+    #[default]
+    Burn,
+    /// Carry the excess through the reveal tx into the recipient output.
+    AddToRecipient,
+}
 
 pub struct BuilderContext {
     pub rpc_client: BitcoinRPCClient,
+    /// Fee rate of the inscription txs. With `fee_target_blocks` set, it is
+    /// only used when the node has no estimate.
     pub fee_rate: FeeRate,
+    /// Estimate the fee rate for confirmation within this many blocks.
+    pub fee_target_blocks: Option<u16>,
     pub operator_l1_addr: Address,
     pub network: Network,
     pub amount: Amount,
+    pub dust_change_policy: DustChangePolicy,
 }
 
-pub fn create_inscription_tx(
-    ctx: &BuilderContext,
-    payloads: &[Vec<u8>],
-) -> Result<(Transaction, Transaction)> {
+/// A commit/reveal pair, with what is needed to sign the reveal tx again when
+/// the commit tx is replaced.
+#[derive(Debug, Clone)]
+pub struct Inscription {
+    pub commit_tx: Transaction,
+    pub reveal_tx: Transaction,
+    reveal_script: ScriptBuf,
+    control_block: ControlBlock,
+    key_pair: UntweakedKeypair,
+}
+
+pub fn create_inscription_tx(ctx: &BuilderContext, payloads: &[Vec<u8>]) -> Result<Inscription> {
     // step 1: generate keypair
     let key_pair = generate_key_pair()?;
     let public_key = XOnlyPublicKey::from_keypair(&key_pair).0;
 
+    let fee_rate = match ctx.fee_target_blocks {
+        Some(target_blocks) => estimate_fee_rate(&ctx.rpc_client, target_blocks, ctx.fee_rate)?,
+        None => ctx.fee_rate,
+    };
+
     // step 2: create reveal script
     let reveal_script = build_reveal_script(&public_key, payloads)?;
     let reveal_leaf = (reveal_script, LeafVersion::TapScript);
@@ -61,7 +102,7 @@ pub fn create_inscription_tx(
     // Calculate commit value
     let commit_value = calculate_reveal_input_value(
         ctx.amount,
-        ctx.fee_rate,
+        fee_rate,
         &ctx.operator_l1_addr,
         &reveal_leaf.0,
         &control_block,
@@ -71,7 +112,7 @@ pub fn create_inscription_tx(
     let unfunded_commit_tx = build_unfunded_commit_tx(&reveal_address, commit_value)?;
 
     // Fund the commit tx. Additional UTxOs might be added to the output set
-    let unsigned_commit_tx = fund_tx(ctx, &unfunded_commit_tx)?;
+    let unsigned_commit_tx = fund_tx(ctx, &unfunded_commit_tx, fee_rate)?;
 
     let outpoints: Vec<OutPoint> = unsigned_commit_tx
         .input
@@ -86,10 +127,18 @@ pub fn create_inscription_tx(
             return Err(Error::Internal("Unexpected error".to_string()));
         }
 
+        let (unsigned_commit_tx, swept) = match ctx.dust_change_policy {
+            DustChangePolicy::Burn => (unsigned_commit_tx, Amount::ZERO),
+            DustChangePolicy::AddToRecipient => {
+                sweep_dust_change(ctx, unsigned_commit_tx, fee_rate)?
+            }
+        };
+        let reveal_amount = ctx.amount.checked_add(swept).ok_or(Error::FeeOverflow)?;
+
         // step 4: build and sign the reveal tx
         let signed_reveal_tx = build_and_sign_reveal_tx(
-            ctx.amount,
-            &ctx.operator_l1_addr,
+            reveal_amount,
+            ctx.operator_l1_addr.script_pubkey(),
             &unsigned_commit_tx,
             &reveal_leaf.0,
             &control_block,
@@ -102,7 +151,13 @@ pub fn create_inscription_tx(
             .sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?
             .transaction()?;
 
-        Ok((signed_commit_tx, signed_reveal_tx))
+        Ok(Inscription {
+            commit_tx: signed_commit_tx,
+            reveal_tx: signed_reveal_tx,
+            reveal_script: reveal_leaf.0.clone(),
+            control_block: control_block.clone(),
+            key_pair,
+        })
     })();
 
     // If the closure returned an error, unlock the UTxOs before returning.
@@ -115,6 +170,354 @@ pub fn create_inscription_tx(
     result
 }
 
+/// Broadcasts a commit/reveal pair created by [`create_inscription_tx`].
+///
+/// The commit inputs are checked first so a conflicting mempool spend is
+/// reported as [`Error::InputConflict`] instead of an opaque rejection. On
+/// conflict the caller should unlock the inputs and build a new pair. If the
+/// reveal tx is rejected after the commit tx went out, the commit output stays
+/// unspent and [`Error::RevealRejected`] carries its txid so the caller can
+/// recover it.
+pub fn broadcast_inscription_tx(
+    ctx: &BuilderContext,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+) -> Result<(Txid, Txid)> {
+    broadcast_pair(&ctx.rpc_client, commit_tx, reveal_tx)
+}
+
+fn broadcast_pair(
+    rpc: &impl RpcApi,
+    commit_tx: &Transaction,
+    reveal_tx: &Transaction,
+) -> Result<(Txid, Txid)> {
+    check_inputs_spendable(rpc, commit_tx)?;
+
+    let commit_txid = rpc.send_raw_transaction(commit_tx)?;
+    let reveal_txid = rpc
+        .send_raw_transaction(reveal_tx)
+        .map_err(|e| Error::RevealRejected {
+            commit_txid,
+            reason: e.to_string(),
+        })?;
+    Ok((commit_txid, reveal_txid))
+}
+
+/// Broadcasts a commit/reveal pair and waits until the reveal tx has
+/// `min_confirmations` confirmations. Returns the reveal txid.
+///
+/// Broadcast errors are those of [`broadcast_inscription_tx`]. Waiting stops
+/// with an error after `timeout`, when `cancel` is cancelled, or when the
+/// reveal tx disappears from the mempool without being mined.
+///
+/// Confirmations are read from the reveal output with `gettxout`, which needs
+/// no `-txindex`.
+pub async fn broadcast_and_confirm(
+    ctx: &BuilderContext,
+    commit: &Transaction,
+    reveal: &Transaction,
+    min_confirmations: u32,
+    timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Txid> {
+    broadcast_and_confirm_with(
+        &ctx.rpc_client,
+        commit,
+        reveal,
+        min_confirmations,
+        CONFIRMATION_POLL_INTERVAL,
+        timeout,
+        cancel,
+    )
+    .await
+}
+
+/// Subset of the `gettxout` and verbose `getrawtransaction` results needed to
+/// track confirmations.
+#[derive(Deserialize)]
+struct TxConfirmations {
+    /// Missing from `getrawtransaction` while the tx is in the mempool.
+    confirmations: Option<u64>,
+}
+
+async fn broadcast_and_confirm_with(
+    rpc: &impl RpcApi,
+    commit: &Transaction,
+    reveal: &Transaction,
+    min_confirmations: u32,
+    poll_interval: Duration,
+    timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Txid> {
+    let (commit_txid, reveal_txid) = broadcast_pair(rpc, commit, reveal)?;
+    tracing::info!(%commit_txid, %reveal_txid, "Broadcast inscription txs");
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let confirmations =
+            tx_confirmations(rpc, &reveal_txid)?.ok_or(Error::RevealDropped(reveal_txid))?;
+        if confirmations >= u64::from(min_confirmations) {
+            return Ok(reveal_txid);
+        }
+        if Instant::now() >= deadline {
+            return Err(Error::ConfirmationTimeout {
+                txid: reveal_txid,
+                confirmations,
+            });
+        }
+        tracing::debug!(
+            %reveal_txid,
+            confirmations,
+            min_confirmations,
+            "Waiting for reveal tx confirmations"
+        );
+        tokio::select! {
+            _ = cancel.cancelled() => return Err(Error::ConfirmationCancelled(reveal_txid)),
+            _ = tokio::time::sleep_until(deadline.min(Instant::now() + poll_interval)) => {}
+        }
+    }
+}
+
+/// Confirmations of `txid`, or `None` when the node has it neither in the
+/// mempool nor in the chain.
+///
+/// The tx is found through its unspent first output, in the mempool or in the
+/// chain. Only once that output is spent does this fall back to
+/// `getrawtransaction`, which finds mined txs only with `-txindex`.
+fn tx_confirmations(rpc: &impl RpcApi, txid: &Txid) -> Result<Option<u64>> {
+    let unspent = rpc.call::<Option<TxConfirmations>>(
+        "gettxout",
+        &[txid.to_string().into(), 0.into(), true.into()],
+    )?;
+    if let Some(tx_out) = unspent {
+        return Ok(Some(tx_out.confirmations.unwrap_or(0)));
+    }
+    match rpc.call::<TxConfirmations>("getrawtransaction", &[txid.to_string().into(), true.into()])
+    {
+        Ok(tx) => Ok(Some(tx.confirmations.unwrap_or(0))),
+        Err(e) if is_not_found(&e) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn is_not_found(error: &bitcoincore_rpc::Error) -> bool {
+    matches!(
+        error,
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(e))
+            if e.code == RPC_NOT_FOUND
+    )
+}
+
+/// Verifies that every input of `tx` is unspent, including by unconfirmed
+/// mempool transactions.
+pub fn ensure_inputs_spendable(ctx: &BuilderContext, tx: &Transaction) -> Result<()> {
+    check_inputs_spendable(&ctx.rpc_client, tx)
+}
+
+fn check_inputs_spendable(rpc: &impl RpcApi, tx: &Transaction) -> Result<()> {
+    for tx_in in &tx.input {
+        let outpoint = tx_in.previous_output;
+        if rpc
+            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+            .is_none()
+        {
+            return Err(Error::InputConflict(outpoint));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the stuck commit tx of `inscription` by one paying
+/// `new_fee_rate` (sat/vB), signed with the wallet, and signs a reveal tx
+/// spending the replacement. The replacement spends the same inputs.
+///
+/// The current fee comes from the wallet's `gettransaction`, so the funding
+/// txs don't need to be looked up, and the bump satisfies the node's
+/// incremental relay fee. The broadcast reveal tx is evicted along with the
+/// commit tx, so the replacement also has to pay for its fee.
+pub fn bump_commit_fee(
+    ctx: &BuilderContext,
+    inscription: &Inscription,
+    new_fee_rate: u64,
+) -> Result<Inscription> {
+    let commit_txid = inscription.commit_tx.compute_txid();
+    let current_fee = ctx
+        .rpc_client
+        .get_transaction(&commit_txid, None)?
+        .fee
+        .ok_or(Error::Internal(format!(
+            "Commit tx {commit_txid} was not sent by the wallet"
+        )))?
+        .unsigned_abs();
+    let output_value = inscription
+        .commit_tx
+        .output
+        .iter()
+        .try_fold(Amount::ZERO, |acc, out| acc.checked_add(out.value))
+        .ok_or(Error::FeeOverflow)?;
+    let input_value = output_value
+        .checked_add(current_fee)
+        .ok_or(Error::FeeOverflow)?;
+    let reveal_fee = reveal_fee(inscription)?;
+    let incremental_relay_fee =
+        fee_rate_per_kvb(ctx.rpc_client.get_network_info()?.incremental_fee);
+
+    let unsigned_tx = bump_fee(
+        &inscription.commit_tx,
+        input_value,
+        reveal_fee,
+        new_fee_rate,
+        incremental_relay_fee,
+    )?;
+    let commit_tx = ctx
+        .rpc_client
+        .sign_raw_transaction_with_wallet(&unsigned_tx, None, None)?
+        .transaction()?;
+    inscription.with_commit_tx(commit_tx)
+}
+
+/// Fee paid by the reveal tx of `inscription`, the commit output it spends
+/// minus the output it keeps.
+fn reveal_fee(inscription: &Inscription) -> Result<Amount> {
+    let spent = inscription
+        .commit_tx
+        .output
+        .first()
+        .ok_or(Error::Internal("Commit tx has no output".to_string()))?
+        .value;
+    let kept = inscription
+        .reveal_tx
+        .output
+        .first()
+        .ok_or(Error::Internal("Reveal tx has no output".to_string()))?
+        .value;
+    spent.checked_sub(kept).ok_or(Error::Internal(
+        "Reveal output exceeds the commit output".to_string(),
+    ))
+}
+
+impl Inscription {
+    /// Pairs `commit_tx`, a replacement of the commit tx, with a reveal tx
+    /// spending it. The reveal output is kept.
+    fn with_commit_tx(&self, commit_tx: Transaction) -> Result<Self> {
+        let output = self
+            .reveal_tx
+            .output
+            .first()
+            .ok_or(Error::Internal("Reveal tx has no output".to_string()))?;
+        let reveal_tx = build_and_sign_reveal_tx(
+            output.value,
+            output.script_pubkey.clone(),
+            &commit_tx,
+            &self.reveal_script,
+            &self.control_block,
+            &self.key_pair,
+        )?;
+        Ok(Self {
+            commit_tx,
+            reveal_tx,
+            ..self.clone()
+        })
+    }
+}
+
+/// Returns a replacement for the signed `tx` paying `new_fee_rate` (sat/vB),
+/// taking the additional fee from the change output. `input_value` is the sum
+/// of the values spent by `tx`, and `evicted_fee` the fee of its descendants
+/// the replacement evicts from the mempool. As required by BIP125, the fee has
+/// to cover both and grow by at least `incremental_relay_fee` for the size of
+/// the replacement.
+///
+/// Inputs are kept so the result is a valid RBF replacement; their witnesses
+/// are cleared and the result has to be signed again.
+pub fn bump_fee(
+    tx: &Transaction,
+    input_value: Amount,
+    evicted_fee: Amount,
+    new_fee_rate: u64,
+    incremental_relay_fee: FeeRate,
+) -> Result<Transaction> {
+    let fee_rate = FeeRate::from_sat_per_vb(new_fee_rate).ok_or(Error::FeeOverflow)?;
+    let output_value = tx
+        .output
+        .iter()
+        .try_fold(Amount::ZERO, |acc, out| acc.checked_add(out.value))
+        .ok_or(Error::FeeOverflow)?;
+    let current_fee = input_value
+        .checked_sub(output_value)
+        .ok_or(Error::Internal(
+            "Outputs exceed the input value".to_string(),
+        ))?;
+    // Signatures have the same size after re-signing, so the signed size holds.
+    let new_fee = fee_rate
+        .fee_vb(tx.vsize() as u64)
+        .ok_or(Error::FeeOverflow)?;
+    let min_bump = incremental_relay_fee
+        .fee_vb(tx.vsize() as u64)
+        .ok_or(Error::FeeOverflow)?;
+    let min_fee = current_fee
+        .checked_add(evicted_fee)
+        .and_then(|fee| fee.checked_add(min_bump))
+        .ok_or(Error::FeeOverflow)?;
+    if new_fee < min_fee {
+        return Err(Error::Internal(format!(
+            "Fee rate of {new_fee_rate} sat/vB pays {new_fee}, below the current fee of \
+             {current_fee} plus the evicted fee of {evicted_fee} and the incremental relay \
+             fee of {min_bump}"
+        )));
+    }
+
+    let change = tx.output.get(CHANGE_OUTPUT_INDEX).ok_or(Error::Internal(
+        "Transaction has no change output".to_string(),
+    ))?;
+    let bump = new_fee - current_fee;
+    let new_change = change.value.checked_sub(bump).unwrap_or(Amount::ZERO);
+    if new_change < BITCOIN_DUST_LIMIT {
+        return Err(Error::ChangeBelowDust(new_change));
+    }
+
+    let mut replacement = tx.clone();
+    replacement.output[CHANGE_OUTPUT_INDEX].value = new_change;
+    for tx_in in &mut replacement.input {
+        tx_in.witness = Witness::new();
+        tx_in.script_sig = ScriptBuf::new();
+    }
+    Ok(replacement)
+}
+
+/// Fee rate for confirmation within `target_blocks` according to the node's
+/// `estimatesmartfee`, or `floor` when the node has no estimate (e.g. on
+/// regtest or right after startup). Never below the node's minimum relay fee.
+pub fn estimate_fee_rate(
+    client: &impl RpcApi,
+    target_blocks: u16,
+    floor: FeeRate,
+) -> Result<FeeRate> {
+    let estimate = client.estimate_smart_fee(target_blocks, None)?;
+    let fee_rate = match estimate.fee_rate {
+        Some(per_kvb) => fee_rate_per_kvb(per_kvb),
+        None => {
+            tracing::debug!(
+                target_blocks,
+                errors = ?estimate.errors,
+                %floor,
+                "No fee estimate available, using the floor"
+            );
+            floor
+        }
+    };
+
+    let min_relay_fee = fee_rate_per_kvb(client.get_network_info()?.relay_fee);
+    Ok(fee_rate.max(min_relay_fee))
+}
+
+/// Converts a fee per kvB, as Bitcoin Core reports it, rounding up so the
+/// result never pays less than the node asked for.
+fn fee_rate_per_kvb(per_kvb: Amount) -> FeeRate {
+    // 1 kvB is 4 kwu.
+    FeeRate::from_sat_per_kwu(per_kvb.to_sat().div_ceil(4))
+}
+
 /// Encode tx in non-segwit format.
 /// This is needed for fundrawtransaction RPC call, which expects a non-segwit tx
 fn encode_tx_non_segwit(tx: &Transaction) -> Result<Vec<u8>> {
@@ -127,15 +530,15 @@ fn encode_tx_non_segwit(tx: &Transaction) -> Result<Vec<u8>> {
     Ok(encoder)
 }
 
-fn fund_tx(ctx: &BuilderContext, tx: &Transaction) -> Result<Transaction> {
+fn fund_tx(ctx: &BuilderContext, tx: &Transaction, fee_rate: FeeRate) -> Result<Transaction> {
     let tx_raw = encode_tx_non_segwit(tx)?;
     let funded_tx = ctx
         .rpc_client
         .fund_raw_transaction(
             &tx_raw,
             Some(&FundRawTransactionOptions {
-                fee_rate: ctx.fee_rate.fee_vb(1000), // convert to sat/kvB
-                change_position: Some(1),
+                fee_rate: fee_rate.fee_vb(1000), // convert to sat/kvB
+                change_position: Some(CHANGE_OUTPUT_INDEX as u32),
                 lock_unspents: Some(true),
                 ..Default::default()
             }),
@@ -146,6 +549,66 @@ fn fund_tx(ctx: &BuilderContext, tx: &Transaction) -> Result<Transaction> {
     Ok(funded_tx)
 }
 
+/// Moves sub-dust excess of a funded commit tx without change output into the
+/// commitment output. Returns the updated tx and the swept amount.
+fn sweep_dust_change(
+    ctx: &BuilderContext,
+    mut tx: Transaction,
+    fee_rate: FeeRate,
+) -> Result<(Transaction, Amount)> {
+    // A change output was created, nothing is lost to fees.
+    if tx.output.len() > 1 {
+        return Ok((tx, Amount::ZERO));
+    }
+
+    let mut input_value = Amount::ZERO;
+    for tx_in in &tx.input {
+        let prev_out = ctx
+            .rpc_client
+            .get_tx_out(
+                &tx_in.previous_output.txid,
+                tx_in.previous_output.vout,
+                Some(true),
+            )?
+            .ok_or(Error::Internal(format!(
+                "Funding input {} is not spendable",
+                tx_in.previous_output
+            )))?;
+        input_value = input_value
+            .checked_add(prev_out.value)
+            .ok_or(Error::FeeOverflow)?;
+    }
+    let output_value = tx
+        .output
+        .iter()
+        .try_fold(Amount::ZERO, |acc, out| acc.checked_add(out.value))
+        .ok_or(Error::FeeOverflow)?;
+
+    // Sign a copy to learn the final size; output values don't affect it.
+    let signed_tx = ctx
+        .rpc_client
+        .sign_raw_transaction_with_wallet(&tx, None, None)?
+        .transaction()?;
+    let required_fee = fee_rate
+        .fee_vb(signed_tx.vsize() as u64)
+        .ok_or(Error::FeeOverflow)?;
+
+    let excess = input_value
+        .checked_sub(output_value)
+        .and_then(|fee| fee.checked_sub(required_fee))
+        .unwrap_or(Amount::ZERO);
+    if excess == Amount::ZERO || excess >= BITCOIN_DUST_LIMIT {
+        return Ok((tx, Amount::ZERO));
+    }
+
+    tracing::debug!(%excess, "Sweeping dust change into the reveal output");
+    tx.output[0].value = tx.output[0]
+        .value
+        .checked_add(excess)
+        .ok_or(Error::FeeOverflow)?;
+    Ok((tx, excess))
+}
+
 fn generate_key_pair() -> Result<UntweakedKeypair> {
     let mut rand_bytes = [0; 32];
     OsRng.fill_bytes(&mut rand_bytes);
@@ -176,6 +639,73 @@ fn build_reveal_script(public_key: &XOnlyPublicKey, payloads: &[Vec<u8>]) -> Res
     Ok(script_builder.into_script())
 }
 
+/// Extracts the single payload inscribed by a reveal tx.
+pub fn parse_reveal_payload(tx: &Transaction) -> Result<Vec<u8>> {
+    let mut payloads = parse_reveal_payloads(tx)?;
+    if payloads.len() != 1 {
+        return Err(Error::Internal(format!(
+            "Expected one inscription envelope, found {}",
+            payloads.len()
+        )));
+    }
+    Ok(payloads.remove(0))
+}
+
+/// Extracts the payloads inscribed by a reveal tx; the inverse of
+/// `build_reveal_script`. Chunks pushed within one `OP_FALSE OP_IF ... OP_ENDIF`
+/// envelope are concatenated back into the original payload.
+pub fn parse_reveal_payloads(tx: &Transaction) -> Result<Vec<Vec<u8>>> {
+    let reveal_script = tx
+        .input
+        .first()
+        .and_then(|tx_in| tx_in.witness.tapscript())
+        .ok_or(Error::Internal(
+            "Reveal input has no tapscript witness".to_string(),
+        ))?;
+
+    let malformed = |what: &str| Error::Internal(format!("Malformed reveal script: {what}"));
+    let mut instructions = reveal_script.instructions();
+
+    // <public key> OP_CHECKSIG
+    match instructions.next().transpose()? {
+        Some(script::Instruction::PushBytes(key)) if key.len() == 32 => {}
+        _ => return Err(malformed("missing public key")),
+    }
+    if instructions.next().transpose()?
+        != Some(script::Instruction::Op(bitcoin::opcodes::all::OP_CHECKSIG))
+    {
+        return Err(malformed("missing OP_CHECKSIG"));
+    }
+
+    let mut payloads = Vec::new();
+    while let Some(instruction) = instructions.next().transpose()? {
+        // OP_FALSE is an empty push.
+        match instruction {
+            script::Instruction::PushBytes(bytes) if bytes.is_empty() => {}
+            _ => return Err(malformed("expected OP_FALSE")),
+        }
+        if instructions.next().transpose()?
+            != Some(script::Instruction::Op(bitcoin::opcodes::all::OP_IF))
+        {
+            return Err(malformed("expected OP_IF"));
+        }
+
+        let mut payload = Vec::new();
+        loop {
+            match instructions.next().transpose()? {
+                Some(script::Instruction::PushBytes(chunk)) => {
+                    payload.extend_from_slice(chunk.as_bytes())
+                }
+                Some(script::Instruction::Op(bitcoin::opcodes::all::OP_ENDIF)) => break,
+                _ => return Err(malformed("unterminated envelope")),
+            }
+        }
+        payloads.push(payload);
+    }
+
+    Ok(payloads)
+}
+
 // Estimate the required input value for reveal_tx
 fn calculate_reveal_input_value(
     amount: Amount,
@@ -208,8 +738,8 @@ fn calculate_reveal_input_value(
 
     let fee = fee_rate
         .fee_vb(tx.vsize() as u64)
-        .ok_or(Error::Internal("Overflow error".to_string()))?;
-    Ok(fee + amount)
+        .ok_or(Error::FeeOverflow)?;
+    fee.checked_add(amount).ok_or(Error::FeeOverflow)
 }
 
 fn build_unfunded_commit_tx(recipient: &Address, output_value: Amount) -> Result<Transaction> {
@@ -231,7 +761,7 @@ fn build_unfunded_commit_tx(recipient: &Address, output_value: Amount) -> Result
 
 fn build_and_sign_reveal_tx(
     amount: Amount,
-    recipient: &Address,
+    recipient: ScriptBuf,
     unsigned_commit_tx: &Transaction,
     reveal_script: &ScriptBuf,
     control_block: &ControlBlock,
@@ -239,7 +769,7 @@ fn build_and_sign_reveal_tx(
 ) -> Result<Transaction> {
     let outputs: Vec<TxOut> = vec![TxOut {
         value: amount,
-        script_pubkey: recipient.script_pubkey(),
+        script_pubkey: recipient,
     }];
 
     let commit_txid = unsigned_commit_tx.compute_txid();
@@ -416,4 +946,537 @@ mod tests {
 
         assert_eq!(calculated_value, Amount::from_sat(112));
     }
+
+    #[test]
+    fn test_calculate_reveal_input_value_overflow() {
+        let recipient = get_testnet_address();
+
+        let public_key = get_public_key();
+        let reveal_script = ScriptBuf::from_hex("00630b48656c6c6f20576f726c6468").unwrap();
+
+        let taproot_spend_info = TaprootBuilder::new()
+            .add_leaf(0, reveal_script.clone())
+            .unwrap()
+            .finalize(SECP256K1, public_key)
+            .unwrap();
+        let control_block = taproot_spend_info
+            .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+            .unwrap();
+
+        // fee_rate * vsize overflows
+        let result = calculate_reveal_input_value(
+            Amount::from_sat(0),
+            FeeRate::from_sat_per_kwu(u64::MAX),
+            &recipient,
+            &reveal_script,
+            &control_block,
+        );
+        assert!(matches!(result, Err(Error::FeeOverflow)));
+
+        // fee + amount overflows
+        let result = calculate_reveal_input_value(
+            Amount::MAX,
+            FeeRate::from_sat_per_vb(1).unwrap(),
+            &recipient,
+            &reveal_script,
+            &control_block,
+        );
+        assert!(matches!(result, Err(Error::FeeOverflow)));
+    }
+
+    /// Signed-looking commit tx spending one P2WPKH input, with commitment and
+    /// change outputs.
+    fn commit_tx_with_change(change: Amount) -> Transaction {
+        let recipient = get_testnet_address();
+        let mut witness = Witness::new();
+        witness.push([0u8; 72]);
+        witness.push([0u8; 33]);
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), 3),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness,
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(10_000),
+                    script_pubkey: recipient.script_pubkey(),
+                },
+                TxOut {
+                    value: change,
+                    script_pubkey: recipient.script_pubkey(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bump_fee_shrinks_change() {
+        let tx = commit_tx_with_change(Amount::from_sat(5_000));
+        // Pays a fee of 1_000 sat.
+        let input_value = Amount::from_sat(16_000);
+        let new_fee = Amount::from_sat(20 * tx.vsize() as u64);
+
+        let bumped = bump_fee(&tx, input_value, Amount::ZERO, 20, FeeRate::ZERO).unwrap();
+
+        assert_eq!(bumped.input.len(), 1);
+        assert_eq!(bumped.input[0].previous_output, tx.input[0].previous_output);
+        assert_eq!(bumped.input[0].sequence, Sequence::ENABLE_RBF_NO_LOCKTIME);
+        assert!(bumped.input[0].witness.is_empty());
+        assert_eq!(bumped.output[0], tx.output[0]);
+        assert_eq!(
+            bumped.output[1].value,
+            Amount::from_sat(5_000) - (new_fee - Amount::from_sat(1_000))
+        );
+    }
+
+    #[test]
+    fn test_bump_fee_rejects_dust_change() {
+        let tx = commit_tx_with_change(Amount::from_sat(1_000));
+        let input_value = Amount::from_sat(11_100);
+
+        let result = bump_fee(&tx, input_value, Amount::ZERO, 10, FeeRate::ZERO);
+
+        assert!(matches!(result, Err(Error::ChangeBelowDust(_))));
+    }
+
+    #[test]
+    fn test_bump_fee_requires_higher_fee() {
+        let tx = commit_tx_with_change(Amount::from_sat(5_000));
+        let input_value = Amount::from_sat(16_000);
+
+        let result = bump_fee(&tx, input_value, Amount::ZERO, 1, FeeRate::ZERO);
+
+        assert!(matches!(result, Err(Error::Internal(_))));
+    }
+
+    #[test]
+    fn test_bump_fee_requires_incremental_relay_fee() {
+        let tx = commit_tx_with_change(Amount::from_sat(5_000));
+        let input_value = Amount::from_sat(16_000);
+        let incremental_relay_fee = FeeRate::from_sat_per_vb_unchecked(20);
+
+        // Raises the fee, but by less than 20 sat/vB.
+        let result = bump_fee(&tx, input_value, Amount::ZERO, 20, incremental_relay_fee);
+        assert!(matches!(result, Err(Error::Internal(_))));
+
+        assert!(bump_fee(&tx, input_value, Amount::ZERO, 40, incremental_relay_fee).is_ok());
+    }
+
+    #[test]
+    fn test_bump_fee_pays_for_the_evicted_reveal() {
+        let tx = commit_tx_with_change(Amount::from_sat(5_000));
+        let input_value = Amount::from_sat(16_000);
+        let reveal_fee = Amount::from_sat(20 * tx.vsize() as u64);
+
+        // Enough to replace the commit tx alone, but not the reveal tx with it.
+        assert!(bump_fee(&tx, input_value, Amount::ZERO, 20, FeeRate::ZERO).is_ok());
+        let result = bump_fee(&tx, input_value, reveal_fee, 20, FeeRate::ZERO);
+        assert!(matches!(result, Err(Error::Internal(_))));
+    }
+
+    #[test]
+    fn test_reveal_fee_is_the_spent_commit_output_minus_the_kept_output() {
+        // The commit output holds 20_000 sat and the reveal keeps 10_000.
+        assert_eq!(
+            reveal_fee(&inscription(b"batch")).unwrap(),
+            Amount::from_sat(10_000)
+        );
+    }
+
+    fn inscription(payload: &[u8]) -> Inscription {
+        let key_pair = generate_key_pair().unwrap();
+        let public_key = XOnlyPublicKey::from_keypair(&key_pair).0;
+        let reveal_script = build_reveal_script(&public_key, &[payload.to_vec()]).unwrap();
+        let spend_info = TaprootBuilder::new()
+            .add_leaf(0, reveal_script.clone())
+            .unwrap()
+            .finalize(SECP256K1, public_key)
+            .unwrap();
+        let control_block = spend_info
+            .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+            .unwrap();
+        let reveal_address = Address::p2tr(
+            SECP256K1,
+            public_key,
+            spend_info.merkle_root(),
+            Network::Regtest,
+        );
+        let commit_tx =
+            build_unfunded_commit_tx(&reveal_address, Amount::from_sat(20_000)).unwrap();
+        let reveal_tx = build_and_sign_reveal_tx(
+            Amount::from_sat(10_000),
+            get_testnet_address().script_pubkey(),
+            &commit_tx,
+            &reveal_script,
+            &control_block,
+            &key_pair,
+        )
+        .unwrap();
+        Inscription {
+            commit_tx,
+            reveal_tx,
+            reveal_script,
+            control_block,
+            key_pair,
+        }
+    }
+
+    #[test]
+    fn test_replaced_commit_tx_gets_a_new_reveal_tx() {
+        let inscription = inscription(b"batch");
+        let mut commit_tx = inscription.commit_tx.clone();
+        commit_tx.input.push(TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array([7; 32]), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        });
+
+        let replaced = inscription.with_commit_tx(commit_tx.clone()).unwrap();
+
+        assert_eq!(replaced.commit_tx, commit_tx);
+        assert_eq!(
+            replaced.reveal_tx.input[0].previous_output,
+            OutPoint::new(commit_tx.compute_txid(), 0)
+        );
+        assert_ne!(
+            replaced.reveal_tx.input[0].witness,
+            inscription.reveal_tx.input[0].witness
+        );
+        assert_eq!(replaced.reveal_tx.output, inscription.reveal_tx.output);
+        assert_eq!(parse_reveal_payload(&replaced.reveal_tx).unwrap(), b"batch");
+    }
+
+    /// `RpcApi` answering each command with queued responses.
+    #[derive(Default)]
+    struct MockRpc {
+        responses: std::sync::Mutex<
+            std::collections::HashMap<
+                &'static str,
+                std::collections::VecDeque<bitcoincore_rpc::Result<serde_json::Value>>,
+            >,
+        >,
+        calls: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockRpc {
+        fn respond(self, cmd: &'static str, response: serde_json::Value) -> Self {
+            self.push(cmd, Ok(response))
+        }
+
+        fn fail(self, cmd: &'static str, message: &str) -> Self {
+            self.push(
+                cmd,
+                Err(bitcoincore_rpc::Error::ReturnedError(message.to_string())),
+            )
+        }
+
+        fn push(
+            self,
+            cmd: &'static str,
+            response: bitcoincore_rpc::Result<serde_json::Value>,
+        ) -> Self {
+            self.responses
+                .lock()
+                .unwrap()
+                .entry(cmd)
+                .or_default()
+                .push_back(response);
+            self
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl RpcApi for MockRpc {
+        fn call<T: for<'a> serde::de::Deserialize<'a>>(
+            &self,
+            cmd: &str,
+            _args: &[serde_json::Value],
+        ) -> bitcoincore_rpc::Result<T> {
+            self.calls.lock().unwrap().push(cmd.to_string());
+            let response = self
+                .responses
+                .lock()
+                .unwrap()
+                .get_mut(cmd)
+                .and_then(|queue| queue.pop_front())
+                .unwrap_or_else(|| panic!("unexpected RPC call {cmd}"))?;
+            Ok(serde_json::from_value(response)?)
+        }
+    }
+
+    fn empty_tx() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        }
+    }
+
+    fn not_found() -> bitcoincore_rpc::Error {
+        bitcoincore_rpc::Error::JsonRpc(bitcoincore_rpc::jsonrpc::Error::Rpc(
+            bitcoincore_rpc::jsonrpc::error::RpcError {
+                code: RPC_NOT_FOUND,
+                message: "No such mempool or blockchain transaction".to_string(),
+                data: None,
+            },
+        ))
+    }
+
+    fn broadcast_rpc() -> MockRpc {
+        MockRpc::default()
+            .respond(
+                "sendrawtransaction",
+                Txid::from_byte_array([1; 32]).to_string().into(),
+            )
+            .respond(
+                "sendrawtransaction",
+                Txid::from_byte_array([2; 32]).to_string().into(),
+            )
+    }
+
+    async fn confirm(rpc: &MockRpc, min_confirmations: u32, timeout: Duration) -> Result<Txid> {
+        broadcast_and_confirm_with(
+            rpc,
+            &empty_tx(),
+            &empty_tx(),
+            min_confirmations,
+            Duration::ZERO,
+            timeout,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_waits_for_confirmations() {
+        let rpc = broadcast_rpc()
+            .respond("gettxout", serde_json::json!({ "confirmations": 0 }))
+            .respond("gettxout", serde_json::json!({ "confirmations": 1 }))
+            .respond("gettxout", serde_json::json!({ "confirmations": 2 }));
+
+        let txid = confirm(&rpc, 2, Duration::from_secs(60)).await.unwrap();
+
+        assert_eq!(txid, Txid::from_byte_array([2; 32]));
+        assert_eq!(
+            rpc.calls(),
+            [
+                "sendrawtransaction",
+                "sendrawtransaction",
+                "gettxout",
+                "gettxout",
+                "gettxout"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_follows_a_spent_reveal_output() {
+        let rpc = broadcast_rpc()
+            .respond("gettxout", serde_json::Value::Null)
+            .respond(
+                "getrawtransaction",
+                serde_json::json!({ "confirmations": 3 }),
+            );
+
+        let txid = confirm(&rpc, 2, Duration::from_secs(60)).await.unwrap();
+
+        assert_eq!(txid, Txid::from_byte_array([2; 32]));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_reports_rejected_reveal() {
+        let commit_txid = Txid::from_byte_array([1; 32]);
+        let rpc = MockRpc::default()
+            .respond("sendrawtransaction", commit_txid.to_string().into())
+            .fail("sendrawtransaction", "non-mandatory-script-verify-flag");
+
+        let result = confirm(&rpc, 1, Duration::from_secs(60)).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::RevealRejected { commit_txid: txid, .. }) if txid == commit_txid
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_reports_dropped_reveal() {
+        let rpc = broadcast_rpc()
+            .respond("gettxout", serde_json::Value::Null)
+            .push("getrawtransaction", Err(not_found()));
+
+        let result = confirm(&rpc, 1, Duration::from_secs(60)).await;
+
+        assert!(
+            matches!(result, Err(Error::RevealDropped(txid)) if txid == Txid::from_byte_array([2; 32]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_times_out() {
+        let rpc = broadcast_rpc().respond("gettxout", serde_json::json!({ "confirmations": 0 }));
+
+        let result = confirm(&rpc, 1, Duration::ZERO).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ConfirmationTimeout {
+                confirmations: 0,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_and_confirm_stops_on_cancel() {
+        let rpc = broadcast_rpc().respond("gettxout", serde_json::json!({ "confirmations": 0 }));
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = broadcast_and_confirm_with(
+            &rpc,
+            &empty_tx(),
+            &empty_tx(),
+            1,
+            Duration::from_secs(30),
+            Duration::from_secs(60),
+            &cancel,
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::ConfirmationCancelled(_))));
+    }
+
+    #[test]
+    fn test_estimate_fee_rate_converts_btc_per_kvb() {
+        // 0.0002 BTC/kvB = 20 sat/vB
+        let rpc = MockRpc::default().respond(
+            "estimatesmartfee",
+            serde_json::json!({ "feerate": 0.0002, "blocks": 6 }),
+        );
+
+        let rpc = rpc.respond("getnetworkinfo", network_info(0.00001));
+
+        let fee_rate = estimate_fee_rate(&rpc, 6, FeeRate::from_sat_per_vb(1).unwrap()).unwrap();
+
+        assert_eq!(fee_rate, FeeRate::from_sat_per_vb(20).unwrap());
+    }
+
+    #[test]
+    fn test_estimate_fee_rate_rounds_up() {
+        // 1001 sat/kvB is 250.25 sat/kwu.
+        let rpc = MockRpc::default()
+            .respond(
+                "estimatesmartfee",
+                serde_json::json!({ "feerate": 0.00001001, "blocks": 6 }),
+            )
+            .respond("getnetworkinfo", network_info(0.00001));
+
+        let fee_rate = estimate_fee_rate(&rpc, 6, FeeRate::from_sat_per_vb(1).unwrap()).unwrap();
+
+        assert_eq!(fee_rate, FeeRate::from_sat_per_kwu(251));
+    }
+
+    #[test]
+    fn test_estimate_fee_rate_is_clamped_to_min_relay_fee() {
+        // The estimate is 2 sat/vB, the node relays nothing below 5 sat/vB.
+        let rpc = MockRpc::default()
+            .respond(
+                "estimatesmartfee",
+                serde_json::json!({ "feerate": 0.00002, "blocks": 6 }),
+            )
+            .respond("getnetworkinfo", network_info(0.00005));
+
+        let fee_rate = estimate_fee_rate(&rpc, 6, FeeRate::from_sat_per_vb(1).unwrap()).unwrap();
+
+        assert_eq!(fee_rate, FeeRate::from_sat_per_vb(5).unwrap());
+    }
+
+    #[test]
+    fn test_estimate_fee_rate_falls_back_to_floor() {
+        let rpc = MockRpc::default().respond(
+            "estimatesmartfee",
+            serde_json::json!({
+                "errors": ["Insufficient data or no feerate found"],
+                "blocks": 0
+            }),
+        );
+        let rpc = rpc.respond("getnetworkinfo", network_info(0.00001));
+        let floor = FeeRate::from_sat_per_vb(3).unwrap();
+
+        assert_eq!(estimate_fee_rate(&rpc, 6, floor).unwrap(), floor);
+    }
+
+    /// `getnetworkinfo` response with `relay_fee` in BTC/kvB.
+    fn network_info(relay_fee: f64) -> serde_json::Value {
+        serde_json::json!({
+            "version": 270000,
+            "subversion": "/Satoshi:27.0.0/",
+            "protocolversion": 70016,
+            "localservices": "0000000000000409",
+            "localrelay": true,
+            "timeoffset": 0,
+            "connections": 0,
+            "networkactive": true,
+            "networks": [],
+            "relayfee": relay_fee,
+            "incrementalfee": 0.00001,
+            "localaddresses": [],
+            "warnings": ""
+        })
+    }
+
+    fn reveal_tx_with_script(reveal_script: ScriptBuf) -> Transaction {
+        let mut witness = Witness::new();
+        witness.push([0u8; SCHNORR_SIGNATURE_SIZE]);
+        witness.push(reveal_script.as_bytes());
+        witness.push([0xc0; 33]);
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::all_zeros(), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness,
+            }],
+            output: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_reveal_payload_round_trip() {
+        let payload: Vec<u8> = (0..60_000).map(|i| (i % 251) as u8).collect();
+        let reveal_script = build_reveal_script(&get_public_key(), &[payload.clone()]).unwrap();
+
+        let parsed = parse_reveal_payload(&reveal_tx_with_script(reveal_script)).unwrap();
+
+        assert_eq!(parsed, payload);
+    }
+
+    #[test]
+    fn test_parse_reveal_payloads_multiple_envelopes() {
+        let payloads = vec![vec![1u8; 700], vec![2u8; 10]];
+        let reveal_script = build_reveal_script(&get_public_key(), &payloads).unwrap();
+        let tx = reveal_tx_with_script(reveal_script);
+
+        assert_eq!(parse_reveal_payloads(&tx).unwrap(), payloads);
+        assert!(parse_reveal_payload(&tx).is_err());
+    }
+
+    #[test]
+    fn test_parse_reveal_payload_rejects_other_scripts() {
+        let tx =
+            reveal_tx_with_script(ScriptBuf::from_hex("00630b48656c6c6f20576f726c6468").unwrap());
+
+        assert!(parse_reveal_payload(&tx).is_err());
+    }
 }
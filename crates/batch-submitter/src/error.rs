@@ -1,3 +1,5 @@
+use bitcoin::{Amount, OutPoint, Txid};
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
@@ -10,10 +12,24 @@ pub enum Error {
     BitcoinRPCError(#[from] bitcoincore_rpc::Error),
     #[error("Encode error: {0}")]
     EncodeError(#[from] bitcoin::consensus::encode::Error),
+    #[error("Fee bump leaves change of {0}, below the dust limit")]
+    ChangeBelowDust(Amount),
+    #[error("Stopped waiting for the confirmations of {0}")]
+    ConfirmationCancelled(Txid),
+    #[error("Reveal tx {txid} has {confirmations} confirmations after the confirmation timeout")]
+    ConfirmationTimeout { txid: Txid, confirmations: u64 },
+    #[error("Fee computation overflowed")]
+    FeeOverflow,
     #[error("Hex to array error: {0}")]
     HexToArrayError(#[from] bitcoin::hex::HexToArrayError),
+    #[error("Input {0} is already spent or unknown")]
+    InputConflict(OutPoint),
     #[error("Internal Error: {0}")]
     Internal(String),
+    #[error("Reveal tx {0} is neither in the mempool nor in the chain")]
+    RevealDropped(Txid),
+    #[error("Reveal tx rejected after commit tx {commit_txid} was broadcast: {reason}")]
+    RevealRejected { commit_txid: Txid, reason: String },
     #[error("secp256k1 error: {0}")]
     Secp256k1Error(#[from] bitcoin::secp256k1::Error),
     #[error("Sighash taproot error: {0}")]
@@ -22,6 +38,8 @@ pub enum Error {
     TaprootError(#[from] bitcoin::taproot::TaprootBuilderError),
     #[error("Push bytes error: {0}")]
     PushBytesError(#[from] bitcoin::script::PushBytesError),
+    #[error("Script error: {0}")]
+    ScriptError(#[from] bitcoin::script::Error),
     #[error("RecvError: {0}")]
     RecvError(#[from] tokio::sync::broadcast::error::RecvError),
     #[error("MsgIO error: {0}")]
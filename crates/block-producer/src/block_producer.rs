@@ -1,6 +1,6 @@
 use crate::{
     error::{Error, Result},
-    types::Request,
+    types::{BlockProducerOptions, Request},
 };
 use ethrex_blockchain::{
     Blockchain,
@@ -41,10 +41,15 @@ use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use ethrex_vm::BlockExecutionResult;
 use mojave_node_lib::types::MojaveNode;
+use mojave_signature::{
+    SigningKey,
+    types::{Signature, Signer},
+};
 use mojave_task::Task;
 use std::{
     collections::{BTreeMap, HashMap},
     ops::Div,
+    str::FromStr,
     sync::Arc,
     time::{Instant, SystemTime},
 };
@@ -57,6 +62,7 @@ pub struct BlockProducer {
     rollup_store: StoreRollup,
     coinbase_address: Address,
     p2p_context: P2PContext,
+    signing_key: SigningKey,
 }
 
 impl Task for BlockProducer {
@@ -73,9 +79,10 @@ impl Task for BlockProducer {
                     Ok(block) => {
                         info!("New block created: {:x}", block.hash());
 
+                        let signature = self.sign_block(&block)?;
                         let msg = Message::Mojave(MojaveMessage::Block(MojaveBlock::new(
                             block.clone(),
-                            None,
+                            Some(signature.bytes.into()),
                         )));
                         if let Err(e) = self.p2p_context.broadcast_mojave_message(msg) {
                             error!(target: module_path!(), "Failed to broadcast new block: {e:?}");
@@ -99,14 +106,21 @@ impl Task for BlockProducer {
 }
 
 impl BlockProducer {
-    pub fn new(node: MojaveNode) -> Self {
-        BlockProducer {
+    pub fn new(node: MojaveNode, options: &BlockProducerOptions) -> Result<Self> {
+        Ok(BlockProducer {
             store: node.store.clone(),
             blockchain: node.blockchain.clone(),
             rollup_store: node.rollup_store.clone(),
             coinbase_address: node.genesis.coinbase,
             p2p_context: node.p2p_context.clone(),
-        }
+            signing_key: SigningKey::from_str(&options.private_key)?,
+        })
+    }
+
+    /// Signs the block header hash so full nodes can check the block came
+    /// from this sequencer.
+    pub(crate) fn sign_block(&self, block: &Block) -> Result<Signature> {
+        Ok(self.signing_key.sign(&block.header.hash())?)
     }
 
     pub(crate) async fn build_block(&self) -> Result<Block> {
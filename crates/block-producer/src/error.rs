@@ -39,6 +39,8 @@ pub enum Error {
     RollupStoreError(#[from] RollupStoreError),
     #[error(transparent)]
     Rpc(#[from] mojave_utils::rpc::error::Error),
+    #[error("BlockProducer failed to sign the block: {0}")]
+    Signature(#[from] mojave_signature::error::Error),
     #[error("BlockProducer stopped.")]
     Stopped,
     #[error("BlockProducer failed to retrieve a block from storage, data is None.")]
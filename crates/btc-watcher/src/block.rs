@@ -1,4 +1,4 @@
-use bitcoin::{Block, consensus::deserialize};
+use bitcoin::{Block, consensus::deserialize, hashes::Hash};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
@@ -16,6 +16,10 @@ impl Decodable for Block {
     fn decode(_topic: &str, payload: &[u8]) -> core::result::Result<Self, Error<Self>> {
         deserialize(payload).map_err(Error::DeserializationError)
     }
+
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        Some(self.block_hash().to_byte_array().to_vec())
+    }
 }
 
 pub type Result<T> = core::result::Result<T, Error<Block>>;
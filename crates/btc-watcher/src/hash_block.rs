@@ -0,0 +1,70 @@
+use bitcoin::{BlockHash, hashes::Hash};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    error::Error,
+    types::HashBlockWatcherBuilder,
+    watch::{Decodable, Topics},
+};
+
+/// ZMQ `-zmqpubhashblock` notification: the hash of a newly connected block.
+///
+/// The payload is the 32-byte hash in RPC/ZMQ (display) byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashBlock(pub BlockHash);
+
+impl Topics for HashBlock {
+    const TOPICS: &'static [&'static str] = &["hashblock"];
+}
+
+impl Decodable for HashBlock {
+    #[inline]
+    fn decode(_topic: &str, payload: &[u8]) -> core::result::Result<Self, Error<Self>> {
+        let mut bytes: [u8; 32] = payload.try_into().map_err(|_| {
+            Error::DeserializationError(bitcoin::consensus::encode::Error::ParseFailed(
+                "hashblock payload is not 32 bytes",
+            ))
+        })?;
+        bytes.reverse();
+        Ok(HashBlock(BlockHash::from_byte_array(bytes)))
+    }
+
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        Some(self.0.to_byte_array().to_vec())
+    }
+}
+
+/// Helper to create a builder with default configuration.
+pub fn builder(socket_url: &str, shutdown: CancellationToken) -> HashBlockWatcherBuilder {
+    HashBlockWatcherBuilder::new(socket_url, shutdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hex::FromHex;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_hash_block_topic() {
+        assert_eq!(HashBlock::TOPICS, &["hashblock"]);
+    }
+
+    #[test]
+    fn test_hash_block_decode_flips_byte_order() {
+        // Genesis block hash as published by bitcoind (display order).
+        let genesis = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+        let payload = Vec::<u8>::from_hex(genesis).unwrap();
+
+        let decoded = HashBlock::decode("hashblock", &payload).unwrap();
+
+        assert_eq!(decoded.0, BlockHash::from_str(genesis).unwrap());
+        assert_eq!(decoded.0.to_byte_array()[0], 0x6f);
+    }
+
+    #[test]
+    fn test_hash_block_decode_rejects_wrong_length() {
+        assert!(HashBlock::decode("hashblock", &[0u8; 31]).is_err());
+        assert!(HashBlock::decode("hashblock", &[0u8; 33]).is_err());
+    }
+}
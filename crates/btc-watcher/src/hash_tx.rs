@@ -0,0 +1,71 @@
+use bitcoin::{Txid, hashes::Hash};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    error::Error,
+    types::HashTxWatcherBuilder,
+    watch::{Decodable, Topics},
+};
+
+/// ZMQ `-zmqpubhashtx` notification: the id of a transaction entering the
+/// mempool or a connected block.
+///
+/// The payload is the 32-byte txid in RPC/ZMQ (display) byte order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashTx(pub Txid);
+
+impl Topics for HashTx {
+    const TOPICS: &'static [&'static str] = &["hashtx"];
+}
+
+impl Decodable for HashTx {
+    #[inline]
+    fn decode(_topic: &str, payload: &[u8]) -> core::result::Result<Self, Error<Self>> {
+        let mut bytes: [u8; 32] = payload.try_into().map_err(|_| {
+            Error::DeserializationError(bitcoin::consensus::encode::Error::ParseFailed(
+                "hashtx payload is not 32 bytes",
+            ))
+        })?;
+        bytes.reverse();
+        Ok(HashTx(Txid::from_byte_array(bytes)))
+    }
+
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        Some(self.0.to_byte_array().to_vec())
+    }
+}
+
+/// Helper to create a builder with default configuration.
+pub fn builder(socket_url: &str, shutdown: CancellationToken) -> HashTxWatcherBuilder {
+    HashTxWatcherBuilder::new(socket_url, shutdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_tx_topic() {
+        assert_eq!(HashTx::TOPICS, &["hashtx"]);
+    }
+
+    #[test]
+    fn test_hash_tx_decode_flips_byte_order() {
+        let payload: Vec<u8> = (0u8..32).collect();
+
+        let decoded = HashTx::decode("hashtx", &payload).unwrap();
+
+        let mut expected: Vec<u8> = (0u8..32).collect();
+        expected.reverse();
+        assert_eq!(decoded.0.to_byte_array().as_slice(), expected.as_slice());
+        assert_eq!(
+            decoded.0.to_string(),
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+        );
+    }
+
+    #[test]
+    fn test_hash_tx_decode_rejects_wrong_length() {
+        assert!(HashTx::decode("hashtx", &[]).is_err());
+    }
+}
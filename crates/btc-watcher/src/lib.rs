@@ -1,5 +1,8 @@
 pub mod block;
 pub mod error;
+pub mod hash_block;
+pub mod hash_tx;
+pub mod metrics;
 pub mod multi;
 pub mod sequence;
 pub mod transaction;
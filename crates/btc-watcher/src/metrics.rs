@@ -0,0 +1,83 @@
+use lazy_static::lazy_static;
+use mojave_utils::metrics::opts;
+use prometheus::{
+    IntCounter, IntCounterVec, IntGaugeVec, register_int_counter, register_int_counter_vec,
+    register_int_gauge_vec,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    /// Number of ZMQ messages received by watchers, labelled by topic.
+    pub static ref WATCHER_MESSAGES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        opts(
+            "watcher_messages_total",
+            "Number of ZMQ messages received by the watcher per topic",
+        ),
+        &["topic"]
+    )
+    .expect("watcher_messages_total can be registered");
+
+    /// Unix timestamp (seconds) of the last message received per topic.
+    /// A stale value indicates a dead ZMQ feed.
+    pub static ref WATCHER_LAST_MESSAGE_TIMESTAMP: IntGaugeVec = register_int_gauge_vec!(
+        opts(
+            "watcher_last_message_timestamp",
+            "Unix timestamp of the last ZMQ message received by the watcher per topic",
+        ),
+        &["topic"]
+    )
+    .expect("watcher_last_message_timestamp can be registered");
+
+    /// Number of decoded items that were not delivered because no subscriber
+    /// was listening.
+    pub static ref WATCHER_UNDELIVERED_TOTAL: IntCounter = register_int_counter!(opts(
+        "watcher_undelivered_total",
+        "Number of watcher items dropped because there was no subscriber",
+    ))
+    .expect("watcher_undelivered_total can be registered");
+
+    /// Number of items skipped by subscribers that fell behind the channel.
+    pub static ref WATCHER_LAGGED_TOTAL: IntCounter = register_int_counter!(opts(
+        "watcher_lagged_total",
+        "Number of watcher items skipped by lagging subscribers",
+    ))
+    .expect("watcher_lagged_total can be registered");
+}
+
+/// Records a message received on `topic`.
+pub(crate) fn record_message(topic: &str) {
+    WATCHER_MESSAGES_TOTAL.with_label_values(&[topic]).inc();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    WATCHER_LAST_MESSAGE_TIMESTAMP
+        .with_label_values(&[topic])
+        .set(now);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_message_updates_counter_and_timestamp() {
+        let topic = "metrics_test_topic";
+        let before = WATCHER_MESSAGES_TOTAL.with_label_values(&[topic]).get();
+
+        record_message(topic);
+        record_message(topic);
+
+        assert_eq!(
+            WATCHER_MESSAGES_TOTAL.with_label_values(&[topic]).get(),
+            before + 2
+        );
+        assert!(
+            WATCHER_LAST_MESSAGE_TIMESTAMP
+                .with_label_values(&[topic])
+                .get()
+                > 0
+        );
+    }
+}
@@ -40,6 +40,21 @@ impl Decodable for Multi {
             )),
         }
     }
+
+    fn mempool_seq(&self) -> Option<u64> {
+        match self {
+            Multi::Sequence(seq) => seq.mempool_seq,
+            _ => None,
+        }
+    }
+
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        match self {
+            Multi::Block(block) => block.dedup_key(),
+            Multi::Transaction(tx) => tx.dedup_key(),
+            Multi::Sequence(seq) => seq.dedup_key(),
+        }
+    }
 }
 
 /// Helper to create a builder with default configuration.
@@ -136,6 +136,51 @@ impl Sequence {
     }
 }
 
+/// Two `TxAdded`/`TxRemoved` notifications whose mempool sequences are not
+/// contiguous. Events in between were missed and the mempool view is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceGap {
+    pub expected: u64,
+    pub got: u64,
+}
+
+/// Tracks the last observed mempool sequence to detect missed notifications.
+///
+/// `bitcoind` increments the mempool sequence by one for every mempool
+/// change, so any other step means events were dropped on the way. The
+/// exception are transactions leaving the mempool with a connected or
+/// disconnected block: they advance the sequence without an `R` event, so the
+/// tracker is re-baselined on `C`/`D` events.
+#[derive(Debug, Default, Clone)]
+pub struct MempoolSequenceTracker {
+    last: Option<u64>,
+}
+
+impl MempoolSequenceTracker {
+    /// Records `mempool_seq` and returns the gap if it does not directly
+    /// follow the previously observed value.
+    pub fn observe(&mut self, mempool_seq: u64) -> Option<SequenceGap> {
+        let gap = self
+            .last
+            .map(|last| last.wrapping_add(1))
+            .filter(|expected| *expected != mempool_seq)
+            .map(|expected| SequenceGap {
+                expected,
+                got: mempool_seq,
+            });
+        if let Some(SequenceGap { expected, got }) = gap {
+            tracing::warn!(expected, got, "mempool sequence gap");
+        }
+        self.last = Some(mempool_seq);
+        gap
+    }
+
+    /// Forgets the last observed value; the next one becomes the baseline.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
 impl Topics for Sequence {
     const TOPICS: &'static [&'static str] = &["sequence"];
 }
@@ -146,6 +191,29 @@ impl WatcherDecodable for Sequence {
         use bitcoin::consensus::deserialize;
         deserialize(payload).map_err(Error::DeserializationError)
     }
+
+    fn mempool_seq(&self) -> Option<u64> {
+        self.mempool_seq
+    }
+
+    fn resets_mempool_seq(&self) -> bool {
+        self.is_block()
+    }
+
+    /// Mempool sequences differ between nodes, so only the hash and the kind
+    /// identify an event.
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        let kind = match self.event {
+            SequenceEvent::BlockConnected => b'C',
+            SequenceEvent::BlockDisconnected => b'D',
+            SequenceEvent::TxAdded => b'A',
+            SequenceEvent::TxRemoved => b'R',
+            SequenceEvent::Unknown(kind) => kind,
+        };
+        let mut key = self.hash_bytes.to_vec();
+        key.push(kind);
+        Some(key)
+    }
 }
 
 /// Helper to create a builder with default configuration.
@@ -438,4 +506,32 @@ mod tests {
         let result = Sequence::consensus_decode_from_finite_reader(&mut cursor);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_mempool_sequence_tracker_detects_gap() {
+        let mut tracker = MempoolSequenceTracker::default();
+
+        assert_eq!(tracker.observe(1), None);
+        assert_eq!(tracker.observe(2), None);
+        assert_eq!(
+            tracker.observe(4),
+            Some(SequenceGap {
+                expected: 3,
+                got: 4
+            })
+        );
+        // Tracking continues from the latest value.
+        assert_eq!(tracker.observe(5), None);
+    }
+
+    #[test]
+    fn test_mempool_sequence_tracker_rebaselines_after_reset() {
+        let mut tracker = MempoolSequenceTracker::default();
+
+        assert_eq!(tracker.observe(1), None);
+        // A block removed its transactions from the mempool without `R` events.
+        tracker.reset();
+        assert_eq!(tracker.observe(7), None);
+        assert_eq!(tracker.observe(8), None);
+    }
 }
@@ -1,4 +1,4 @@
-use bitcoin::{Transaction, consensus::deserialize};
+use bitcoin::{Transaction, consensus::deserialize, hashes::Hash};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
@@ -15,6 +15,10 @@ impl Decodable for Transaction {
     fn decode(_topic: &str, payload: &[u8]) -> core::result::Result<Self, Error<Self>> {
         deserialize(payload).map_err(Error::DeserializationError)
     }
+
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        Some(self.compute_txid().to_byte_array().to_vec())
+    }
 }
 
 /// Helper to create a builder with default configuration.
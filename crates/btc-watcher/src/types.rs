@@ -1,6 +1,8 @@
 use bitcoin::{Block, Transaction};
 
 use crate::{
+    hash_block::HashBlock,
+    hash_tx::HashTx,
     multi::Multi,
     sequence::Sequence,
     watch::{Watcher, WatcherBuilder, WatcherHandle},
@@ -21,3 +23,11 @@ pub type TransactionWatcherHandle = WatcherHandle<Transaction>;
 pub type MultiWatcher = Watcher<Multi>;
 pub type MultiWatcherBuilder = WatcherBuilder<Multi>;
 pub type MultiWatcherHandle = WatcherHandle<Multi>;
+
+pub type HashBlockWatcher = Watcher<HashBlock>;
+pub type HashBlockWatcherBuilder = WatcherBuilder<HashBlock>;
+pub type HashBlockWatcherHandle = WatcherHandle<HashBlock>;
+
+pub type HashTxWatcher = Watcher<HashTx>;
+pub type HashTxWatcherBuilder = WatcherBuilder<HashTx>;
+pub type HashTxWatcherHandle = WatcherHandle<HashTx>;
@@ -0,0 +1,432 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{sync::broadcast, time::Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::watch::{
+    Decodable, Topics, WatcherEvent, WatcherHandle, WatcherMessage, WatcherReceiver,
+};
+
+const DEFAULT_DEDUP_WINDOW: usize = 1024;
+
+/// Watches a secondary ZMQ endpoint next to the primary one.
+///
+/// Items from the primary are forwarded as long as it keeps delivering. Once
+/// the primary has been silent for `staleness`, items from the secondary are
+/// forwarded instead, until the primary delivers again. Items seen on both
+/// endpoints are delivered once, based on [`Decodable::dedup_key`].
+///
+/// While the primary is healthy, the last `dedup_window` secondary items it
+/// has not delivered yet are held back. They are forwarded on failover, or
+/// when the primary subscription lags, so items the primary missed before
+/// going silent are not lost.
+#[derive(Debug, Clone)]
+pub struct FailoverPolicy {
+    pub secondary_url: String,
+    /// How long the primary may stay silent before failing over.
+    pub staleness: Duration,
+    /// Number of recently forwarded item keys remembered for deduplication.
+    pub dedup_window: usize,
+}
+
+impl FailoverPolicy {
+    pub fn new(secondary_url: &str, staleness: Duration) -> Self {
+        Self {
+            secondary_url: secondary_url.to_string(),
+            staleness,
+            dedup_window: DEFAULT_DEDUP_WINDOW,
+        }
+    }
+
+    pub fn with_dedup_window(mut self, dedup_window: usize) -> Self {
+        self.dedup_window = dedup_window;
+        self
+    }
+}
+
+/// Bounded set of recently forwarded item keys.
+struct RecentKeys {
+    capacity: usize,
+    order: VecDeque<Vec<u8>>,
+    keys: HashSet<Vec<u8>>,
+}
+
+impl RecentKeys {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            keys: HashSet::with_capacity(capacity),
+        }
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Returns `false` if `key` was already seen within the window.
+    fn insert(&mut self, key: Vec<u8>) -> bool {
+        if self.capacity == 0 {
+            return true;
+        }
+        if self.keys.contains(&key) {
+            return false;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.keys.remove(&oldest);
+        }
+        self.order.push_back(key.clone());
+        self.keys.insert(key);
+        true
+    }
+}
+
+struct Failover<T> {
+    policy: FailoverPolicy,
+    sender: broadcast::Sender<T>,
+    events: broadcast::Sender<WatcherEvent>,
+    recent: RecentKeys,
+    /// Secondary items not yet delivered by the primary, oldest first.
+    held_back: VecDeque<(Vec<u8>, T)>,
+    lagged: Arc<AtomicU64>,
+    last_primary: Instant,
+    on_secondary: bool,
+}
+
+impl<T> Failover<T>
+where
+    T: Decodable + Clone,
+{
+    fn on_primary(&mut self, item: T) {
+        self.last_primary = Instant::now();
+        if self.on_secondary {
+            self.on_secondary = false;
+            self.held_back.clear();
+            tracing::info!("Primary ZMQ endpoint recovered; failing back");
+            let _ = self.events.send(WatcherEvent::FailedBack);
+        }
+        if let Some(key) = item.dedup_key() {
+            self.held_back.retain(|(held, _)| *held != key);
+        }
+        self.forward(item);
+    }
+
+    /// The primary subscription skipped `skipped` items. The held back
+    /// secondary items may include them, so they are forwarded right away.
+    fn on_primary_lagged(&mut self, skipped: u64) {
+        self.lagged.fetch_add(skipped, Ordering::Relaxed);
+        tracing::warn!(
+            skipped,
+            held_back = self.held_back.len(),
+            "Primary ZMQ watcher lagged; forwarding held back secondary items"
+        );
+        self.flush_held_back();
+    }
+
+    fn on_secondary(&mut self, item: T, primary_alive: bool) {
+        let idle = self.last_primary.elapsed();
+        if !self.on_secondary && (!primary_alive || idle >= self.policy.staleness) {
+            self.on_secondary = true;
+            tracing::warn!(
+                ?idle,
+                secondary = %self.policy.secondary_url,
+                held_back = self.held_back.len(),
+                "Primary ZMQ endpoint is stale; failing over"
+            );
+            let _ = self.events.send(WatcherEvent::FailedOver { idle });
+            self.flush_held_back();
+        }
+        if self.on_secondary {
+            self.forward(item);
+        } else {
+            self.hold_back(item);
+        }
+    }
+
+    fn on_secondary_lagged(&mut self, skipped: u64) {
+        if self.on_secondary {
+            self.lagged.fetch_add(skipped, Ordering::Relaxed);
+        }
+    }
+
+    /// Keeps a secondary item the primary has not delivered, dropping the
+    /// oldest one when `dedup_window` items are already held back.
+    fn hold_back(&mut self, item: T) {
+        let Some(key) = item.dedup_key() else {
+            return;
+        };
+        if self.recent.contains(&key) || self.held_back.iter().any(|(held, _)| *held == key) {
+            return;
+        }
+        if self.held_back.len() >= self.policy.dedup_window {
+            self.held_back.pop_front();
+        }
+        if self.policy.dedup_window > 0 {
+            self.held_back.push_back((key, item));
+        }
+    }
+
+    fn flush_held_back(&mut self) {
+        while let Some((_, item)) = self.held_back.pop_front() {
+            self.forward(item);
+        }
+    }
+
+    fn forward(&mut self, item: T) {
+        if let Some(key) = item.dedup_key()
+            && !self.recent.insert(key)
+        {
+            tracing::debug!("Dropping duplicate watcher item");
+            return;
+        }
+        if self.sender.send(item).is_err() {
+            crate::metrics::WATCHER_UNDELIVERED_TOTAL.inc();
+            tracing::debug!("No subscriber for watcher item; dropping it");
+        }
+    }
+}
+
+/// Spawns the task forwarding the items of the active endpoint's watcher.
+///
+/// `workers` is the shutdown token of both endpoint watchers; it is cancelled
+/// when the returned watcher stops.
+pub(crate) fn spawn<T>(
+    primary: WatcherHandle<T>,
+    secondary: WatcherHandle<T>,
+    policy: FailoverPolicy,
+    capacity: usize,
+    shutdown: CancellationToken,
+    workers: CancellationToken,
+    events: broadcast::Sender<WatcherEvent>,
+) -> WatcherHandle<T>
+where
+    T: Topics + Decodable + Send + Clone + 'static + core::fmt::Debug,
+{
+    let (sender, _) = broadcast::channel(capacity);
+    let lagged = Arc::new(AtomicU64::new(0));
+    let mut failover = Failover {
+        recent: RecentKeys::new(policy.dedup_window),
+        held_back: VecDeque::new(),
+        policy,
+        sender: sender.clone(),
+        events: events.clone(),
+        lagged: lagged.clone(),
+        last_primary: Instant::now(),
+        on_secondary: false,
+    };
+
+    let task_shutdown = shutdown.clone();
+    let join = tokio::spawn(async move {
+        let mut primary_rx = Some(primary.subscribe());
+        let mut secondary_rx = Some(secondary.subscribe());
+
+        while primary_rx.is_some() || secondary_rx.is_some() {
+            tokio::select! {
+                biased;
+
+                _ = task_shutdown.cancelled() => break,
+
+                msg = recv(&mut primary_rx) => match msg {
+                    Some(WatcherMessage::Item(item)) => failover.on_primary(item),
+                    Some(WatcherMessage::Lagged(skipped)) => failover.on_primary_lagged(skipped),
+                    None => {
+                        tracing::warn!("Primary ZMQ watcher stopped");
+                        primary_rx = None;
+                    }
+                },
+
+                msg = recv(&mut secondary_rx) => match msg {
+                    Some(WatcherMessage::Item(item)) => {
+                        failover.on_secondary(item, primary_rx.is_some())
+                    }
+                    Some(WatcherMessage::Lagged(skipped)) => failover.on_secondary_lagged(skipped),
+                    None => {
+                        tracing::warn!("Secondary ZMQ watcher stopped");
+                        secondary_rx = None;
+                    }
+                },
+            }
+        }
+
+        workers.cancel();
+        let primary = primary.join().await;
+        let secondary = secondary.join().await;
+        primary.and(secondary)
+    });
+
+    WatcherHandle {
+        sender,
+        events,
+        shutdown,
+        join,
+        lagged,
+    }
+}
+
+/// Receives from `receiver`, pending forever once it is gone.
+async fn recv<T: Clone>(receiver: &mut Option<WatcherReceiver<T>>) -> Option<WatcherMessage<T>> {
+    match receiver {
+        Some(receiver) => receiver.recv().await.ok(),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash_block::HashBlock, watch::WatcherBuilder};
+    use bitcoin::hashes::Hash;
+    use zeromq::{Socket, SocketSend, ZmqMessage};
+
+    fn hashblock(byte: u8) -> ZmqMessage {
+        let mut msg = ZmqMessage::from("hashblock");
+        msg.push_back(vec![byte; 32].into());
+        msg.push_back(vec![0u8; 4].into());
+        msg
+    }
+
+    async fn next_item(receiver: &mut WatcherReceiver<HashBlock>) -> Option<HashBlock> {
+        match tokio::time::timeout(Duration::from_millis(300), receiver.recv()).await {
+            Ok(Ok(WatcherMessage::Item(item))) => Some(item),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_recent_keys_evicts_oldest() {
+        let mut recent = RecentKeys::new(2);
+
+        assert!(recent.insert(vec![1]));
+        assert!(!recent.insert(vec![1]));
+        assert!(recent.insert(vec![2]));
+        assert!(recent.insert(vec![3]));
+        assert!(recent.insert(vec![1]));
+    }
+
+    fn failover(dedup_window: usize) -> (Failover<HashBlock>, broadcast::Receiver<HashBlock>) {
+        let (sender, receiver) = broadcast::channel(16);
+        let (events, _) = broadcast::channel(16);
+        let policy = FailoverPolicy::new("tcp://127.0.0.1:0", Duration::from_secs(60))
+            .with_dedup_window(dedup_window);
+        let failover = Failover {
+            recent: RecentKeys::new(policy.dedup_window),
+            held_back: VecDeque::new(),
+            policy,
+            sender,
+            events,
+            lagged: Default::default(),
+            last_primary: Instant::now(),
+            on_secondary: false,
+        };
+        (failover, receiver)
+    }
+
+    fn block(byte: u8) -> HashBlock {
+        HashBlock(bitcoin::BlockHash::from_byte_array([byte; 32]))
+    }
+
+    #[tokio::test]
+    async fn test_held_back_items_are_flushed_on_failover() {
+        let (mut failover, mut items) = failover(2);
+
+        // Block 1 reaches the primary too; blocks 2 to 4 only the secondary,
+        // and only the last two of them are kept.
+        failover.on_secondary(block(1), true);
+        failover.on_primary(block(1));
+        failover.on_secondary(block(2), true);
+        failover.on_secondary(block(3), true);
+        failover.on_secondary(block(4), true);
+        assert_eq!(items.try_recv().unwrap(), block(1));
+        assert!(items.try_recv().is_err());
+
+        // The primary stops; block 5 triggers the failover.
+        failover.on_secondary(block(5), false);
+        assert_eq!(items.try_recv().unwrap(), block(3));
+        assert_eq!(items.try_recv().unwrap(), block(4));
+        assert_eq!(items.try_recv().unwrap(), block(5));
+        assert!(items.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_primary_lag_flushes_held_back_items_and_is_counted() {
+        let (mut failover, mut items) = failover(8);
+        let lagged = failover.lagged.clone();
+
+        failover.on_secondary(block(1), true);
+        failover.on_primary_lagged(3);
+        assert_eq!(lagged.load(Ordering::Relaxed), 3);
+        assert_eq!(items.try_recv().unwrap(), block(1));
+
+        // The primary delivering the flushed block later is not a duplicate
+        // delivery.
+        failover.on_primary(block(1));
+        assert!(items.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_on_stalled_primary_and_back() {
+        let mut primary = zeromq::PubSocket::new();
+        let primary_url = primary.bind("tcp://127.0.0.1:0").await.unwrap();
+        let mut secondary = zeromq::PubSocket::new();
+        let secondary_url = secondary.bind("tcp://127.0.0.1:0").await.unwrap();
+
+        let shutdown = CancellationToken::new();
+        let handle = WatcherBuilder::<HashBlock>::new(&primary_url.to_string(), shutdown)
+            .with_failover(FailoverPolicy::new(
+                &secondary_url.to_string(),
+                Duration::from_millis(100),
+            ))
+            .spawn()
+            .await
+            .unwrap();
+        let mut items = handle.subscribe();
+        let mut events = handle.subscribe_events();
+        // Let both subscriptions reach the publishers.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Both endpoints deliver block 1; it is forwarded once, from the primary.
+        primary.send(hashblock(1)).await.unwrap();
+        secondary.send(hashblock(1)).await.unwrap();
+        assert_eq!(
+            next_item(&mut items).await.unwrap().0.to_byte_array(),
+            [1; 32]
+        );
+        assert!(next_item(&mut items).await.is_none());
+
+        // The primary stalls; block 2 only arrives on the secondary.
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        secondary.send(hashblock(2)).await.unwrap();
+        assert_eq!(
+            next_item(&mut items).await.unwrap().0.to_byte_array(),
+            [2; 32]
+        );
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            WatcherEvent::FailedOver { .. }
+        ));
+
+        // The primary catches up with block 2 and delivers block 3.
+        primary.send(hashblock(2)).await.unwrap();
+        primary.send(hashblock(3)).await.unwrap();
+        assert_eq!(
+            next_item(&mut items).await.unwrap().0.to_byte_array(),
+            [3; 32]
+        );
+        assert_eq!(events.recv().await.unwrap(), WatcherEvent::FailedBack);
+
+        // Back on the primary, the secondary is ignored again.
+        secondary.send(hashblock(4)).await.unwrap();
+        assert!(next_item(&mut items).await.is_none());
+
+        handle.shutdown();
+        assert!(handle.join().await.is_ok());
+    }
+}
@@ -1,7 +1,9 @@
+mod failover;
 mod watcher;
 mod watcher_builder;
 mod watcher_handle;
 
+pub use failover::FailoverPolicy;
 pub use watcher::*;
 pub use watcher_builder::*;
 pub use watcher_handle::*;
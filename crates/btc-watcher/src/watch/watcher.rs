@@ -1,13 +1,20 @@
 use crate::{
     error::{Error, Result},
-    watch::WatcherHandle,
+    sequence::MempoolSequenceTracker,
+    watch::{WatcherBuilder, WatcherHandle},
 };
 use mojave_utils::constants::{
     ZMQ_MESSAGE_MIN_FRAMES, ZMQ_PAYLOAD_FRAME_INDEX, ZMQ_TOPIC_FRAME_INDEX,
 };
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 use zeromq::{Socket, SocketRecv, SubSocket, ZmqMessage};
 
+/// First delay between reconnect attempts after the ZMQ feed fails.
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// Upper bound for the delay between reconnect attempts.
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
 /// Trait describing the default subscription topic for a watcher type.
 pub trait Topics {
     /// ZMQ topics to subscribe to.
@@ -16,13 +23,50 @@ pub trait Topics {
 
 pub trait Decodable: Sized + core::fmt::Debug {
     fn decode(topic: &str, payload: &[u8]) -> Result<Self, Self>;
+
+    /// Mempool sequence carried by the item, used to detect missed events.
+    fn mempool_seq(&self) -> Option<u64> {
+        None
+    }
+
+    /// Whether the mempool sequence may advance past this item without
+    /// notifications of its own, e.g. for transactions mined in a block.
+    fn resets_mempool_seq(&self) -> bool {
+        false
+    }
+
+    /// Identity of the item, used to drop duplicates delivered by several
+    /// endpoints. Items without a key are never deduplicated.
+    fn dedup_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Events about the state of the ZMQ feed, published next to the decoded items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatcherEvent {
+    /// No message arrived within the liveness timeout; the watcher reconnects.
+    FeedStalled { idle: Duration },
+    /// Receiving from the socket failed; the watcher is reconnecting.
+    Reconnecting { attempt: u32 },
+    /// Mempool notifications were missed; consumers should resync the mempool.
+    MempoolSequenceGap { expected: u64, got: u64 },
+    /// The primary endpoint was silent for `idle`; items come from the secondary.
+    FailedOver { idle: Duration },
+    /// The primary endpoint delivers again and is used instead of the secondary.
+    FailedBack,
 }
 
 /// Generic ZMQ watcher.
 pub struct Watcher<T> {
     pub(crate) socket: SubSocket,
+    pub(crate) socket_url: String,
+    pub(crate) topics: Vec<String>,
+    pub(crate) liveness_timeout: Option<Duration>,
     pub(crate) shutdown: CancellationToken,
     pub(crate) sender: tokio::sync::broadcast::Sender<T>,
+    pub(crate) events: tokio::sync::broadcast::Sender<WatcherEvent>,
+    pub(crate) mempool_seq: MempoolSequenceTracker,
 }
 
 impl<T> Watcher<T>
@@ -34,33 +78,24 @@ where
         shutdown: CancellationToken,
         max_channel_capacity: usize,
     ) -> Result<WatcherHandle<T>, T> {
-        let mut socket = SubSocket::new();
-        socket.connect(socket_url).await?;
-        for topic in T::TOPICS {
-            socket.subscribe(topic).await?;
-        }
-
-        let (sender, _) = tokio::sync::broadcast::channel(max_channel_capacity);
-
-        let mut worker = Watcher {
-            socket,
-            shutdown: shutdown.clone(),
-            sender: sender.clone(),
-        };
-
-        let join = tokio::spawn(async move { worker.watch().await });
-
-        Ok(WatcherHandle {
-            sender,
-            shutdown,
-            join,
-        })
+        WatcherBuilder::<T>::new(socket_url, shutdown)
+            .with_capacity(max_channel_capacity)
+            .spawn()
+            .await
     }
 
     pub(crate) async fn watch(&mut self) -> Result<(), T> {
         tracing::info!("Watcher started");
 
         loop {
+            let liveness_timeout = self.liveness_timeout;
+            let stalled = async move {
+                match liveness_timeout {
+                    Some(timeout) => tokio::time::sleep(timeout).await,
+                    None => std::future::pending().await,
+                }
+            };
+
             tokio::select! {
                 biased;
 
@@ -69,13 +104,85 @@ where
                     return Ok(());
                 }
 
-                msg = self.socket.recv() => self.process_message(msg?).await?,
+                msg = self.socket.recv() => match msg {
+                    Ok(msg) => self.process_message(msg).await?,
+                    Err(error) => {
+                        tracing::warn!(%error, "ZMQ receive failed; reconnecting");
+                        if !self.reconnect_with_backoff().await {
+                            tracing::info!("Watcher shutting down gracefully");
+                            return Ok(());
+                        }
+                    }
+                },
+
+                _ = stalled => self.handle_stalled_feed().await,
             }
         }
     }
 
+    async fn handle_stalled_feed(&mut self) {
+        let idle = self.liveness_timeout.unwrap_or_default();
+        tracing::warn!(
+            ?idle,
+            "No ZMQ message within liveness timeout; reconnecting"
+        );
+        // Nobody listening for events is not an error.
+        let _ = self.events.send(WatcherEvent::FeedStalled { idle });
+
+        if let Err(error) = self.reconnect().await {
+            tracing::error!(%error, "Failed to reconnect ZMQ socket; retrying after next timeout");
+        }
+    }
+
+    /// Re-creates the socket until it succeeds, doubling the delay between
+    /// attempts. Returns `false` if shutdown was requested in the meantime.
+    async fn reconnect_with_backoff(&mut self) -> bool {
+        let mut delay = RECONNECT_INITIAL_BACKOFF;
+        let mut attempt = 0u32;
+
+        loop {
+            attempt = attempt.saturating_add(1);
+            let _ = self.events.send(WatcherEvent::Reconnecting { attempt });
+
+            let result = tokio::select! {
+                biased;
+
+                _ = self.shutdown.cancelled() => return false,
+                result = self.reconnect() => result,
+            };
+            match result {
+                Ok(()) => return true,
+                Err(error) => tracing::warn!(
+                    %error,
+                    attempt,
+                    ?delay,
+                    "Failed to reconnect ZMQ socket; backing off"
+                ),
+            }
+
+            tokio::select! {
+                biased;
+
+                _ = self.shutdown.cancelled() => return false,
+                _ = tokio::time::sleep(delay) => {}
+            }
+            delay = (delay * 2).min(RECONNECT_MAX_BACKOFF);
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<(), T> {
+        let mut socket = SubSocket::new();
+        socket.connect(&self.socket_url).await?;
+        for topic in &self.topics {
+            socket.subscribe(topic).await?;
+        }
+        self.socket = socket;
+        tracing::info!(url = %self.socket_url, "ZMQ socket reconnected");
+        Ok(())
+    }
+
     #[inline]
-    async fn process_message(&self, msg: ZmqMessage) -> Result<(), T> {
+    async fn process_message(&mut self, msg: ZmqMessage) -> Result<(), T> {
         if msg.len() < ZMQ_MESSAGE_MIN_FRAMES {
             tracing::debug!("ZMQ message without payload; skipping");
             return Ok(());
@@ -91,6 +198,8 @@ where
                 "topic frame is not valid UTF-8",
             ))
         })?;
+        crate::metrics::record_message(topic);
+
         let Some(payload) = &msg.get(ZMQ_PAYLOAD_FRAME_INDEX) else {
             tracing::warn!("Unable to get payload");
             return Ok(());
@@ -99,7 +208,22 @@ where
         let item = T::decode(topic, payload)?;
         tracing::debug!("Received item");
 
-        self.sender.send(item)?;
+        if item.resets_mempool_seq() {
+            self.mempool_seq.reset();
+        }
+        if let Some(mempool_seq) = item.mempool_seq()
+            && let Some(gap) = self.mempool_seq.observe(mempool_seq)
+        {
+            let _ = self.events.send(WatcherEvent::MempoolSequenceGap {
+                expected: gap.expected,
+                got: gap.got,
+            });
+        }
+
+        if self.sender.send(item).is_err() {
+            crate::metrics::WATCHER_UNDELIVERED_TOTAL.inc();
+            tracing::debug!("No subscriber for watcher item; dropping it");
+        }
 
         Ok(())
     }
@@ -145,8 +269,13 @@ mod tests {
 
         let watcher = Watcher::<Block> {
             socket,
+            socket_url: "tcp://localhost:28332".to_string(),
+            topics: vec![],
+            liveness_timeout: None,
             shutdown: shutdown.clone(),
             sender,
+            events: tokio::sync::broadcast::channel(1).0,
+            mempool_seq: MempoolSequenceTracker::default(),
         };
 
         assert!(!watcher.shutdown.is_cancelled());
@@ -162,8 +291,13 @@ mod tests {
 
         let watcher = Watcher::<Block> {
             socket,
+            socket_url: "tcp://localhost:28332".to_string(),
+            topics: vec![],
+            liveness_timeout: None,
             shutdown: shutdown1.clone(),
             sender,
+            events: tokio::sync::broadcast::channel(1).0,
+            mempool_seq: MempoolSequenceTracker::default(),
         };
 
         assert!(!watcher.shutdown.is_cancelled());
@@ -184,8 +318,13 @@ mod tests {
 
         let watcher = Watcher::<Transaction> {
             socket,
+            socket_url: "tcp://localhost:28332".to_string(),
+            topics: vec![],
+            liveness_timeout: None,
             shutdown,
             sender,
+            events: tokio::sync::broadcast::channel(1).0,
+            mempool_seq: MempoolSequenceTracker::default(),
         };
 
         assert_eq!(watcher.sender.receiver_count(), 0);
@@ -196,4 +335,55 @@ mod tests {
         let _receiver2 = watcher.sender.subscribe();
         assert_eq!(watcher.sender.receiver_count(), 2);
     }
+
+    #[tokio::test]
+    async fn test_stalled_feed_emits_event_and_reconnects() {
+        let mut publisher = zeromq::PubSocket::new();
+        let endpoint = publisher.bind("tcp://127.0.0.1:0").await.unwrap();
+
+        let shutdown = CancellationToken::new();
+        let handle = WatcherBuilder::<Block>::new(&endpoint.to_string(), shutdown.clone())
+            .with_liveness_timeout(Duration::from_millis(50))
+            .spawn()
+            .await
+            .unwrap();
+        let mut events = handle.subscribe_events();
+
+        let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .expect("stall detected")
+            .unwrap();
+        assert!(matches!(event, WatcherEvent::FeedStalled { .. }));
+
+        handle.shutdown();
+        assert!(handle.join().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_publisher_triggers_reconnect() {
+        let mut publisher = zeromq::PubSocket::new();
+        let endpoint = publisher.bind("tcp://127.0.0.1:0").await.unwrap();
+
+        let shutdown = CancellationToken::new();
+        let handle = WatcherBuilder::<Block>::new(&endpoint.to_string(), shutdown.clone())
+            .spawn()
+            .await
+            .unwrap();
+        let mut events = handle.subscribe_events();
+
+        // Give the subscriber time to finish the handshake before the peer goes away.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(publisher);
+
+        let event = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .expect("reconnect attempted")
+            .unwrap();
+        assert!(matches!(event, WatcherEvent::Reconnecting { attempt: 1 }));
+
+        // Shutdown must still work while the watcher keeps backing off.
+        handle.shutdown();
+        let joined = tokio::time::timeout(Duration::from_secs(1), handle.join()).await;
+        assert!(matches!(joined, Ok(Ok(()))));
+    }
 }
@@ -1,16 +1,22 @@
+use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 use zeromq::{Socket, SubSocket};
 
 use crate::{
     error::Result,
-    watch::{Decodable, Topics, Watcher, WatcherHandle},
+    sequence::MempoolSequenceTracker,
+    watch::{Decodable, FailoverPolicy, Topics, Watcher, WatcherEvent, WatcherHandle, failover},
 };
 
+const EVENT_CHANNEL_CAPACITY: usize = 16;
+
 /// Builder used for configuring and spawning watchers.
 pub struct WatcherBuilder<T> {
     socket_url: String,
     max_channel_capacity: usize,
     subscription_topics: Vec<String>,
+    liveness_timeout: Option<Duration>,
+    failover: Option<FailoverPolicy>,
     shutdown: CancellationToken,
     _marker: core::marker::PhantomData<T>,
 }
@@ -26,6 +32,8 @@ where
             socket_url: socket_url.to_string(),
             max_channel_capacity: MAX_CHANNEL_CAPACITY,
             subscription_topics: T::TOPICS.iter().map(|s| s.to_string()).collect(),
+            liveness_timeout: None,
+            failover: None,
             shutdown,
             _marker: core::marker::PhantomData,
         }
@@ -50,7 +58,58 @@ where
         self
     }
 
-    pub async fn spawn(self) -> Result<WatcherHandle<T>, T> {
+    /// Reconnect and emit [`WatcherEvent::FeedStalled`] when no message arrives
+    /// within `timeout`. Pick it according to the network's block interval.
+    pub fn with_liveness_timeout(mut self, timeout: Duration) -> Self {
+        self.liveness_timeout = Some(timeout);
+        self
+    }
+
+    /// Also watch a secondary endpoint and fail over to it while this one is
+    /// stale. See [`FailoverPolicy`].
+    pub fn with_failover(mut self, policy: FailoverPolicy) -> Self {
+        self.failover = Some(policy);
+        self
+    }
+
+    pub async fn spawn(mut self) -> Result<WatcherHandle<T>, T> {
+        let (events, _) = tokio::sync::broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        let Some(policy) = self.failover.take() else {
+            return self.spawn_worker(events).await;
+        };
+
+        let shutdown = self.shutdown.clone();
+        let workers = shutdown.child_token();
+        let capacity = self.max_channel_capacity;
+        let secondary = Self {
+            socket_url: policy.secondary_url.clone(),
+            max_channel_capacity: capacity,
+            subscription_topics: self.subscription_topics.clone(),
+            liveness_timeout: self.liveness_timeout,
+            failover: None,
+            shutdown: workers.clone(),
+            _marker: core::marker::PhantomData,
+        };
+        self.shutdown = workers.clone();
+
+        let primary = self.spawn_worker(events.clone()).await?;
+        let secondary = match secondary.spawn_worker(events.clone()).await {
+            Ok(secondary) => secondary,
+            Err(error) => {
+                workers.cancel();
+                return Err(error);
+            }
+        };
+
+        Ok(failover::spawn(
+            primary, secondary, policy, capacity, shutdown, workers, events,
+        ))
+    }
+
+    async fn spawn_worker(
+        self,
+        events: tokio::sync::broadcast::Sender<WatcherEvent>,
+    ) -> Result<WatcherHandle<T>, T> {
         let mut socket = SubSocket::new();
         socket.connect(&self.socket_url).await?;
         for topic in &self.subscription_topics {
@@ -61,16 +120,23 @@ where
 
         let mut worker = Watcher {
             socket,
+            socket_url: self.socket_url,
+            topics: self.subscription_topics,
+            liveness_timeout: self.liveness_timeout,
             shutdown: self.shutdown.clone(),
             sender: sender.clone(),
+            events: events.clone(),
+            mempool_seq: MempoolSequenceTracker::default(),
         };
 
         let join = tokio::spawn(async move { worker.watch().await });
 
         Ok(WatcherHandle {
             sender,
+            events,
             shutdown: self.shutdown,
             join,
+            lagged: Default::default(),
         })
     }
 }
@@ -90,6 +156,8 @@ mod tests {
         assert_eq!(builder.socket_url, "tcp://localhost:28332");
         assert_eq!(builder.max_channel_capacity, 1000);
         assert_eq!(builder.subscription_topics, Block::TOPICS);
+        assert!(builder.liveness_timeout.is_none());
+        assert!(builder.failover.is_none());
         assert!(!builder.shutdown.is_cancelled());
         assert!(!shutdown.is_cancelled());
     }
@@ -123,6 +191,15 @@ mod tests {
         assert_eq!(builder.max_channel_capacity, 500);
     }
 
+    #[test]
+    fn test_with_liveness_timeout_sets_timeout() {
+        let shutdown = CancellationToken::new();
+        let builder = WatcherBuilder::<Block>::new("tcp://localhost:28332", shutdown)
+            .with_liveness_timeout(Duration::from_secs(600));
+
+        assert_eq!(builder.liveness_timeout, Some(Duration::from_secs(600)));
+    }
+
     #[test]
     fn test_with_topic_sets_topic() {
         let shutdown = CancellationToken::new();
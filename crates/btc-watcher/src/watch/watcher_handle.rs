@@ -1,8 +1,13 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::broadcast::error::RecvError;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
     error::Result,
-    watch::{Decodable, Topics, WatcherBuilder},
+    watch::{Decodable, Topics, WatcherBuilder, WatcherEvent},
 };
 
 /// Handle to an active watcher.
@@ -11,16 +16,73 @@ where
     T: Clone + core::fmt::Debug,
 {
     pub(crate) sender: tokio::sync::broadcast::Sender<T>,
+    pub(crate) events: tokio::sync::broadcast::Sender<WatcherEvent>,
     pub(crate) shutdown: CancellationToken,
     pub(crate) join: tokio::task::JoinHandle<Result<(), T>>,
+    pub(crate) lagged: Arc<AtomicU64>,
+}
+
+/// Item received from a watcher subscription.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WatcherMessage<T> {
+    Item(T),
+    /// The subscriber fell behind and this many items were skipped.
+    Lagged(u64),
+}
+
+/// Subscription to a watcher that reports skipped items instead of hiding them.
+pub struct WatcherReceiver<T> {
+    receiver: tokio::sync::broadcast::Receiver<T>,
+    lagged: Arc<AtomicU64>,
+}
+
+impl<T: Clone> WatcherReceiver<T> {
+    /// Receives the next item. When the subscriber lagged behind the channel
+    /// capacity, [`WatcherMessage::Lagged`] is returned once with the number of
+    /// skipped items and receiving continues with the oldest retained item.
+    ///
+    /// Fails only when the watcher has stopped.
+    pub async fn recv(&mut self) -> core::result::Result<WatcherMessage<T>, RecvError> {
+        match self.receiver.recv().await {
+            Ok(item) => Ok(WatcherMessage::Item(item)),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "Watcher subscriber lagged; items were dropped");
+                self.lagged.fetch_add(skipped, Ordering::Relaxed);
+                crate::metrics::WATCHER_LAGGED_TOTAL.inc_by(skipped);
+                Ok(WatcherMessage::Lagged(skipped))
+            }
+            Err(RecvError::Closed) => Err(RecvError::Closed),
+        }
+    }
+
+    /// Number of items waiting to be received.
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
 }
 
 impl<T> WatcherHandle<T>
 where
     T: Topics + Decodable + Send + Clone + 'static + core::fmt::Debug,
 {
-    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<T> {
-        self.sender.subscribe()
+    pub fn subscribe(&self) -> WatcherReceiver<T> {
+        WatcherReceiver {
+            receiver: self.sender.subscribe(),
+            lagged: self.lagged.clone(),
+        }
+    }
+
+    /// Total number of items skipped by lagging subscribers of this watcher.
+    pub fn lagged_count(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<WatcherEvent> {
+        self.events.subscribe()
     }
 
     pub fn shutdown(&self) {
@@ -36,7 +98,7 @@ where
         socket_url: &str,
         shutdown: CancellationToken,
         max_channel_capacity: usize,
-    ) -> Result<(Self, tokio::sync::broadcast::Receiver<T>), T> {
+    ) -> Result<(Self, WatcherReceiver<T>), T> {
         let handle = WatcherBuilder::<T>::new(socket_url, shutdown)
             .with_capacity(max_channel_capacity)
             .spawn()
@@ -60,8 +122,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown: shutdown.clone(),
             join,
+            lagged: Default::default(),
         };
 
         // Test that handle contains the shutdown token
@@ -76,8 +141,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown,
             join,
+            lagged: Default::default(),
         };
 
         let receiver = handle.subscribe();
@@ -94,8 +162,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown: shutdown.clone(),
             join,
+            lagged: Default::default(),
         };
 
         assert!(!shutdown.is_cancelled());
@@ -113,8 +184,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown,
             join,
+            lagged: Default::default(),
         };
 
         let result = handle.join().await;
@@ -131,8 +205,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown,
             join,
+            lagged: Default::default(),
         };
 
         let result = handle.join().await;
@@ -147,8 +224,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown,
             join,
+            lagged: Default::default(),
         };
 
         let receiver1 = handle.subscribe();
@@ -182,8 +262,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown: shutdown.clone(),
             join,
+            lagged: Default::default(),
         };
 
         // Should already be cancelled
@@ -200,8 +283,11 @@ mod tests {
 
         let handle = WatcherHandle {
             sender,
+
+            events: tokio::sync::broadcast::channel(1).0,
             shutdown: parent_shutdown.clone(),
             join,
+            lagged: Default::default(),
         };
 
         assert!(!child_shutdown.is_cancelled());
@@ -222,6 +308,8 @@ mod tests {
 
         let handle = WatcherHandle {
             sender: sender.clone(),
+            events: tokio::sync::broadcast::channel(1).0,
+            lagged: Default::default(),
             shutdown,
             join,
         };
@@ -246,7 +334,45 @@ mod tests {
         // Receiver should get the message
         let received = timeout(Duration::from_millis(100), receiver.recv()).await;
         assert!(received.is_ok());
-        let received_block = received.unwrap().unwrap();
+        let WatcherMessage::Item(received_block) = received.unwrap().unwrap() else {
+            panic!("expected an item");
+        };
         assert_eq!(received_block.header.nonce, test_block.header.nonce);
     }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_is_notified() {
+        use crate::sequence::{Sequence, SequenceEvent};
+
+        let (sender, _) = tokio::sync::broadcast::channel::<Sequence>(2);
+        let handle = WatcherHandle {
+            sender: sender.clone(),
+            events: tokio::sync::broadcast::channel(1).0,
+            shutdown: CancellationToken::new(),
+            join: tokio::spawn(async { Ok(()) }),
+            lagged: Default::default(),
+        };
+        let mut receiver = handle.subscribe();
+
+        for seq in 1..=5 {
+            sender
+                .send(Sequence {
+                    hash_bytes: [0; 32],
+                    event: SequenceEvent::TxAdded,
+                    mempool_seq: Some(seq),
+                })
+                .unwrap();
+        }
+
+        assert!(matches!(
+            receiver.recv().await.unwrap(),
+            WatcherMessage::Lagged(3)
+        ));
+        assert_eq!(handle.lagged_count(), 3);
+
+        let WatcherMessage::Item(item) = receiver.recv().await.unwrap() else {
+            panic!("expected an item after the lag notification");
+        };
+        assert_eq!(item.mempool_seq, Some(4));
+    }
 }
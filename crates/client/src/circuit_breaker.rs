@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::Url;
+
+use crate::{
+    constants::{CIRCUIT_BREAKER_COOLDOWN, CIRCUIT_BREAKER_FAILURE_THRESHOLD},
+    error::Error,
+    utils::is_endpoint_failure,
+};
+
+/// When an endpoint is taken out of rotation and for how long.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed requests after which the endpoint's circuit opens.
+    pub failure_threshold: u32,
+    /// How long an open circuit skips the endpoint before it is probed again.
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            cooldown: CIRCUIT_BREAKER_COOLDOWN,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// When the half-open circuit let its probe request through.
+    probe_started_at: Option<Instant>,
+}
+
+impl Circuit {
+    /// Whether a request may go to the endpoint, taking the probe permit when
+    /// the circuit is half-open.
+    fn admit(&mut self, cooldown: Duration) -> bool {
+        let Some(opened_at) = self.opened_at else {
+            return true;
+        };
+        if opened_at.elapsed() < cooldown {
+            return false;
+        }
+        // A probe whose outcome was never recorded, e.g. because failover
+        // stopped before reaching the URL, gives up its permit after another
+        // cooldown.
+        if self
+            .probe_started_at
+            .is_some_and(|started_at| started_at.elapsed() < cooldown)
+        {
+            return false;
+        }
+        self.probe_started_at = Some(Instant::now());
+        true
+    }
+}
+
+/// Per-URL circuit breakers shared by every request of a client.
+///
+/// A circuit opens after `failure_threshold` consecutive failures and the URL
+/// is skipped until `cooldown` has passed. After that the circuit is half-open:
+/// a single request probes the URL, closing the circuit on success and
+/// reopening it on failure, while concurrent requests keep skipping it.
+#[derive(Debug)]
+pub(crate) struct CircuitBreakers {
+    config: CircuitBreakerConfig,
+    circuits: Mutex<HashMap<Url, Circuit>>,
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            circuits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the URLs whose circuit is closed or half-open, keeping their
+    /// order. When every circuit is open all URLs are returned, so a full
+    /// outage still gets probed instead of failing without a request.
+    pub(crate) fn available<'a>(&self, urls: &'a [Url]) -> Vec<&'a Url> {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        let available: Vec<_> = urls
+            .iter()
+            .filter(|url| {
+                circuits
+                    .get_mut(*url)
+                    .is_none_or(|circuit| circuit.admit(self.config.cooldown))
+            })
+            .collect();
+
+        if available.is_empty() {
+            urls.iter().collect()
+        } else {
+            available
+        }
+    }
+
+    /// Records the outcome of a request to `url`. Only errors that point at
+    /// the endpoint itself count as failures; JSON-RPC error responses do not.
+    pub(crate) fn record<T>(&self, url: &Url, result: &Result<T, Error>) {
+        match result {
+            Ok(_) => self.record_success(url),
+            Err(error) if is_endpoint_failure(error) => self.record_failure(url),
+            Err(_) => {}
+        }
+    }
+
+    fn record_success(&self, url: &Url) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(circuit) = circuits.remove(url)
+            && circuit.opened_at.is_some()
+        {
+            tracing::info!(url = %url, "Endpoint recovered, closing circuit");
+        }
+    }
+
+    fn record_failure(&self, url: &Url) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        let circuit = circuits.entry(url.clone()).or_default();
+        circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+        if circuit.consecutive_failures >= self.config.failure_threshold {
+            if circuit.opened_at.is_none() {
+                tracing::warn!(
+                    url = %url,
+                    failures = circuit.consecutive_failures,
+                    cooldown = ?self.config.cooldown,
+                    "Endpoint keeps failing, opening circuit"
+                );
+            }
+            circuit.opened_at = Some(Instant::now());
+            circuit.probe_started_at = None;
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_open(&self, url: &Url) -> bool {
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(url)
+            .and_then(|circuit| circuit.opened_at)
+            .is_some_and(|opened_at| opened_at.elapsed() < self.config.cooldown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breakers(failure_threshold: u32, cooldown: Duration) -> CircuitBreakers {
+        CircuitBreakers::new(CircuitBreakerConfig {
+            failure_threshold,
+            cooldown,
+        })
+    }
+
+    fn urls() -> Vec<Url> {
+        vec![
+            Url::parse("http://127.0.0.1:1").unwrap(),
+            Url::parse("http://127.0.0.1:2").unwrap(),
+        ]
+    }
+
+    #[test]
+    fn opens_after_threshold_and_skips_url() {
+        let breakers = breakers(2, Duration::from_secs(60));
+        let urls = urls();
+
+        breakers.record::<()>(&urls[0], &Err(Error::TimeOut));
+        assert_eq!(breakers.available(&urls).len(), 2);
+
+        breakers.record::<()>(&urls[0], &Err(Error::TimeOut));
+        assert_eq!(breakers.available(&urls), vec![&urls[1]]);
+    }
+
+    #[test]
+    fn rpc_errors_do_not_count_as_failures() {
+        let breakers = breakers(1, Duration::from_secs(60));
+        let urls = urls();
+
+        breakers.record::<()>(
+            &urls[0],
+            &Err(Error::JsonRpc {
+                code: -32603,
+                message: "Internal Error".into(),
+                data: None,
+            }),
+        );
+
+        assert_eq!(breakers.available(&urls).len(), 2);
+    }
+
+    #[test]
+    fn half_open_after_cooldown_and_closes_on_success() {
+        let breakers = breakers(1, Duration::from_millis(20));
+        let urls = urls();
+
+        breakers.record::<()>(&urls[0], &Err(Error::TimeOut));
+        assert_eq!(breakers.available(&urls), vec![&urls[1]]);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breakers.available(&urls).len(), 2);
+
+        // A failed probe reopens the circuit right away.
+        breakers.record::<()>(&urls[0], &Err(Error::TimeOut));
+        assert_eq!(breakers.available(&urls), vec![&urls[1]]);
+
+        std::thread::sleep(Duration::from_millis(30));
+        breakers.record(&urls[0], &Ok(()));
+        assert!(breakers.circuits.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn half_open_admits_a_single_probe() {
+        let breakers = breakers(1, Duration::from_millis(20));
+        let urls = urls();
+
+        breakers.record::<()>(&urls[0], &Err(Error::TimeOut));
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(breakers.available(&urls).len(), 2);
+        // The probe is in flight, so a concurrent request skips the URL.
+        assert_eq!(breakers.available(&urls), vec![&urls[1]]);
+
+        // A probe that never reports back frees its permit after a cooldown.
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breakers.available(&urls).len(), 2);
+        assert_eq!(breakers.available(&urls), vec![&urls[1]]);
+    }
+
+    #[test]
+    fn all_open_falls_back_to_every_url() {
+        let breakers = breakers(1, Duration::from_secs(60));
+        let urls = urls();
+
+        for url in &urls {
+            breakers.record::<()>(url, &Err(Error::TimeOut));
+        }
+
+        assert_eq!(breakers.available(&urls).len(), 2);
+    }
+}
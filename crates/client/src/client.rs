@@ -1,14 +1,30 @@
 use crate::{
-    constants::DEFAULT_TIMEOUT,
+    circuit_breaker::{CircuitBreakerConfig, CircuitBreakers},
+    constants::{BLOCK_RANGE_CONCURRENCY, DEFAULT_TIMEOUT},
     error::{Error, Result},
-    request_builder::RequestBuilder,
+    request_builder::{BatchRequestBuilder, RequestBuilder},
     retry_config::RetryConfig,
-    types::{JobId, ProofResponse, ProverData},
+    types::{JobId, ProofResponse, ProofResult, ProverData},
     utils::parse_urls,
 };
+use ethrex_common::{Address, H256, types::Block};
+use futures::{Stream, StreamExt, stream};
+use guest_program::input::ProgramInput;
 use mojave_signature::SigningKey;
 use reqwest::{ClientBuilder, Url};
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Invoked after every completed client request with the method name, the
+/// elapsed time (including retries) and the outcome.
+pub type RequestHook = Arc<dyn Fn(&str, Duration, &Result<()>) + Send + Sync>;
 
 #[derive(Default)]
 pub struct MojaveClientBuilder {
@@ -18,6 +34,9 @@ pub struct MojaveClientBuilder {
     private_key: Option<String>,
     timeout: Duration,
     retry_config: RetryConfig,
+    on_request: Option<RequestHook>,
+    block_range_concurrency: usize,
+    circuit_breaker: CircuitBreakerConfig,
 }
 
 impl MojaveClientBuilder {
@@ -25,6 +44,8 @@ impl MojaveClientBuilder {
         Self {
             timeout: DEFAULT_TIMEOUT,
             retry_config: RetryConfig::default(),
+            block_range_concurrency: BLOCK_RANGE_CONCURRENCY,
+            circuit_breaker: CircuitBreakerConfig::default(),
             ..Default::default()
         }
     }
@@ -71,6 +92,37 @@ impl MojaveClientBuilder {
         self
     }
 
+    pub fn on_request(mut self, hook: RequestHook) -> Self {
+        self.on_request = Some(hook);
+        self
+    }
+
+    /// Number of blocks [`MojaveClient::get_blocks_range`] fetches concurrently.
+    /// Values below one are treated as one.
+    pub fn block_range_concurrency(mut self, concurrency: usize) -> Self {
+        self.block_range_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Failure threshold and cool-down for taking a failing endpoint out of
+    /// rotation. See [`CircuitBreakerConfig`].
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = config;
+        self
+    }
+
+    /// Like [`Self::build`], but fails with [`Error::NoRPCUrlsConfigured`] when
+    /// no sequencer, full node or prover URL is set.
+    pub fn build_validated(self) -> Result<MojaveClient> {
+        if self.sequencer_urls.is_empty()
+            && self.full_node_urls.is_empty()
+            && self.prover_urls.is_empty()
+        {
+            return Err(Error::NoRPCUrlsConfigured);
+        }
+        self.build()
+    }
+
     pub fn build(self) -> Result<MojaveClient> {
         let http_client = ClientBuilder::new().timeout(self.timeout).build()?;
 
@@ -88,7 +140,11 @@ impl MojaveClientBuilder {
                 full_node_urls: parse_urls(self.full_node_urls)?,
                 prover_urls: parse_urls(self.prover_urls)?,
                 retry_config: self.retry_config,
-                _signing_key: signing_key,
+                on_request: self.on_request,
+                block_range_concurrency: self.block_range_concurrency.max(1),
+                circuit_breakers: CircuitBreakers::new(self.circuit_breaker),
+                last_nonce: AtomicU64::new(0),
+                signing_key,
             }),
         })
     }
@@ -99,14 +155,33 @@ pub struct MojaveClient {
     pub(crate) inner: Arc<MojaveClientInner>,
 }
 
-#[derive(Debug)]
 pub(crate) struct MojaveClientInner {
     pub(crate) client: reqwest::Client,
     pub(crate) sequencer_urls: Vec<Url>,
     pub(crate) full_node_urls: Vec<Url>,
     pub(crate) prover_urls: Vec<Url>,
     pub(crate) retry_config: RetryConfig,
-    _signing_key: Option<SigningKey>,
+    pub(crate) on_request: Option<RequestHook>,
+    pub(crate) block_range_concurrency: usize,
+    pub(crate) circuit_breakers: CircuitBreakers,
+    /// Last nonce attached to a proof submission.
+    last_nonce: AtomicU64,
+    pub(crate) signing_key: Option<SigningKey>,
+}
+
+impl fmt::Debug for MojaveClientInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MojaveClientInner")
+            .field("client", &self.client)
+            .field("sequencer_urls", &self.sequencer_urls)
+            .field("full_node_urls", &self.full_node_urls)
+            .field("prover_urls", &self.prover_urls)
+            .field("retry_config", &self.retry_config)
+            .field("on_request", &self.on_request.is_some())
+            .field("block_range_concurrency", &self.block_range_concurrency)
+            .field("circuit_breakers", &self.circuit_breakers)
+            .finish_non_exhaustive()
+    }
 }
 
 impl MojaveClient {
@@ -126,6 +201,24 @@ impl MojaveClient {
         &self.inner.prover_urls
     }
 
+    /// Returns the nonce for the next proof submission: the current Unix time
+    /// in milliseconds, bumped when needed so it always grows, even across
+    /// calls within the same millisecond.
+    pub(crate) fn next_nonce(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let previous = self
+            .inner
+            .last_nonce
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_else(|last| last);
+        now.max(previous + 1)
+    }
+
     pub fn request(&self) -> RequestBuilder<'_> {
         RequestBuilder::new(self)
     }
@@ -141,6 +234,26 @@ impl MojaveClient {
             .await
     }
 
+    /// Idempotently ensures the prover has a job for `batch_number`.
+    ///
+    /// Returns the id of the pending or proven job if one exists, otherwise
+    /// the prover enqueues the input again (e.g. after a failed proof).
+    pub async fn resubmit_proof(
+        &self,
+        batch_number: u64,
+        proof_input: ProgramInput,
+        sequencer_address: &str,
+    ) -> Result<JobId> {
+        let proof_input = ProverData {
+            batch_number,
+            input: proof_input,
+        };
+        self.request()
+            .with_provers()
+            .resubmit_proof(&proof_input, sequencer_address)
+            .await
+    }
+
     pub async fn get_pending_job_ids(&self) -> Result<Vec<JobId>> {
         self.request().with_provers().get_pending_job_ids().await
     }
@@ -148,6 +261,101 @@ impl MojaveClient {
     pub async fn get_proof(&self, job_id: JobId) -> Result<ProofResponse> {
         self.request().with_provers().get_proof(job_id).await
     }
+
+    /// Polls [`Self::get_proof`] every `poll_interval` until the job has a
+    /// proof or has failed. Each poll keeps the client's request timeout;
+    /// after `overall_timeout` it gives up with [`Error::ProofTimeout`].
+    pub async fn wait_for_proof(
+        &self,
+        job_id: JobId,
+        poll_interval: Duration,
+        overall_timeout: Duration,
+    ) -> Result<ProofResponse> {
+        let poll = async {
+            loop {
+                let response = self.get_proof(job_id.clone()).await?;
+                if !matches!(response.result, ProofResult::NotReady { .. }) {
+                    return Ok(response);
+                }
+                tracing::debug!(job_id = %job_id, "Proof not ready yet");
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        tokio::time::timeout(overall_timeout, poll)
+            .await
+            .map_err(|_| Error::ProofTimeout(job_id.clone()))?
+    }
+
+    /// Latest block number of the full nodes.
+    pub async fn get_block_number(&self) -> Result<u64> {
+        self.request().with_full_nodes().get_block_number().await
+    }
+
+    /// Transaction count (nonce) of `address` at the latest block.
+    pub async fn get_transaction_count(&self, address: Address) -> Result<u64> {
+        self.request()
+            .with_full_nodes()
+            .get_transaction_count(address)
+            .await
+    }
+
+    /// Sends an RLP-encoded signed transaction to the full nodes and returns
+    /// its hash.
+    pub async fn send_raw_transaction(&self, encoded: &[u8]) -> Result<H256> {
+        self.request()
+            .with_full_nodes()
+            .send_raw_transaction(encoded)
+            .await
+    }
+
+    /// Starts a batch of calls sent in a single round trip, e.g.
+    /// `client.batch().get_proof(a).get_proof(b).send()`.
+    pub fn batch<T: serde::de::DeserializeOwned>(&self) -> BatchRequestBuilder<'_, T> {
+        BatchRequestBuilder::new(self)
+    }
+
+    /// Streams blocks `from..=to` from the full nodes, in order.
+    ///
+    /// Up to the builder's `block_range_concurrency` requests are in flight at
+    /// once and consecutive blocks start at different full nodes, falling back
+    /// to the others when one fails.
+    pub fn get_blocks_range(&self, from: u64, to: u64) -> impl Stream<Item = Result<Block>> + '_ {
+        stream::iter(from..=to)
+            .map(move |number| self.get_block_balanced(number))
+            .buffered(self.inner.block_range_concurrency)
+    }
+
+    async fn get_block_balanced(&self, number: u64) -> Result<Block> {
+        let urls = &self.inner.full_node_urls;
+        if urls.is_empty() {
+            return Err(Error::NoRPCUrlsConfigured);
+        }
+        let first = (number % urls.len() as u64) as usize;
+        let urls: Vec<Url> = urls[first..]
+            .iter()
+            .chain(&urls[..first])
+            .cloned()
+            .collect();
+
+        match self
+            .request()
+            .with_urls(&urls)
+            .get_block_by_number(number)
+            .await
+        {
+            // A node that only serves transaction hashes cannot fill the gap;
+            // ask the other full nodes before giving up.
+            Err(Error::BlockWithoutTransactions(_)) if urls.len() > 1 => {
+                tracing::warn!(number, url = %urls[0], "Full node returned only transaction hashes, asking the others");
+                self.request()
+                    .with_urls(&urls[1..])
+                    .get_block_by_number(number)
+                    .await
+            }
+            result => result,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -188,7 +396,8 @@ mod tests {
                             } else {
                                 Err(RpcErr::Internal(format!(
                                     "Method '{method}' did not match expected '{matcher}'",
-                                )))
+                                ))
+                                .into())
                             }
                         }
                         Behavior::SleepThenOk(duration, matcher, val) => {
@@ -198,16 +407,21 @@ mod tests {
                             } else {
                                 Err(RpcErr::Internal(format!(
                                     "Method '{method}' did not match expected '{matcher}'",
-                                )))
+                                ))
+                                .into())
                             }
                         }
                         Behavior::JsonRpcInternalError(msg) => {
-                            Err(RpcErr::Internal(msg.to_string()))
+                            Err(RpcErr::Internal(msg.to_string()).into())
                         }
                     }
                 })
             });
 
+            Self::spawn_registry(reg).await
+        }
+
+        pub async fn spawn_registry(reg: RpcRegistry<()>) -> Self {
             let service = RpcService::new((), reg);
 
             let port = pick_free_port().unwrap_or(0);
@@ -268,9 +482,26 @@ mod tests {
             ])
             .build();
 
-        // This error requires that each url within the vector be propagated as an error individually.
-        // and not just a custom "empty host" error but a specific InvalidUrl error.
-        assert!(matches!(res, Err(Error::Custom(_))));
+        // The first malformed entry is reported by name.
+        match res {
+            Err(Error::InvalidUrl { url, source }) => {
+                assert_eq!(url, "http://:://not-valid");
+                assert_eq!(source, url::ParseError::EmptyHost);
+            }
+            other => panic!("expected InvalidUrl, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn build_validated_requires_some_url() {
+        let res = MojaveClient::builder().build_validated();
+        assert!(matches!(res, Err(Error::NoRPCUrlsConfigured)));
+
+        let client = MojaveClient::builder()
+            .full_node_urls(vec!["http://127.0.0.1:1"])
+            .build_validated()
+            .unwrap();
+        assert_eq!(client.full_node_urls().len(), 1);
     }
 
     #[test]
@@ -304,6 +535,25 @@ mod tests {
         assert_eq!(client.inner.retry_config.max_delay, cfg.max_delay);
     }
 
+    #[test]
+    fn block_range_concurrency_is_applied() {
+        let client = MojaveClient::builder()
+            .full_node_urls(vec!["http://127.0.0.1:1"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.inner.block_range_concurrency,
+            BLOCK_RANGE_CONCURRENCY
+        );
+
+        let client = MojaveClient::builder()
+            .full_node_urls(vec!["http://127.0.0.1:1"])
+            .block_range_concurrency(0)
+            .build()
+            .unwrap();
+        assert_eq!(client.inner.block_range_concurrency, 1);
+    }
+
     #[tokio::test]
     async fn builder_sets_urls_from_accessors() {
         let p1 = "http://127.0.0.1:12345";
@@ -349,6 +599,39 @@ mod tests {
         assert!(res.unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn on_request_hook_reports_method_and_outcome() {
+        use std::sync::Mutex;
+
+        let server = TestRpc::spawn(Behavior::Ok("moj_getPendingJobIds", json!([]))).await;
+        let calls: Arc<Mutex<Vec<(String, bool)>>> = Arc::default();
+        let recorded = calls.clone();
+
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .on_request(Arc::new(move |method, _elapsed, outcome| {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((method.to_string(), outcome.is_ok()));
+            }))
+            .build()
+            .unwrap();
+
+        client.get_pending_job_ids().await.unwrap();
+        client.get_proof("job-1".into()).await.unwrap_err();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(
+            *calls,
+            vec![
+                ("moj_getPendingJobIds".to_string(), true),
+                ("moj_getProof".to_string(), false),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn get_pending_job_ids_jsonrpc_error_is_propagated() {
         let server = TestRpc::spawn(Behavior::JsonRpcInternalError("boom")).await;
@@ -466,6 +749,156 @@ mod tests {
         assert_eq!(job_id, "job-42".into());
     }
 
+    #[tokio::test]
+    async fn fails_over_to_next_prover_when_first_is_unreachable() {
+        let service = TestRpc::spawn(Behavior::Ok("moj_sendProofInput", json!("job-42"))).await;
+
+        let client = MojaveClient::builder()
+            .prover_urls(vec![
+                "http://127.0.0.1:1".to_string(),
+                service.url().to_string(),
+            ])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        let proof_in = ProverData {
+            batch_number: 1,
+            input: guest_program::input::ProgramInput::default(),
+        };
+
+        let job_id = client.send_proof_input(&proof_in, "0xabc").await.unwrap();
+
+        assert_eq!(job_id, "job-42".into());
+    }
+
+    #[tokio::test]
+    async fn jsonrpc_error_does_not_fail_over() {
+        let failing = TestRpc::spawn(Behavior::JsonRpcInternalError("boom")).await;
+        let healthy = TestRpc::spawn(Behavior::Ok("moj_getPendingJobIds", json!([]))).await;
+
+        let client = MojaveClient::builder()
+            .prover_urls(vec![failing.url().to_string(), healthy.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+
+        let err = client.get_pending_job_ids().await.unwrap_err();
+
+        assert!(matches!(err, Error::JsonRpc { ref message, .. } if message.contains("boom")));
+    }
+
+    #[tokio::test]
+    async fn failing_prover_is_skipped_until_cooldown_passes() {
+        let service = TestRpc::spawn(Behavior::Ok("moj_sendProofInput", json!("job-42"))).await;
+        let dead = "http://127.0.0.1:1/";
+
+        let client = MojaveClient::builder()
+            .prover_urls(vec![dead.to_string(), service.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .circuit_breaker(CircuitBreakerConfig {
+                failure_threshold: 1,
+                cooldown: Duration::from_millis(200),
+            })
+            .build()
+            .unwrap();
+        let dead = Url::parse(dead).unwrap();
+        let proof_in = ProverData {
+            batch_number: 1,
+            input: guest_program::input::ProgramInput::default(),
+        };
+
+        let job_id = client.send_proof_input(&proof_in, "0xabc").await.unwrap();
+        assert_eq!(job_id, "job-42".into());
+        assert!(client.inner.circuit_breakers.is_open(&dead));
+        assert_eq!(
+            client
+                .inner
+                .circuit_breakers
+                .available(client.prover_urls())
+                .len(),
+            1
+        );
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(
+            client
+                .inner
+                .circuit_breakers
+                .available(client.prover_urls())
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn send_proof_input_is_signed_with_private_key() {
+        use crate::types::{ProofInputMessage, RequestSignature};
+        use mojave_signature::types::Verifier;
+
+        let captured = Arc::new(std::sync::Mutex::new(None));
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        let params = captured.clone();
+        reg.register_fn("moj_sendProofInput", move |req, _| {
+            *params.lock().unwrap() = req.params.clone();
+            Box::pin(async { Ok(json!("job-42")) })
+        });
+        let service = TestRpc::spawn_registry(reg).await;
+
+        // Anvil account 0.
+        let client = MojaveClient::builder()
+            .prover_urls(vec![service.url().to_string()])
+            .private_key("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+            .build()
+            .unwrap();
+        let proof_in = ProverData {
+            batch_number: 1,
+            input: guest_program::input::ProgramInput::default(),
+        };
+        client
+            .send_proof_input(&proof_in, "http://sequencer:1234")
+            .await
+            .unwrap();
+
+        let params = captured.lock().unwrap().take().unwrap();
+        assert_eq!(params.len(), 4);
+        let sequencer_addr: String = serde_json::from_value(params[1].clone()).unwrap();
+        let nonce: u64 = serde_json::from_value(params[2].clone()).unwrap();
+        let signed: RequestSignature = serde_json::from_value(params[3].clone()).unwrap();
+        assert_eq!(
+            signed.verifying_key.to_address(),
+            "f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+        signed
+            .verifying_key
+            .verify(
+                &ProofInputMessage {
+                    prover_data: &proof_in,
+                    sequencer_addr: &sequencer_addr,
+                    nonce: Some(nonce),
+                },
+                &signed.signature,
+            )
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn resubmit_proof_ok() {
+        let service = TestRpc::spawn(Behavior::Ok("moj_resubmitProofInput", json!("job-42"))).await;
+
+        let client = MojaveClient::builder()
+            .prover_urls(vec![service.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+
+        let job_id = client
+            .resubmit_proof(1, guest_program::input::ProgramInput::default(), "0xabc")
+            .await
+            .unwrap();
+
+        assert_eq!(job_id, "job-42".into());
+    }
+
     #[tokio::test]
     async fn send_proof_input_failed_with_delay() {
         let service = TestRpc::spawn(Behavior::SleepThenOk(
@@ -491,4 +924,364 @@ mod tests {
 
         assert!(s.contains("timedout"));
     }
+
+    #[test]
+    fn next_nonce_strictly_increases() {
+        let client = MojaveClient::builder().build().unwrap();
+        let first = client.next_nonce();
+        let second = client.next_nonce();
+        assert!(first > 0);
+        assert!(second > first);
+    }
+
+    #[tokio::test]
+    async fn batch_get_proof_returns_results_by_index() {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_getProof", |req, _| {
+            let job_id: String =
+                serde_json::from_value(req.params.as_ref().unwrap()[0].clone()).unwrap();
+            Box::pin(async move {
+                match job_id.as_str() {
+                    "job-1" | "job-2" => Ok(serde_json::to_value(ProofResponse {
+                        batch_number: job_id[4..].parse().unwrap(),
+                        job_id: job_id.into(),
+                        result: ProofResult::Error("dummy".to_string()),
+                    })
+                    .unwrap()),
+                    _ => Err(RpcErr::BadParams("unknown job".into()).into()),
+                }
+            })
+        });
+        let server = TestRpc::spawn_registry(reg).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .build()
+            .unwrap();
+
+        let results = client
+            .batch()
+            .get_proof("job-2".into())
+            .get_proof("job-x".into())
+            .get_proof("job-1".into())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().batch_number, 2);
+        assert!(matches!(
+            results[1],
+            Err(Error::JsonRpc { code: -32602, .. })
+        ));
+        assert_eq!(results[2].as_ref().unwrap().batch_number, 1);
+    }
+
+    #[tokio::test]
+    async fn eth_helpers_parse_hex_results() {
+        let address = Address::from_low_u64_be(0xabc);
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("eth_blockNumber", |_req, _| {
+            Box::pin(async { Ok(json!("0x2a")) })
+        });
+        reg.register_fn("eth_getTransactionCount", move |req, _| {
+            let params = req.params.clone().unwrap();
+            Box::pin(async move {
+                assert_eq!(params, vec![json!(address), json!("latest")]);
+                Ok(json!("0x5"))
+            })
+        });
+        reg.register_fn("eth_sendRawTransaction", |req, _| {
+            let params = req.params.clone().unwrap();
+            Box::pin(async move {
+                assert_eq!(params, vec![json!("0x02ab")]);
+                Ok(json!(H256::repeat_byte(0x11)))
+            })
+        });
+        let server = TestRpc::spawn_registry(reg).await;
+        let client = MojaveClient::builder()
+            .full_node_urls(vec![server.url().to_string()])
+            .build()
+            .unwrap();
+
+        assert_eq!(client.get_block_number().await.unwrap(), 42);
+        assert_eq!(client.get_transaction_count(address).await.unwrap(), 5);
+        assert_eq!(
+            client.send_raw_transaction(&[0x02, 0xab]).await.unwrap(),
+            H256::repeat_byte(0x11)
+        );
+    }
+
+    /// Prover whose `moj_getProof` reports the job as not ready for the first
+    /// `pending` polls and as failed afterwards.
+    async fn spawn_proving_prover(pending: usize) -> TestRpc {
+        let polls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_getProof", move |_req, _| {
+            let poll = polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let result = if poll < pending {
+                ProofResult::NotReady { progress: None }
+            } else {
+                ProofResult::Error("dummy".to_string())
+            };
+            Box::pin(async move {
+                Ok(serde_json::to_value(ProofResponse {
+                    job_id: "job-1".into(),
+                    batch_number: 7,
+                    result,
+                })
+                .unwrap())
+            })
+        });
+        TestRpc::spawn_registry(reg).await
+    }
+
+    #[tokio::test]
+    async fn wait_for_proof_polls_until_done() {
+        let server = spawn_proving_prover(2).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .build()
+            .unwrap();
+
+        let response = client
+            .wait_for_proof(
+                "job-1".into(),
+                Duration::from_millis(10),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(response.result, ProofResult::Error(_)));
+    }
+
+    #[tokio::test]
+    async fn wait_for_proof_times_out() {
+        let server = spawn_proving_prover(usize::MAX).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .build()
+            .unwrap();
+
+        let err = client
+            .wait_for_proof(
+                "job-1".into(),
+                Duration::from_millis(10),
+                Duration::from_millis(100),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::ProofTimeout(ref job_id) if job_id.as_str() == "job-1"));
+    }
+
+    /// Prover whose `moj_getPendingJobIds` fails with `error` for the first
+    /// `failures` calls, recording when each call arrived.
+    async fn spawn_flaky_prover(
+        failures: usize,
+        error: fn() -> RpcErr,
+        calls: Arc<std::sync::Mutex<Vec<std::time::Instant>>>,
+    ) -> TestRpc {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_getPendingJobIds", move |_req, _| {
+            let mut calls = calls.lock().unwrap();
+            calls.push(std::time::Instant::now());
+            let result = if calls.len() <= failures {
+                Err(error().into())
+            } else {
+                Ok(json!([]))
+            };
+            Box::pin(async move { result })
+        });
+        TestRpc::spawn_registry(reg).await
+    }
+
+    #[tokio::test]
+    async fn retries_internal_errors_with_backoff() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server =
+            spawn_flaky_prover(2, || RpcErr::Internal("flaky".into()), calls.clone()).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .retry_config(RetryConfig {
+                max_retries: 3,
+                initial_delay: Duration::from_millis(30),
+                backoff_factor: 2,
+                max_delay: Duration::from_secs(1),
+            })
+            .build()
+            .unwrap();
+
+        let ids = client.get_pending_job_ids().await.unwrap();
+
+        assert!(ids.is_empty());
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 3);
+        let first_gap = calls[1] - calls[0];
+        let second_gap = calls[2] - calls[1];
+        assert!(first_gap >= Duration::from_millis(30));
+        assert!(second_gap >= Duration::from_millis(60));
+        assert!(second_gap > first_gap);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server =
+            spawn_flaky_prover(10, || RpcErr::Internal("down".into()), calls.clone()).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .retry_config(RetryConfig {
+                max_retries: 3,
+                initial_delay: Duration::from_millis(1),
+                backoff_factor: 2,
+                max_delay: Duration::from_millis(5),
+            })
+            .build()
+            .unwrap();
+
+        let err = client.get_pending_job_ids().await.unwrap_err();
+
+        assert!(matches!(err, Error::JsonRpc { code: -32603, .. }));
+        assert_eq!(calls.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn bad_params_fail_fast() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let server =
+            spawn_flaky_prover(10, || RpcErr::BadParams("nope".into()), calls.clone()).await;
+        let client = MojaveClient::builder()
+            .prover_urls(vec![server.url().to_string()])
+            .retry_config(RetryConfig {
+                max_retries: 3,
+                initial_delay: Duration::from_millis(1),
+                backoff_factor: 2,
+                max_delay: Duration::from_millis(5),
+            })
+            .build()
+            .unwrap();
+
+        let err = client.get_pending_job_ids().await.unwrap_err();
+
+        assert!(matches!(err, Error::JsonRpc { code: -32602, .. }));
+        assert_eq!(calls.lock().unwrap().len(), 1);
+    }
+
+    /// Full node without any blocks that counts the `eth_getBlockByNumber` calls.
+    async fn spawn_empty_full_node(calls: Arc<std::sync::atomic::AtomicUsize>) -> TestRpc {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fallback(Namespace::Eth, move |_req, _| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async { Ok(serde_json::Value::Null) })
+        });
+        TestRpc::spawn_registry(reg).await
+    }
+
+    #[tokio::test]
+    async fn get_blocks_range_requires_full_nodes() {
+        let client = MojaveClient::builder()
+            .sequencer_urls(vec!["http://127.0.0.1:1"])
+            .build()
+            .unwrap();
+
+        let results: Vec<_> = client.get_blocks_range(0, 1).collect().await;
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|result| matches!(result, Err(Error::NoRPCUrlsConfigured)))
+        );
+    }
+
+    /// Full node that serves every block, with full transactions or with
+    /// transaction hashes only, counting the calls.
+    async fn spawn_block_full_node(
+        full_transactions: bool,
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+    ) -> TestRpc {
+        use ethrex_common::types::{BlockBody, BlockHeader};
+        use ethrex_rpc::types::block::RpcBlock;
+
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fallback(Namespace::Eth, move |req, _| {
+            calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let number = req.params.as_ref().unwrap()[0].as_str().unwrap().to_owned();
+            Box::pin(async move {
+                let header = BlockHeader {
+                    number: u64::from_str_radix(number.trim_start_matches("0x"), 16).unwrap(),
+                    ..Default::default()
+                };
+                let hash = header.hash();
+                let block = RpcBlock::build(header, BlockBody::default(), hash, true).unwrap();
+                let mut block = serde_json::to_value(block).unwrap();
+                if !full_transactions {
+                    block["transactions"] = json!([H256::repeat_byte(1)]);
+                }
+                Ok(block)
+            })
+        });
+        TestRpc::spawn_registry(reg).await
+    }
+
+    #[tokio::test]
+    async fn get_blocks_range_asks_other_nodes_for_full_transactions() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = [Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0))];
+        let hashes_only = spawn_block_full_node(false, calls[0].clone()).await;
+        let full = spawn_block_full_node(true, calls[1].clone()).await;
+
+        // Block 10 starts at the first node, which only returns hashes.
+        let client = MojaveClient::builder()
+            .full_node_urls(vec![hashes_only.url().to_string(), full.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        let blocks: Vec<_> = client.get_blocks_range(10, 10).collect().await;
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].as_ref().unwrap().header.number, 10);
+        assert_eq!(calls[0].load(Ordering::SeqCst), 1);
+        assert_eq!(calls[1].load(Ordering::SeqCst), 1);
+
+        // Without a node serving full bodies the error is returned, not a panic.
+        let client = MojaveClient::builder()
+            .full_node_urls(vec![hashes_only.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        let blocks: Vec<_> = client.get_blocks_range(10, 10).collect().await;
+        assert!(matches!(
+            blocks[..],
+            [Err(Error::BlockWithoutTransactions(10))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_blocks_range_spreads_requests_and_keeps_order() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = [Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0))];
+        let first = spawn_empty_full_node(calls[0].clone()).await;
+        let second = spawn_empty_full_node(calls[1].clone()).await;
+
+        let client = MojaveClient::builder()
+            .full_node_urls(vec![first.url().to_string(), second.url().to_string()])
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+
+        let missing: Vec<_> = client
+            .get_blocks_range(10, 13)
+            .map(|result| match result {
+                Err(Error::BlockNotFound(number)) => number,
+                other => panic!("unexpected result: {other:?}"),
+            })
+            .collect()
+            .await;
+
+        assert_eq!(missing, vec![10, 11, 12, 13]);
+        assert_eq!(calls[0].load(Ordering::SeqCst), 2);
+        assert_eq!(calls[1].load(Ordering::SeqCst), 2);
+    }
 }
@@ -5,3 +5,9 @@ pub(crate) const BACKOFF_FACTOR: u32 = 2;
 pub(crate) const MAX_DELAY: Duration = Duration::from_secs(30);
 pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 pub(crate) const DEFAULT_MAX_RETRY: usize = 1;
+/// JSON-RPC `Internal error`, treated as transient by the retry logic.
+pub(crate) const JSON_RPC_INTERNAL_ERROR: i32 = -32603;
+/// Blocks requested concurrently by `MojaveClient::get_blocks_range`.
+pub(crate) const BLOCK_RANGE_CONCURRENCY: usize = 8;
+pub(crate) const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 5;
+pub(crate) const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
@@ -1,9 +1,29 @@
+use crate::types::JobId;
+
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Block {0} not found")]
+    BlockNotFound(u64),
+    #[error("Block {0} was returned with transaction hashes only")]
+    BlockWithoutTransactions(u64),
     #[error("Error: {0}")]
     Custom(String),
+    #[error("HTTP status {0}")]
+    HttpStatus(u16),
+    #[error("Invalid URL {url:?}: {source}")]
+    InvalidUrl {
+        url: String,
+        source: url::ParseError,
+    },
+    #[error("RPC Error {code}: {message}")]
+    JsonRpc {
+        code: i32,
+        message: String,
+        /// The error object's `data` member, e.g. which parameter was invalid.
+        data: Option<serde_json::Value>,
+    },
     #[error("Missing full node URLs")]
     MissingFullNodeUrls,
     #[error("Missing max attempts")]
@@ -18,6 +38,8 @@ pub enum Error {
     MissingTimeout,
     #[error("No RPC URLs configured")]
     NoRPCUrlsConfigured,
+    #[error("Timed out waiting for the proof of job {0}")]
+    ProofTimeout(JobId),
     #[error("Reqwest error: {0}")]
     Reqwest(#[from] reqwest::Error),
     #[error("Retry failed after {0} attempts")]
@@ -1,3 +1,4 @@
+mod circuit_breaker;
 mod client;
 mod constants;
 pub mod error;
@@ -6,7 +7,8 @@ mod retry_config;
 pub mod types;
 mod utils;
 
-pub use client::MojaveClient;
+pub use circuit_breaker::CircuitBreakerConfig;
+pub use client::{MojaveClient, MojaveClientBuilder, RequestHook};
 
 pub mod prelude {
     pub use crate::{
@@ -1,15 +1,27 @@
-use ethrex_rpc::utils::RpcRequest;
+use ethrex_common::{
+    Address, H256,
+    types::{Block, BlockBody},
+};
+use ethrex_rpc::{
+    types::block::{BlockBodyWrapper, RpcBlock},
+    utils::{RpcRequest, RpcRequestId},
+};
+use mojave_signature::types::Signer;
 use mojave_utils::rpc::types::MojaveRequestMethods;
 use reqwest::Url;
 use serde::de::DeserializeOwned;
-use serde_json::json;
+use serde_json::{Value, json};
+use std::{marker::PhantomData, time::Instant};
 
 use crate::{
     MojaveClient,
     error::{Error, Result},
     retry_config::RetryConfig,
-    types::{JobId, ProofResponse, ProverData, Strategy},
-    utils::{create_rpc_request, send_request_race, send_request_sequential},
+    types::{JobId, ProofInputMessage, ProofResponse, ProverData, RequestSignature, Strategy},
+    utils::{
+        create_rpc_request, parse_hex_u64, send_batch_sequential, send_request_race,
+        send_request_sequential,
+    },
 };
 
 pub struct RequestBuilder<'a> {
@@ -74,6 +86,31 @@ impl<'a> RequestBuilder<'a> {
     }
 
     async fn send_rpc_request<T>(&self, request: &RpcRequest) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let started = Instant::now();
+        let result = self.dispatch(request).await;
+
+        let Some(hook) = self.client.inner.on_request.as_ref() else {
+            return result;
+        };
+        let method = request.method.trim_matches('"');
+        let elapsed = started.elapsed();
+        match result {
+            Ok(value) => {
+                hook(method, elapsed, &Ok(()));
+                Ok(value)
+            }
+            Err(error) => {
+                let outcome: Result<()> = Err(error);
+                hook(method, elapsed, &outcome);
+                Err(outcome.unwrap_err())
+            }
+        }
+    }
+
+    async fn dispatch<T>(&self, request: &RpcRequest) -> Result<T>
     where
         T: DeserializeOwned,
     {
@@ -82,13 +119,64 @@ impl<'a> RequestBuilder<'a> {
 
         match self.strategy {
             Strategy::Sequential => {
-                send_request_sequential(&self.client.inner.client, request, urls, retry_config)
-                    .await
+                send_request_sequential(
+                    &self.client.inner.client,
+                    request,
+                    urls,
+                    retry_config,
+                    &self.client.inner.circuit_breakers,
+                )
+                .await
+            }
+            Strategy::Race => {
+                send_request_race(
+                    &self.client.inner.client,
+                    request,
+                    urls,
+                    &self.client.inner.circuit_breakers,
+                )
+                .await
             }
-            Strategy::Race => send_request_race(&self.client.inner.client, request, urls).await,
         }
     }
 
+    /// Params for a proof-input submission: the input, the sequencer address
+    /// and a fresh nonce, followed by a [`RequestSignature`] over them when the
+    /// client has a signing key.
+    fn proof_input_params(
+        &self,
+        proof_input: &ProverData,
+        sequencer_address: &str,
+    ) -> Result<Vec<Value>> {
+        let nonce = self.client.next_nonce();
+        let Some(signing_key) = self.client.inner.signing_key.as_ref() else {
+            return Ok(vec![
+                json!(proof_input),
+                json!(sequencer_address),
+                json!(nonce),
+            ]);
+        };
+
+        // Signed in the form the prover parses it back into.
+        let sequencer_addr = Url::parse(sequencer_address)
+            .map_err(|e| Error::Custom(format!("Invalid sequencer address: {e}")))?;
+        let signature = signing_key.sign(&ProofInputMessage {
+            prover_data: proof_input,
+            sequencer_addr: sequencer_addr.as_str(),
+            nonce: Some(nonce),
+        })?;
+        let signature = RequestSignature {
+            signature,
+            verifying_key: signing_key.verifying_key(),
+        };
+        Ok(vec![
+            json!(proof_input),
+            json!(sequencer_addr),
+            json!(nonce),
+            json!(signature),
+        ])
+    }
+
     pub async fn send_proof_input(
         self,
         proof_input: &ProverData,
@@ -96,7 +184,20 @@ impl<'a> RequestBuilder<'a> {
     ) -> Result<JobId> {
         let request = create_rpc_request(
             MojaveRequestMethods::SendProofInput,
-            Some(vec![json!(proof_input), json!(sequencer_address)]),
+            Some(self.proof_input_params(proof_input, sequencer_address)?),
+        )?;
+
+        self.send_rpc_request(&request).await
+    }
+
+    pub async fn resubmit_proof(
+        self,
+        proof_input: &ProverData,
+        sequencer_address: &str,
+    ) -> Result<JobId> {
+        let request = create_rpc_request(
+            MojaveRequestMethods::ResubmitProofInput,
+            Some(self.proof_input_params(proof_input, sequencer_address)?),
         )?;
 
         self.send_rpc_request(&request).await
@@ -114,4 +215,144 @@ impl<'a> RequestBuilder<'a> {
 
         self.send_rpc_request(&request).await
     }
+
+    /// Latest block number via `eth_blockNumber`.
+    pub async fn get_block_number(self) -> Result<u64> {
+        let request = create_rpc_request(MojaveRequestMethods::BlockNumber, None)?;
+
+        let number: String = self.send_rpc_request(&request).await?;
+        parse_hex_u64(&number)
+    }
+
+    /// Nonce of `address` at the latest block via `eth_getTransactionCount`.
+    pub async fn get_transaction_count(self, address: Address) -> Result<u64> {
+        let request = create_rpc_request(
+            MojaveRequestMethods::GetTransactionCount,
+            Some(vec![json!(address), json!("latest")]),
+        )?;
+
+        let count: String = self.send_rpc_request(&request).await?;
+        parse_hex_u64(&count)
+    }
+
+    /// Submits an encoded transaction via `eth_sendRawTransaction` and returns
+    /// its hash.
+    pub async fn send_raw_transaction(self, encoded: &[u8]) -> Result<H256> {
+        let request = create_rpc_request(
+            MojaveRequestMethods::SendRawTransaction,
+            Some(vec![json!(format!("0x{}", hex::encode(encoded)))]),
+        )?;
+
+        self.send_rpc_request(&request).await
+    }
+
+    /// Fetches a full block (with transactions) via `eth_getBlockByNumber`.
+    pub async fn get_block_by_number(self, number: u64) -> Result<Block> {
+        let request = create_rpc_request(
+            MojaveRequestMethods::GetBlockByNumber,
+            Some(vec![json!(format!("{number:#x}")), json!(true)]),
+        )?;
+
+        let block: Option<RpcBlock> = self.send_rpc_request(&request).await?;
+        let block = block.ok_or(Error::BlockNotFound(number))?;
+        let BlockBodyWrapper::Full(body) = block.body else {
+            return Err(Error::BlockWithoutTransactions(number));
+        };
+
+        Ok(Block::new(
+            block.header,
+            BlockBody {
+                transactions: body.transactions.into_iter().map(|tx| tx.tx).collect(),
+                ommers: Vec::new(),
+                withdrawals: Some(body.withdrawals),
+            },
+        ))
+    }
+}
+
+/// Queues calls that return `T` and sends them as one JSON-RPC batch.
+///
+/// Created by [`MojaveClient::batch`]. The batch goes through the client's
+/// retry config, timeout and circuit breakers, failing over between the
+/// target URLs like [`Strategy::Sequential`].
+pub struct BatchRequestBuilder<'a, T> {
+    client: &'a MojaveClient,
+    target_urls: Option<&'a [Url]>,
+    calls: Vec<(MojaveRequestMethods, Option<Vec<Value>>)>,
+    _response: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: DeserializeOwned> BatchRequestBuilder<'a, T> {
+    pub fn new(client: &'a MojaveClient) -> Self {
+        Self {
+            client,
+            target_urls: None,
+            calls: Vec::new(),
+            _response: PhantomData,
+        }
+    }
+
+    /// Sends the batch to `urls` instead of the default for the queued calls.
+    pub fn with_urls(mut self, urls: &'a [Url]) -> Self {
+        self.target_urls = Some(urls);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Sends the queued calls in one round trip. Results are in the order the
+    /// calls were queued; each fails on its own when the server returns an
+    /// error for it.
+    pub async fn send(self) -> Result<Vec<Result<T>>> {
+        if self.calls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let urls = match self.target_urls {
+            Some(urls) if !urls.is_empty() => urls,
+            _ => return Err(Error::NoRPCUrlsConfigured),
+        };
+        let requests = self
+            .calls
+            .into_iter()
+            .enumerate()
+            .map(|(index, (method, params))| {
+                let mut request = create_rpc_request(method, params)?;
+                request.id = RpcRequestId::Number(index as u64);
+                Ok(request)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let inner = &self.client.inner;
+        let results = send_batch_sequential(
+            &inner.client,
+            &requests,
+            urls,
+            &inner.retry_config,
+            &inner.circuit_breakers,
+        )
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|result| result.and_then(|value| Ok(serde_json::from_value(value)?)))
+            .collect())
+    }
+}
+
+impl<'a> BatchRequestBuilder<'a, ProofResponse> {
+    /// Queues a `moj_getProof` call. Targets the provers unless
+    /// [`Self::with_urls`] was used.
+    pub fn get_proof(mut self, job_id: JobId) -> Self {
+        self.target_urls
+            .get_or_insert(&self.client.inner.prover_urls);
+        self.calls
+            .push((MojaveRequestMethods::GetProof, Some(vec![json!(job_id)])));
+        self
+    }
 }
@@ -10,6 +10,15 @@ pub struct RetryConfig {
     pub max_delay: Duration,
 }
 
+impl RetryConfig {
+    /// Delay before the retry that follows one made after `delay`.
+    pub(crate) fn next_delay(&self, delay: Duration) -> Duration {
+        delay
+            .saturating_mul(self.backoff_factor)
+            .min(self.max_delay)
+    }
+}
+
 impl Default for RetryConfig {
     fn default() -> Self {
         Self {
@@ -20,3 +29,25 @@ impl Default for RetryConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_delay_backs_off_up_to_max_delay() {
+        let config = RetryConfig {
+            max_retries: 5,
+            initial_delay: Duration::from_millis(100),
+            backoff_factor: 3,
+            max_delay: Duration::from_millis(500),
+        };
+
+        let first = config.next_delay(config.initial_delay);
+        let second = config.next_delay(first);
+
+        assert_eq!(first, Duration::from_millis(300));
+        assert_eq!(second, Duration::from_millis(500));
+        assert_eq!(config.next_delay(second), Duration::from_millis(500));
+    }
+}
@@ -23,6 +23,33 @@ pub struct SignedBlock {
     pub verifying_key: VerifyingKey,
 }
 
+/// Signature a submitter attaches to a proof input so the prover can verify
+/// where it came from.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RequestSignature {
+    pub signature: Signature,
+    pub verifying_key: VerifyingKey,
+}
+
+/// What a [`RequestSignature`] on `moj_sendProofInput` and
+/// `moj_resubmitProofInput` covers. `sequencer_addr` is the normalized URL.
+#[derive(Serialize)]
+pub struct ProofInputMessage<'a> {
+    pub prover_data: &'a ProverData,
+    pub sequencer_addr: &'a str,
+    pub nonce: Option<u64>,
+}
+
+/// What a [`RequestSignature`] on `moj_cancelJob` covers. `nonce` shares the
+/// signer's sequence with proof-input submissions, so a captured cancellation
+/// cannot be replayed against a later resubmission of the same job.
+#[derive(Serialize)]
+pub struct CancelJobMessage<'a> {
+    pub job_id: &'a JobId,
+    pub nonce: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct SignedProofResponse {
@@ -93,4 +120,8 @@ pub struct ProofResponse {
 pub enum ProofResult {
     Proof(BatchProof),
     Error(String),
+    /// The job is still queued or proving; keep polling.
+    NotReady {
+        progress: Option<f32>,
+    },
 }
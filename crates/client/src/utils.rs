@@ -1,29 +1,36 @@
-use std::pin::Pin;
+use std::{future::Future, pin::Pin};
 
-use ethrex_rpc::{
-    clients::eth::RpcResponse,
-    utils::{RpcRequest, RpcRequestId},
-};
+use ethrex_rpc::utils::{RpcRequest, RpcRequestId};
 use futures::{
     FutureExt,
     future::{Fuse, select_ok},
 };
+use mojave_rpc_core::RpcErrorObject;
 use mojave_utils::rpc::types::MojaveRequestMethods;
 use reqwest::Url;
-use serde::de::DeserializeOwned;
-use serde_json::to_string;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, to_string};
 
 use crate::{
+    circuit_breaker::CircuitBreakers,
+    constants::JSON_RPC_INTERNAL_ERROR,
     error::{Error, Result},
     retry_config::RetryConfig,
 };
 
 pub fn parse_urls(urls: Vec<String>) -> Result<Vec<Url>> {
     urls.into_iter()
-        .map(|url| Url::parse(&url).map_err(|e| Error::Custom(e.to_string())))
+        .map(|url| Url::parse(&url).map_err(|source| Error::InvalidUrl { url, source }))
         .collect()
 }
 
+/// Parses a JSON-RPC hex quantity such as `"0x2a"`.
+pub fn parse_hex_u64(quantity: &str) -> Result<u64> {
+    let digits = quantity.strip_prefix("0x").unwrap_or(quantity);
+    u64::from_str_radix(digits, 16)
+        .map_err(|e| Error::Custom(format!("Invalid hex quantity {quantity:?}: {e}")))
+}
+
 pub fn create_rpc_request(
     method: MojaveRequestMethods,
     params: Option<Vec<serde_json::Value>>,
@@ -36,8 +43,28 @@ pub fn create_rpc_request(
     })
 }
 
+/// Whether `error` means the endpoint itself could not serve the request
+/// (unreachable, timed out, 5xx), as opposed to a JSON-RPC application error
+/// it answered with. Only these move a request on to the next URL.
+pub fn is_endpoint_failure(error: &Error) -> bool {
+    match error {
+        Error::Reqwest(_) | Error::TimeOut | Error::RetryFailed(_) => true,
+        Error::HttpStatus(status) => *status >= 500,
+        _ => false,
+    }
+}
+
+/// Whether `error` is likely transient: timeouts, refused connections, 5xx
+/// responses and JSON-RPC internal errors. Everything else, e.g. bad params
+/// or a 4xx, fails fast.
 pub fn is_retryable_error(error: &Error) -> bool {
-    matches!(error, Error::TimeOut)
+    match error {
+        Error::TimeOut => true,
+        Error::Reqwest(error) => error.is_timeout() || error.is_connect(),
+        Error::HttpStatus(status) => *status >= 500,
+        Error::JsonRpc { code, .. } => *code == JSON_RPC_INTERNAL_ERROR,
+        _ => false,
+    }
 }
 
 pub async fn send_request_sequential<T>(
@@ -45,16 +72,55 @@ pub async fn send_request_sequential<T>(
     request: &RpcRequest,
     urls: &[Url],
     retry_config: &RetryConfig,
+    circuit_breakers: &CircuitBreakers,
 ) -> Result<T>
 where
     T: DeserializeOwned,
+{
+    failover(urls, circuit_breakers, |url| {
+        send_request_with_retry(client, request, url, retry_config)
+    })
+    .await
+}
+
+/// Sends `requests` as one JSON-RPC batch with the same retry and failover
+/// rules as [`send_request_sequential`]. Request ids must be their index; the
+/// results come back aligned with `requests`.
+pub async fn send_batch_sequential(
+    client: &reqwest::Client,
+    requests: &[RpcRequest],
+    urls: &[Url],
+    retry_config: &RetryConfig,
+    circuit_breakers: &CircuitBreakers,
+) -> Result<Vec<Result<Value>>> {
+    failover(urls, circuit_breakers, |url| {
+        with_retry(retry_config, move || send_batch_once(client, requests, url))
+    })
+    .await
+}
+
+/// Tries `urls` in order, moving on only when an endpoint fails.
+async fn failover<'a, T, F, Fut>(
+    urls: &'a [Url],
+    circuit_breakers: &CircuitBreakers,
+    mut send: F,
+) -> Result<T>
+where
+    F: FnMut(&'a Url) -> Fut,
+    Fut: Future<Output = Result<T>>,
 {
     let mut last_error = Error::Custom("All RPC calls failed".to_owned());
 
-    for url in urls {
-        match send_request_with_retry(client, request, url, retry_config).await {
+    for url in circuit_breakers.available(urls) {
+        let result = send(url).await;
+        circuit_breakers.record(url, &result);
+        match result {
             Ok(response) => return Ok(response),
-            Err(error) => last_error = error,
+            Err(error) if is_endpoint_failure(&error) => {
+                tracing::warn!(url = %url, error = %error, "Endpoint failed, trying next URL");
+                last_error = error;
+            }
+            Err(error) => return Err(error),
         }
     }
 
@@ -65,13 +131,21 @@ pub async fn send_request_race<T>(
     client: &reqwest::Client,
     request: &RpcRequest,
     urls: &[Url],
+    circuit_breakers: &CircuitBreakers,
 ) -> Result<T>
 where
     T: DeserializeOwned,
 {
-    let requests: Vec<Pin<Box<Fuse<_>>>> = urls
-        .iter()
-        .map(|url| Box::pin(send_request_once(client, request, url).fuse()))
+    let requests: Vec<Pin<Box<Fuse<_>>>> = circuit_breakers
+        .available(urls)
+        .into_iter()
+        .map(|url| {
+            Box::pin(
+                send_request_once(client, request, url)
+                    .inspect(move |result| circuit_breakers.record(url, result))
+                    .fuse(),
+            )
+        })
         .collect();
 
     let (response, _) = select_ok(requests)
@@ -89,6 +163,16 @@ pub async fn send_request_with_retry<T>(
 ) -> Result<T>
 where
     T: DeserializeOwned,
+{
+    with_retry(retry_config, || send_request_once(client, request, url)).await
+}
+
+/// Runs `attempt` until it succeeds, fails with a non-retryable error or
+/// `retry_config.max_retries` attempts are used up, backing off in between.
+async fn with_retry<T, F, Fut>(retry_config: &RetryConfig, mut attempt_fn: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
 {
     let mut attempt = 0;
     let mut delay = retry_config.initial_delay;
@@ -97,7 +181,7 @@ where
     while attempt < retry_config.max_retries {
         attempt += 1;
 
-        match send_request_once(client, request, url).await {
+        match attempt_fn().await {
             Ok(response) => return Ok(response),
             Err(error) => {
                 tracing::error!(
@@ -115,11 +199,7 @@ where
                         "Retrying request"
                     );
                     tokio::time::sleep(delay).await;
-
-                    delay = delay.saturating_mul(retry_config.backoff_factor);
-                    if delay > retry_config.max_delay {
-                        delay = retry_config.max_delay;
-                    }
+                    delay = retry_config.next_delay(delay);
 
                     last_error = Some(error);
                 } else {
@@ -139,21 +219,154 @@ pub async fn send_request_once<T>(
 ) -> Result<T>
 where
     T: DeserializeOwned,
+{
+    let response: RpcResponse = post_json(client, request, url).await?;
+    response_result(response)
+}
+
+/// Sends one batch and lines the responses up with `requests` by id.
+pub async fn send_batch_once(
+    client: &reqwest::Client,
+    requests: &[RpcRequest],
+    url: &Url,
+) -> Result<Vec<Result<Value>>> {
+    let responses = match post_json::<_, Value>(client, requests, url).await? {
+        Value::Array(responses) => responses,
+        // The batch as a whole was rejected, e.g. for being too large.
+        other => {
+            return match response_result::<Value>(serde_json::from_value(other)?) {
+                Err(error) => Err(error),
+                Ok(_) => Err(Error::Custom("Expected a batch response".to_owned())),
+            };
+        }
+    };
+
+    let mut results: Vec<Option<Result<Value>>> = requests.iter().map(|_| None).collect();
+    for response in responses {
+        let index = response
+            .get("id")
+            .and_then(Value::as_u64)
+            .and_then(|id| usize::try_from(id).ok())
+            .filter(|index| *index < results.len());
+        let Some(index) = index else {
+            tracing::warn!(response = %response, "Ignoring batch response with unknown id");
+            continue;
+        };
+        results[index] = Some(
+            serde_json::from_value(response)
+                .map_err(Error::from)
+                .and_then(response_result),
+        );
+    }
+
+    Ok(results
+        .into_iter()
+        .enumerate()
+        .map(|(index, result)| {
+            result.unwrap_or_else(|| {
+                Err(Error::Custom(format!(
+                    "Missing response for batch request {index}"
+                )))
+            })
+        })
+        .collect())
+}
+
+/// Posts `body` as JSON and parses the reply. Replies that don't parse are
+/// reported by HTTP status when it isn't a success, since JSON-RPC errors may
+/// come with a non-2xx status too.
+async fn post_json<B, R>(client: &reqwest::Client, body: &B, url: &Url) -> Result<R>
+where
+    B: Serialize + ?Sized,
+    R: DeserializeOwned,
 {
     let response = client
         .post(url.as_ref())
         .header("content-type", "application/json")
-        .body(serde_json::to_string(request)?)
+        .body(serde_json::to_string(body)?)
         .send()
-        .await?
-        .json::<RpcResponse>()
         .await?;
+    let status = response.status();
+    let body = response.bytes().await?;
+
+    match serde_json::from_slice::<R>(&body) {
+        Ok(response) => Ok(response),
+        Err(_) if !status.is_success() => Err(Error::HttpStatus(status.as_u16())),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// A JSON-RPC response. Unlike ethrex's `RpcResponse`, the error's `data` can
+/// be any JSON value.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RpcResponse {
+    Success { result: Value },
+    Error { error: RpcErrorObject },
+}
 
+fn response_result<T: DeserializeOwned>(response: RpcResponse) -> Result<T> {
     match response {
-        RpcResponse::Success(ok_response) => Ok(serde_json::from_value::<T>(ok_response.result)?),
-        RpcResponse::Error(error_response) => Err(Error::Custom(format!(
-            "RPC Error {}: {}",
-            error_response.error.code, error_response.error.message
-        ))),
+        RpcResponse::Success { result } => Ok(serde_json::from_value::<T>(result)?),
+        RpcResponse::Error { error } => Err(Error::JsonRpc {
+            code: error.code,
+            message: error.message,
+            data: error.data,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_result_keeps_structured_error_data() {
+        let response = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": { "code": -32602, "message": "Invalid params", "data": { "field": "to" } }
+        }))
+        .unwrap();
+
+        let error = response_result::<Value>(response).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::JsonRpc { code: -32602, data: Some(ref data), .. }
+                if *data == serde_json::json!({ "field": "to" })
+        ));
+    }
+
+    #[test]
+    fn response_result_accepts_null_results() {
+        let response = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "result": null
+        }))
+        .unwrap();
+
+        assert_eq!(response_result::<Value>(response).unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn parse_hex_u64_accepts_quantities() {
+        assert_eq!(parse_hex_u64("0x0").unwrap(), 0);
+        assert_eq!(parse_hex_u64("0x2a").unwrap(), 42);
+        assert!(parse_hex_u64("0xzz").is_err());
+    }
+
+    #[test]
+    fn parse_urls_reports_first_invalid_entry() {
+        let err = parse_urls(vec![
+            "http://127.0.0.1:1".into(),
+            "not-url".into(),
+            "http://:://not-valid".into(),
+        ])
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::InvalidUrl { ref url, source: url::ParseError::RelativeUrlWithoutBase }
+                if url == "not-url"
+        ));
     }
 }
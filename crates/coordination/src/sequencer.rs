@@ -1,6 +1,9 @@
 use std::{path::PathBuf, time::Duration};
 
-use mojave_batch_producer::{BatchProducer, types::Request as BatchRequest};
+use mojave_batch_producer::{
+    BatchProducer,
+    types::{BatchProducerOptions, Request as BatchRequest},
+};
 use mojave_block_producer::{
     BlockProducer,
     types::{BlockProducerOptions, Request as BlockRequest},
@@ -35,6 +38,7 @@ async fn run_sequencer_leader_task(
     node: MojaveNode,
     options: &NodeOptions,
     block_producer_options: &BlockProducerOptions,
+    batch_producer_options: &BatchProducerOptions,
     proof_coordinator_options: &ProofCoordinatorOptions,
     cancel_token: CancellationToken,
 ) -> Result<(), BoxError> {
@@ -44,6 +48,7 @@ async fn run_sequencer_leader_task(
         node,
         options,
         block_producer_options,
+        batch_producer_options,
         proof_coordinator_options,
         cancel_token.clone(),
     )
@@ -62,6 +67,7 @@ pub async fn run_sequencer(
     node: MojaveNode,
     options: &NodeOptions,
     block_producer_options: &BlockProducerOptions,
+    batch_producer_options: &BatchProducerOptions,
     proof_coordinator_options: &ProofCoordinatorOptions,
 ) -> Result<(), BoxError> {
     let node_clone = node.clone();
@@ -70,6 +76,7 @@ pub async fn run_sequencer(
             let node_task = node.clone();
             let options_task = options.clone();
             let block_producer_options_task = block_producer_options.clone();
+            let batch_producer_options_task = batch_producer_options.clone();
             let proof_coordinator_options_task = proof_coordinator_options.clone();
 
             async move {
@@ -77,6 +84,7 @@ pub async fn run_sequencer(
                     node_task,
                     &options_task,
                     &block_producer_options_task,
+                    &batch_producer_options_task,
                     &proof_coordinator_options_task,
                     shutdown_token,
                 )
@@ -95,6 +103,7 @@ pub async fn run_sequencer(
         let node_task = node.clone();
         let options_task = options.clone();
         let block_producer_options_task = block_producer_options.clone();
+        let batch_producer_options_task = batch_producer_options.clone();
         let proof_coordinator_options_task = proof_coordinator_options.clone();
 
         let mut leader_task = tokio::spawn(async move {
@@ -102,6 +111,7 @@ pub async fn run_sequencer(
                 node_task,
                 &options_task,
                 &block_producer_options_task,
+                &batch_producer_options_task,
                 &proof_coordinator_options_task,
                 shutdown_for_task,
             )
@@ -154,12 +164,12 @@ async fn start_leader_tasks(
     node: MojaveNode,
     options: &NodeOptions,
     block_producer_options: &BlockProducerOptions,
+    batch_producer_options: &BatchProducerOptions,
     proof_coordinator_options: &ProofCoordinatorOptions,
     cancel_token: CancellationToken,
 ) -> Result<LeaderTasks, BoxError> {
-    let batch_counter = node.rollup_store.get_batch_number().await?.unwrap_or(0);
-    let batch_producer = BatchProducer::new(node.clone(), batch_counter);
-    let block_producer = BlockProducer::new(node.clone());
+    let batch_producer = BatchProducer::from_store(node.clone(), batch_producer_options).await?;
+    let block_producer = BlockProducer::new(node.clone(), block_producer_options)?;
     let proof_coordinator =
         ProofCoordinator::new(node.clone(), options, proof_coordinator_options)?;
 
@@ -14,9 +14,15 @@ pub enum Error {
     #[error("Config error: {0}")]
     Config(String),
     #[error(transparent)]
+    Client(#[from] mojave_client::error::Error),
+    #[error(transparent)]
     EthClient(#[from] EthClientError),
+    #[error("Datadir is in use by another mojave process (pid {held_by_pid})")]
+    DatadirLocked { held_by_pid: u32 },
     #[error("Failed to force remove the database: {0}")]
     ForceRemoveDatabase(std::io::Error),
+    #[error("Refusing to remove the database: {0}")]
+    RefuseRemoveDatabase(String),
     #[error(transparent)]
     Genesis(#[from] GenesisError),
     #[error(transparent)]
@@ -1,8 +1,10 @@
 pub mod error;
 pub mod initializers;
+pub mod metrics;
 pub mod node;
 pub mod p2p;
 pub mod pending_heap;
+pub mod reorg_guard;
 pub mod rpc;
 pub mod services;
 pub mod types;
@@ -0,0 +1,12 @@
+use lazy_static::lazy_static;
+use mojave_utils::metrics::opts;
+use prometheus::{IntGauge, register_int_gauge};
+
+lazy_static! {
+    /// Signed blocks waiting in the pending heap for their predecessors.
+    pub static ref PENDING_BLOCKS_DEPTH: IntGauge = register_int_gauge!(opts(
+        "node_pending_blocks",
+        "Number of signed blocks queued until their predecessors are applied",
+    ))
+    .expect("node_pending_blocks can be registered");
+}
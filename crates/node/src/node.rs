@@ -2,9 +2,13 @@ use crate::{
     error::{Error, Result},
     initializers::{get_local_node_record, get_signer, init_blockchain, init_store},
     p2p::network::start_network,
+    pending_heap::PendingHeap,
     rpc::{context::RpcApiContext, start_api},
     types::{MojaveNode, NodeConfigFile, NodeOptions},
-    utils::{get_local_p2p_node, read_jwtsecret_file, resolve_data_dir, store_node_config_file},
+    utils::{
+        DatadirLock, StartupTimer, get_local_p2p_node, read_jwtsecret_file, remove_db,
+        resolve_data_dir, store_node_config_file,
+    },
 };
 use ethrex_blockchain::BlockchainType;
 use ethrex_p2p::{
@@ -12,38 +16,48 @@ use ethrex_p2p::{
     sync_manager::SyncManager,
 };
 use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use mojave_client::MojaveClient;
 use mojave_rpc_server::RpcRegistry;
 use mojave_utils::{
     network::{
-        ensure_tcp_port_available, ensure_udp_port_available, get_authrpc_socket_addr,
+        Network, ensure_tcp_port_available, ensure_udp_port_available, get_authrpc_socket_addr,
         get_http_socket_addr,
     },
     unique_heap::AsyncUniqueHeap,
 };
-use std::{path::PathBuf, sync::Arc};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 use tokio_util::task::TaskTracker;
 
+/// How long shutdown waits for the RPC server and its background tasks.
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl MojaveNode {
     pub async fn init(options: &NodeOptions) -> Result<Self> {
+        let mut startup = StartupTimer::start();
         let (data_dir, data_dir_str) = resolve_data_dir(&options.datadir).await?;
         tracing::info!("Data directory resolved to: {:?}", data_dir);
 
         if options.force {
-            tracing::info!("Force removing the database at {:?}", data_dir);
-            tokio::fs::remove_dir_all(&data_dir)
-                .await
-                .map_err(Error::ForceRemoveDatabase)?;
+            remove_db(&data_dir, true).await?;
+            tokio::fs::create_dir_all(&data_dir).await?;
         }
+        let datadir_lock = Arc::new(DatadirLock::acquire(&data_dir)?);
+        startup.phase("datadir");
 
-        let genesis = options.network.get_genesis()?;
+        let genesis = options
+            .network
+            .get_genesis_with_overrides(&options.genesis_overrides)?;
+        startup.phase("genesis");
 
         let store = init_store(&data_dir_str, genesis.clone()).await?;
         tracing::info!("Successfully initialized the database.");
+        startup.phase("store");
 
         let rollup_store = StoreRollup::new(&data_dir, EngineTypeRollup::InMemory)?;
         rollup_store.init().await?;
         tracing::info!("Successfully initialized the rollup database.");
+        startup.phase("rollup_store");
 
         let blockchain = init_blockchain(store.clone(), BlockchainType::L2);
 
@@ -78,6 +92,8 @@ impl MojaveNode {
             options.bootnodes.clone(),
             &options.network,
             &data_dir_str,
+            options.bootnodes_url.as_deref(),
+            options.strict_bootnodes,
             local_p2p_node.clone(),
             local_node_record.clone(),
             signer,
@@ -88,6 +104,7 @@ impl MojaveNode {
             based_context,
         )
         .await?;
+        startup.phase("p2p");
 
         // Create SyncManager
         let syncer = Arc::new(
@@ -101,9 +118,11 @@ impl MojaveNode {
             )
             .await,
         );
+        startup.phase("sync");
 
         Ok(MojaveNode {
             data_dir: data_dir_str.to_string(),
+            datadir_lock,
             genesis,
             store,
             rollup_store,
@@ -115,6 +134,7 @@ impl MojaveNode {
             peer_table,
             peer_handler,
             p2p_context,
+            startup,
         })
     }
 
@@ -123,7 +143,15 @@ impl MojaveNode {
         options: &NodeOptions,
         registry: RpcRegistry<RpcApiContext>,
     ) -> Result<()> {
+        let mut startup = self.startup;
         let rpc_shutdown = self.cancel_token.child_token();
+        let registry = if options.rpc_read_only {
+            let denied = &options.rpc_read_only_denied_methods;
+            tracing::info!(?denied, "RPC read-only mode enabled");
+            registry.deny_methods(denied)
+        } else {
+            registry
+        };
 
         let jwt_secret = read_jwtsecret_file(
             options
@@ -164,10 +192,24 @@ impl MojaveNode {
             self.peer_handler,
             get_client_version(),
             self.rollup_store.clone(),
-            AsyncUniqueHeap::new(),
+            AsyncUniqueHeap::bounded(
+                options.pending_blocks_limit,
+                options.pending_blocks_eviction.into(),
+            ),
+            PendingHeap::bounded(
+                options.pending_blocks_limit,
+                options.pending_blocks_eviction,
+            ),
             rpc_shutdown.clone(),
             registry,
-        );
+            options.sequencer_verifying_key.clone(),
+            options.max_reorg_depth,
+            backfill_client(options)?,
+            Duration::from_secs(options.filter_ttl),
+            Duration::from_secs(options.filter_cleanup_interval),
+        )
+        .await?;
+        startup.phase("rpc");
 
         let health_socket_addr =
             get_http_socket_addr(&options.health_addr, &options.health_port).await?;
@@ -176,13 +218,13 @@ impl MojaveNode {
             self.cancel_token.clone().cancelled_owned(),
         )
         .await?;
+        startup.phase("health");
+        startup.ready();
 
         tokio::pin!(api_task);
         tokio::select! {
-            res = &mut api_task => {
-                if let Err(error) = res {
-                    tracing::error!("API task returned error: {}", error);
-                }
+            _ = &mut api_task => {
+                tracing::error!("API task stopped unexpectedly");
             }
             res = health_handle => {
                 if let Err(error) = res {
@@ -197,7 +239,7 @@ impl MojaveNode {
                 let node_config = NodeConfigFile::new(self.peer_table, self.local_node_record.lock().await.clone()).await;
                 store_node_config_file(node_config, node_config_path).await;
 
-                if let Err(_elapsed) = tokio::time::timeout(std::time::Duration::from_secs(10), api_task).await {
+                if let Err(_elapsed) = tokio::time::timeout(SHUTDOWN_TIMEOUT, api_task).await {
                     tracing::warn!("Timed out waiting for API to stop");
                 }
                 tracing::info!("Successfully shut down the full node.");
@@ -208,6 +250,13 @@ impl MojaveNode {
     }
 
     pub async fn validate_node_options(options: &NodeOptions) -> Result<()> {
+        if !options.genesis_overrides.is_empty() && !matches!(options.network, Network::DefaultNet)
+        {
+            return Err(Error::Config(format!(
+                "genesis overrides only apply to the default network, got {}",
+                options.network
+            )));
+        }
         ensure_udp_port_available(&options.p2p_addr, &options.p2p_port).await?;
         if let (Some(addr), Some(port)) = (&options.http_addr, &options.http_port) {
             ensure_tcp_port_available(addr, port).await?;
@@ -225,6 +274,18 @@ impl MojaveNode {
     }
 }
 
+/// Client for fetching missing blocks, when any full node to sync from is set.
+fn backfill_client(options: &NodeOptions) -> Result<Option<MojaveClient>> {
+    if options.sync_full_node_urls.is_empty() {
+        return Ok(None);
+    }
+    let client = MojaveClient::builder()
+        .full_node_urls(options.sync_full_node_urls.clone())
+        .block_range_concurrency(options.sync_pipeline_depth)
+        .build()?;
+    Ok(Some(client))
+}
+
 pub fn get_client_version() -> String {
     format!("{}/v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),)
 }
@@ -20,6 +20,8 @@ pub async fn start_network(
     bootnodes: Vec<Node>,
     network: &Network,
     data_dir: &str,
+    bootnodes_url: Option<&str>,
+    strict_bootnodes: bool,
     local_p2p_node: Node,
     local_node_record: Arc<Mutex<NodeRecord>>,
     signer: SecretKey,
@@ -29,7 +31,14 @@ pub async fn start_network(
     blockchain: Arc<Blockchain>,
     based_context: Option<P2PBasedContext>,
 ) -> Result<P2PContext> {
-    let bootnodes = get_bootnodes(bootnodes, network, data_dir).await;
+    let bootnodes = get_bootnodes(
+        bootnodes,
+        network,
+        data_dir,
+        bootnodes_url,
+        strict_bootnodes,
+    )
+    .await?;
 
     let context = P2PContext::new(
         local_p2p_node,
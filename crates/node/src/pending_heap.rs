@@ -1,4 +1,8 @@
-use mojave_utils::{ordered_block::OrderedBlock, unique_heap::AsyncUniqueHeap};
+use crate::{metrics::PENDING_BLOCKS_DEPTH, types::PendingBlockEviction};
+use mojave_utils::{
+    ordered_block::OrderedBlock,
+    unique_heap::{AsyncUniqueHeap, EvictionPolicy},
+};
 
 #[derive(Clone, Debug)]
 pub struct PendingHeap {
@@ -12,12 +16,29 @@ impl PendingHeap {
         }
     }
 
+    /// Creates a heap holding at most `max_len` blocks.
+    pub fn bounded(max_len: usize, eviction: PendingBlockEviction) -> Self {
+        Self {
+            inner: AsyncUniqueHeap::bounded(max_len, eviction.into()),
+        }
+    }
+
     pub async fn push_signed(&self, block: OrderedBlock) -> bool {
-        self.inner.push(block).await
+        let (inserted, evicted) = self.inner.push_evicting(block).await;
+        if let Some(OrderedBlock(evicted)) = evicted {
+            tracing::warn!(
+                number = evicted.header.number,
+                "Pending block queue is full, dropped a block"
+            );
+        }
+        self.record_depth().await;
+        inserted
     }
 
     pub async fn pop(&self) -> Option<OrderedBlock> {
-        self.inner.pop().await
+        let block = self.inner.pop().await;
+        self.record_depth().await;
+        block
     }
 
     pub async fn peek(&self) -> Option<OrderedBlock> {
@@ -27,6 +48,28 @@ impl PendingHeap {
     pub async fn peek_wait(&self) -> OrderedBlock {
         self.inner.peek_wait().await
     }
+
+    pub async fn len(&self) -> usize {
+        self.inner.len().await
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.inner.is_empty().await
+    }
+
+    async fn record_depth(&self) {
+        PENDING_BLOCKS_DEPTH.set(self.len().await as i64);
+    }
+}
+
+impl From<PendingBlockEviction> for EvictionPolicy {
+    fn from(eviction: PendingBlockEviction) -> Self {
+        match eviction {
+            // Higher block numbers have lower priority, so they pop last.
+            PendingBlockEviction::Highest => EvictionPolicy::Last,
+            PendingBlockEviction::Oldest => EvictionPolicy::Oldest,
+        }
+    }
 }
 
 impl Default for PendingHeap {
@@ -166,6 +209,39 @@ mod tests {
         assert!(block_large > block_max);
     }
 
+    #[tokio::test]
+    async fn test_bounded_drops_highest_block() {
+        let heap = PendingHeap::bounded(3, PendingBlockEviction::Highest);
+        for number in [4, 2, 6] {
+            assert!(heap.push_signed(create_test_block(number)).await);
+        }
+        assert!(heap.push_signed(create_test_block(3)).await);
+        assert!(!heap.push_signed(create_test_block(9)).await);
+
+        assert_eq!(heap.len().await, 3);
+        let mut numbers = Vec::new();
+        while let Some(block) = heap.pop().await {
+            numbers.push(block.0.header.number);
+        }
+        assert_eq!(numbers, vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_bounded_drops_oldest_block() {
+        let heap = PendingHeap::bounded(3, PendingBlockEviction::Oldest);
+        for number in [4, 2, 6, 9] {
+            assert!(heap.push_signed(create_test_block(number)).await);
+        }
+
+        assert_eq!(heap.len().await, 3);
+        let mut numbers = Vec::new();
+        while let Some(block) = heap.pop().await {
+            numbers.push(block.0.header.number);
+        }
+        assert_eq!(numbers, vec![2, 6, 9]);
+        assert!(heap.is_empty().await);
+    }
+
     #[tokio::test]
     async fn test_concurrent_block_insertion() {
         use std::sync::Arc;
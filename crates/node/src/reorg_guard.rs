@@ -0,0 +1,94 @@
+use mojave_utils::rpc::error::{Error, Result};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Deepest reorg a full node follows unless `--max-reorg-depth` says otherwise.
+pub const DEFAULT_MAX_REORG_DEPTH: u64 = 64;
+
+/// Stops block ingestion once a reorg deeper than `max_depth` is seen.
+///
+/// The halt is sticky: a reorg that deep points at an attack or a severe
+/// fault, so the node waits for an operator instead of rewriting history.
+#[derive(Clone, Debug)]
+pub struct ReorgGuard {
+    max_depth: u64,
+    halted: Arc<AtomicBool>,
+}
+
+impl ReorgGuard {
+    pub fn new(max_depth: u64) -> Self {
+        Self {
+            max_depth,
+            halted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    /// Fails once ingestion has been halted.
+    pub fn ensure_active(&self) -> Result<()> {
+        if self.is_halted() {
+            return Err(Error::Internal(
+                "Block ingestion is halted after a reorg deeper than the configured limit"
+                    .to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks a block at `number` that conflicts with the canonical chain
+    /// whose head is `head`, halting ingestion if the reorg is too deep.
+    pub fn check(&self, head: u64, number: u64) -> Result<()> {
+        self.ensure_active()?;
+        let depth = head.saturating_sub(number) + 1;
+        if depth > self.max_depth {
+            self.halted.store(true, Ordering::SeqCst);
+            tracing::error!(
+                head,
+                number,
+                depth,
+                max_depth = self.max_depth,
+                "CRITICAL: reorg exceeds the maximum depth, halting block ingestion"
+            );
+            return Err(Error::Internal(format!(
+                "Reorg of depth {depth} exceeds the maximum of {}",
+                self.max_depth
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ReorgGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_REORG_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_reorgs_up_to_the_limit() {
+        let guard = ReorgGuard::new(3);
+        guard.check(10, 10).unwrap();
+        guard.check(10, 8).unwrap();
+        assert!(!guard.is_halted());
+    }
+
+    #[test]
+    fn deeper_reorg_halts_ingestion_for_good() {
+        let guard = ReorgGuard::new(3);
+        assert!(guard.check(10, 7).is_err());
+        assert!(guard.is_halted());
+
+        // Shallow reorgs and fresh blocks are refused from now on.
+        assert!(guard.check(10, 10).is_err());
+        assert!(guard.clone().ensure_active().is_err());
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{
     pending_heap::PendingHeap,
-    rpc::{context::RpcApiContext, tasks::spawn_filter_cleanup_task},
+    reorg_guard::ReorgGuard,
+    rpc::{
+        context::RpcApiContext,
+        handlers,
+        tasks::{spawn_block_backfill_task, spawn_block_import_task, spawn_filter_cleanup_task},
+    },
 };
 use ethrex_blockchain::Blockchain;
 use ethrex_common::{Bytes, types::DEFAULT_BUILDER_GAS_CEIL};
@@ -12,17 +17,23 @@ use ethrex_p2p::{
 use ethrex_rpc::{GasTipEstimator, NodeData, RpcApiContext as L1Context, RpcErr};
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
+use mojave_client::MojaveClient;
 use mojave_rpc_server::{RpcRegistry, RpcService};
+use mojave_signature::VerifyingKey;
 use mojave_utils::{ordered_block::OrderedBlock, rpc::error::Result, unique_heap::AsyncUniqueHeap};
 use std::{
     collections::HashMap,
     net::SocketAddr,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use tokio::{net::TcpListener, sync::Mutex as TokioMutex};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+/// Binds the RPC listener and spawns the background tasks, returning a future
+/// that serves requests until `shutdown_token` is cancelled and then waits for
+/// every task to stop.
 #[expect(clippy::too_many_arguments)]
 pub async fn start_api(
     http_addr: SocketAddr,
@@ -37,9 +48,15 @@ pub async fn start_api(
     client_version: String,
     rollup_store: StoreRollup,
     block_queue: AsyncUniqueHeap<OrderedBlock, u64>,
+    pending_signed_blocks: PendingHeap,
     shutdown_token: CancellationToken,
-    registry: RpcRegistry<RpcApiContext>,
-) -> Result<()> {
+    mut registry: RpcRegistry<RpcApiContext>,
+    sequencer_verifying_key: Option<VerifyingKey>,
+    max_reorg_depth: u64,
+    backfill_client: Option<MojaveClient>,
+    filter_ttl: Duration,
+    filter_cleanup_interval: Duration,
+) -> Result<impl Future<Output = ()>> {
     let active_filters = Arc::new(Mutex::new(HashMap::new()));
     let context = RpcApiContext {
         l1_context: L1Context {
@@ -61,11 +78,29 @@ pub async fn start_api(
         },
         rollup_store,
         block_queue,
-        pending_signed_blocks: PendingHeap::new(),
+        pending_signed_blocks,
+        sequencer_verifying_key,
+        reorg_guard: ReorgGuard::new(max_reorg_depth),
     };
 
+    let http_listener = TcpListener::bind(http_addr)
+        .await
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+
     // Periodically clean up the active filters for the filters endpoints.
-    let filter_handle = spawn_filter_cleanup_task(active_filters.clone(), shutdown_token.clone());
+    let filter_handle = spawn_filter_cleanup_task(
+        active_filters.clone(),
+        filter_ttl,
+        filter_cleanup_interval,
+        shutdown_token.clone(),
+    );
+
+    // Apply the queued blocks to the chain as they become contiguous.
+    let import_handle = spawn_block_import_task(context.clone(), shutdown_token.clone());
+
+    // Catch up on blocks missed while offline or lagging behind.
+    let backfill_handle = backfill_client
+        .map(|client| spawn_block_backfill_task(context.clone(), client, shutdown_token.clone()));
 
     // // Build RPC registry and service
     // let registry: RpcRegistry<RpcApiContext> = RpcRegistry::new()
@@ -73,11 +108,9 @@ pub async fn start_api(
     //         Box::pin(ethrex_rpc::map_eth_requests(req, ctx.l1_context))
     //     });
     // Passing registry instead of building here to avoid bloating this function
+    mojave_rpc_macros::register_all!(registry, [handlers::send_broadcast_block]);
     let service = RpcService::new(context.clone(), registry).with_permissive_cors();
     let http_router = service.router();
-    let http_listener = TcpListener::bind(http_addr)
-        .await
-        .map_err(|error| RpcErr::Internal(error.to_string()))?;
     let http_server = axum::serve(http_listener, http_router)
         .with_graceful_shutdown(shutdown_token.cancelled_owned())
         .into_future();
@@ -85,19 +118,28 @@ pub async fn start_api(
 
     info!("Not starting Auth-RPC server. The address passed as argument is {authrpc_addr}");
 
-    let _ = tokio::try_join!(
-        async {
-            http_server
-                .await
-                .map_err(|e| RpcErr::Internal(e.to_string()))
-        },
-        async {
-            filter_handle
-                .await
-                .map_err(|e| RpcErr::Internal(e.to_string()))
-        },
-    )
-    .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
-
-    Ok(())
+    Ok(async move {
+        // Wait for every task, even when one of them fails, so none is left
+        // running after the API returns.
+        let backfill = async {
+            match backfill_handle {
+                Some(handle) => handle.await,
+                None => Ok(()),
+            }
+        };
+        let (http_result, filter_result, import_result, backfill_result) =
+            tokio::join!(http_server, filter_handle, import_handle, backfill);
+        if let Err(error) = http_result {
+            info!("Error shutting down HTTP server: {error:?}");
+        }
+        if let Err(error) = filter_result {
+            info!("Error shutting down filter clean task: {error:?}");
+        }
+        if let Err(error) = import_result {
+            info!("Error shutting down block import task: {error:?}");
+        }
+        if let Err(error) = backfill_result {
+            info!("Error shutting down block backfill task: {error:?}");
+        }
+    })
 }
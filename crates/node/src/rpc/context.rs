@@ -1,6 +1,7 @@
-use crate::pending_heap::PendingHeap;
+use crate::{pending_heap::PendingHeap, reorg_guard::ReorgGuard};
 use ethrex_rpc::RpcApiContext as L1Context;
 use ethrex_storage_rollup::StoreRollup;
+use mojave_signature::VerifyingKey;
 use mojave_utils::{ordered_block::OrderedBlock, unique_heap::AsyncUniqueHeap};
 
 #[derive(Clone, Debug)]
@@ -9,4 +10,97 @@ pub struct RpcApiContext {
     pub rollup_store: StoreRollup,
     pub block_queue: AsyncUniqueHeap<OrderedBlock, u64>,
     pub pending_signed_blocks: PendingHeap,
+    /// Only blocks signed by this key are ingested, when set.
+    pub sequencer_verifying_key: Option<VerifyingKey>,
+    pub reorg_guard: ReorgGuard,
+}
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::*;
+    use ethrex_blockchain::Blockchain;
+    use ethrex_common::{
+        Bytes, H512,
+        types::{Block, BlockBody, BlockHeader, DEFAULT_BUILDER_GAS_CEIL, Genesis},
+    };
+    use ethrex_p2p::{
+        peer_handler::PeerHandler,
+        sync_manager::SyncManager,
+        types::{Node, NodeRecord},
+    };
+    use ethrex_rpc::{GasTipEstimator, NodeData};
+    use ethrex_storage::{EngineType, Store};
+    use ethrex_storage_rollup::EngineTypeRollup;
+    use mojave_client::types::SignedBlock;
+    use mojave_signature::{SigningKey, types::Signer};
+    use std::{
+        collections::HashMap,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+    use tokio::sync::Mutex as TokioMutex;
+
+    const TEST_GENESIS: &str = include_str!("../../../../tests/mock-genesis.json");
+    pub(crate) const SEQUENCER_KEY: &str =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    pub(crate) const OTHER_KEY: &str =
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    /// Empty block number `number`, signed with `key`.
+    pub(crate) fn signed_by(key: &str, number: u64) -> SignedBlock {
+        let signing_key = SigningKey::from_str(key).unwrap();
+        let header = BlockHeader {
+            number,
+            ..Default::default()
+        };
+        let block = Block::new(header, BlockBody::default());
+        let signature = signing_key.sign(&block.header.hash()).unwrap();
+        SignedBlock {
+            block,
+            signature,
+            verifying_key: signing_key.verifying_key(),
+        }
+    }
+
+    /// Context over an in-memory store holding only the test genesis, which
+    /// accepts blocks signed with [`SEQUENCER_KEY`].
+    pub(crate) async fn make_ctx(max_reorg_depth: u64) -> RpcApiContext {
+        let storage = Store::new("", EngineType::InMemory).unwrap();
+        let genesis: Genesis = serde_json::from_str(TEST_GENESIS).unwrap();
+        storage.add_initial_state(genesis).await.unwrap();
+        let blockchain = Arc::new(Blockchain::default_with_store(storage.clone()));
+        let rollup_store = StoreRollup::new(".", EngineTypeRollup::InMemory).unwrap();
+        rollup_store.init().await.unwrap();
+
+        let local_p2p_node = Node::new("127.0.0.1".parse().unwrap(), 30303, 30303, H512::zero());
+        let secret_key = secp256k1::SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let local_node_record = NodeRecord::from_node(&local_p2p_node, 1, &secret_key).unwrap();
+
+        RpcApiContext {
+            l1_context: L1Context {
+                gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+                storage,
+                blockchain,
+                active_filters: Arc::new(Mutex::new(HashMap::new())),
+                syncer: Arc::new(SyncManager::dummy()),
+                peer_handler: PeerHandler::dummy(),
+                node_data: NodeData {
+                    jwt_secret: Bytes::new(),
+                    local_p2p_node,
+                    local_node_record,
+                    client_version: "mojave/test".to_owned(),
+                    extra_data: Bytes::new(),
+                },
+                gas_tip_estimator: Arc::new(TokioMutex::new(GasTipEstimator::new())),
+                log_filter_handler: None,
+            },
+            rollup_store,
+            block_queue: AsyncUniqueHeap::new(),
+            pending_signed_blocks: PendingHeap::new(),
+            sequencer_verifying_key: Some(
+                SigningKey::from_str(SEQUENCER_KEY).unwrap().verifying_key(),
+            ),
+            reorg_guard: ReorgGuard::new(max_reorg_depth),
+        }
+    }
 }
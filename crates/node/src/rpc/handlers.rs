@@ -0,0 +1,66 @@
+use crate::{rpc::context::RpcApiContext, services::block::ingest_signed_block};
+use mojave_client::types::SignedBlock;
+
+/// Receives a block broadcast by the sequencer. The block is queued for
+/// import only if it is signed by the configured sequencer key and does not
+/// reorg deeper than `--max-reorg-depth`.
+#[mojave_rpc_macros::rpc(namespace = "moj", method = "sendBroadcastBlock")]
+pub async fn send_broadcast_block(
+    ctx: RpcApiContext,
+    signed: SignedBlock,
+) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
+    ingest_signed_block(&ctx, signed).await?;
+    Ok(serde_json::Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::context::test_utils::{OTHER_KEY, SEQUENCER_KEY, make_ctx, signed_by};
+    use mojave_rpc_core::RpcErr;
+
+    #[tokio::test]
+    async fn send_broadcast_block_queues_sequencer_block() {
+        let ctx = make_ctx(64).await;
+
+        super::send_broadcast_block(ctx.clone(), signed_by(SEQUENCER_KEY, 1))
+            .await
+            .unwrap();
+
+        let queued = ctx.pending_signed_blocks.peek().await.unwrap();
+        assert_eq!(queued.0.header.number, 1);
+    }
+
+    #[tokio::test]
+    async fn send_broadcast_block_rejects_forged_block() {
+        let ctx = make_ctx(64).await;
+
+        let err = super::send_broadcast_block(ctx.clone(), signed_by(OTHER_KEY, 1))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, RpcErr::BadParams(_)));
+        assert!(ctx.pending_signed_blocks.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn send_broadcast_block_halts_on_reorg_deeper_than_max() {
+        // The genesis is the head, so a different block 0 is a reorg of depth one.
+        let ctx = make_ctx(0).await;
+
+        let err = super::send_broadcast_block(ctx.clone(), signed_by(SEQUENCER_KEY, 0))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, RpcErr::Internal(_)));
+        assert!(ctx.reorg_guard.is_halted());
+        assert!(ctx.pending_signed_blocks.is_empty().await);
+
+        // Later blocks are refused too, even when they extend the chain.
+        assert!(
+            super::send_broadcast_block(ctx.clone(), signed_by(SEQUENCER_KEY, 1))
+                .await
+                .is_err()
+        );
+    }
+}
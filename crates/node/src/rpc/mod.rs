@@ -1,5 +1,11 @@
 mod api;
 pub mod context;
+mod handlers;
 mod tasks;
 
 pub use api::start_api;
+
+/// Methods refused by a node running with `rpc_read_only` unless
+/// `rpc_read_only_denied_methods` says otherwise: everything that submits
+/// transactions.
+pub const READ_ONLY_DENIED_METHODS: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
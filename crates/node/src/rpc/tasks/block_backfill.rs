@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use ethrex_common::types::Block;
+use futures::StreamExt;
+use mojave_client::MojaveClient;
+use mojave_utils::{
+    ordered_block::OrderedBlock,
+    rpc::error::{Error, Result},
+};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::rpc::context::RpcApiContext;
+
+/// How often the pending queue is checked for a gap behind its lowest block.
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most blocks requested from the full nodes in one backfill round.
+const MAX_BACKFILL_BLOCKS: u64 = 256;
+
+/// Fills the gap between the local head and the pending blocks from the full
+/// nodes `client` points at.
+pub(crate) fn spawn_block_backfill_task(
+    ctx: RpcApiContext,
+    client: MojaveClient,
+    shutdown_token: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(BACKFILL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(error) = backfill_gap(&ctx, &client, &shutdown_token).await {
+                        tracing::warn!(%error, "Block backfill failed");
+                    }
+                }
+                _ = shutdown_token.cancelled() => {
+                    tracing::info!("Shutting down block backfill task");
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// Fetches the missing blocks several at a time and queues them in the
+/// pending heap, which hands them out in block order. At most
+/// [`MAX_BACKFILL_BLOCKS`] are fetched per round, starting from the top of the
+/// gap so each batch can be checked against the block above it. Stops early on
+/// shutdown, dropping the unfinished batch.
+async fn backfill_gap(
+    ctx: &RpcApiContext,
+    client: &MojaveClient,
+    shutdown_token: &CancellationToken,
+) -> Result<()> {
+    let Some(OrderedBlock(next)) = ctx.pending_signed_blocks.peek().await else {
+        return Ok(());
+    };
+    let head = ctx
+        .l1_context
+        .storage
+        .get_latest_block_number()
+        .await
+        .map_err(|error| Error::Internal(error.to_string()))?;
+    let Some((from, to)) = missing_range(head, next.header.number) else {
+        return Ok(());
+    };
+
+    tracing::info!(from, to, "Backfilling missing blocks");
+    let mut blocks = Vec::with_capacity((to - from + 1) as usize);
+    let mut stream = std::pin::pin!(client.get_blocks_range(from, to));
+    while let Some(block) = tokio::select! {
+        block = stream.next() => block,
+        _ = shutdown_token.cancelled() => return Ok(()),
+    } {
+        blocks.push(block.map_err(|error| Error::Internal(error.to_string()))?);
+    }
+
+    verify_chain_to(&blocks, &next)?;
+    for block in blocks {
+        ctx.pending_signed_blocks
+            .push_signed(OrderedBlock(block))
+            .await;
+    }
+    Ok(())
+}
+
+/// Blocks after `head` that are missing before `next_pending`, if any, capped
+/// to the [`MAX_BACKFILL_BLOCKS`] right below `next_pending`.
+fn missing_range(head: u64, next_pending: u64) -> Option<(u64, u64)> {
+    (next_pending > head + 1).then(|| {
+        let to = next_pending - 1;
+        (
+            (head + 1).max(to.saturating_sub(MAX_BACKFILL_BLOCKS - 1)),
+            to,
+        )
+    })
+}
+
+/// Checks that `blocks` form a chain ending right below `anchor`.
+///
+/// Fetched blocks carry no sequencer signature, but `anchor` is either signed
+/// or was itself checked this way, and every header commits to its parent's
+/// hash. A full node serving a forged block therefore breaks the chain.
+fn verify_chain_to(blocks: &[Block], anchor: &Block) -> Result<()> {
+    let mut child = &anchor.header;
+    for block in blocks.iter().rev() {
+        if block.header.number + 1 != child.number || block.hash() != child.parent_hash {
+            return Err(Error::Internal(format!(
+                "Backfilled block {} is not the parent of block {}",
+                block.header.number, child.number
+            )));
+        }
+        child = &block.header;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::context::test_utils::make_ctx;
+    use ethrex_common::types::{BlockBody, BlockHeader};
+    use std::sync::Arc;
+    use tokio::{net::TcpListener, sync::Notify};
+
+    #[test]
+    fn missing_range_covers_the_gap_only() {
+        assert_eq!(missing_range(10, 11), None);
+        assert_eq!(missing_range(10, 5), None);
+        assert_eq!(missing_range(10, 12), Some((11, 11)));
+        assert_eq!(missing_range(0, 100), Some((1, 99)));
+    }
+
+    #[test]
+    fn missing_range_is_capped_below_the_pending_block() {
+        assert_eq!(
+            missing_range(0, 1000),
+            Some((1000 - MAX_BACKFILL_BLOCKS, 999))
+        );
+    }
+
+    fn chain(from: u64, len: u64) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        for number in from..from + len {
+            let header = BlockHeader {
+                number,
+                parent_hash: blocks.last().map(Block::hash).unwrap_or_default(),
+                ..Default::default()
+            };
+            blocks.push(Block::new(header, BlockBody::default()));
+        }
+        blocks
+    }
+
+    #[test]
+    fn accepts_blocks_linked_to_the_pending_block() {
+        let mut blocks = chain(5, 4);
+        let anchor = blocks.pop().unwrap();
+        verify_chain_to(&blocks, &anchor).unwrap();
+    }
+
+    #[test]
+    fn rejects_forged_backfilled_block() {
+        let mut blocks = chain(5, 4);
+        let anchor = blocks.pop().unwrap();
+        let forged = BlockHeader {
+            number: blocks[1].header.number,
+            parent_hash: blocks[1].header.parent_hash,
+            gas_used: 1,
+            ..Default::default()
+        };
+        blocks[1] = Block::new(forged, BlockBody::default());
+        assert!(verify_chain_to(&blocks, &anchor).is_err());
+
+        // A block missing from the range breaks the chain too.
+        let mut blocks = chain(5, 4);
+        let anchor = blocks.pop().unwrap();
+        blocks.remove(2);
+        assert!(verify_chain_to(&blocks, &anchor).is_err());
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_stalled_backfill_returns_within_the_shutdown_timeout() {
+        // A full node that accepts requests but never answers them.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requested = Arc::new(Notify::new());
+        let notify = requested.clone();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+                notify.notify_one();
+            }
+        });
+        let client = MojaveClient::builder()
+            .full_node_urls([url])
+            .timeout(Duration::from_secs(3600))
+            .build()
+            .unwrap();
+
+        let ctx = make_ctx(64).await;
+        let header = BlockHeader {
+            number: 100,
+            ..Default::default()
+        };
+        ctx.pending_signed_blocks
+            .push_signed(OrderedBlock(Block::new(header, BlockBody::default())))
+            .await;
+
+        let token = CancellationToken::new();
+        let handle = spawn_block_backfill_task(ctx, client, token.clone());
+        requested.notified().await;
+
+        token.cancel();
+        tokio::time::timeout(crate::node::SHUTDOWN_TIMEOUT, handle)
+            .await
+            .expect("block backfill task outlived the shutdown timeout")
+            .unwrap();
+    }
+}
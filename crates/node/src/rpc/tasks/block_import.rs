@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::{rpc::context::RpcApiContext, services::block::import_ready_blocks};
+
+/// How often the pending queue is checked for blocks that extend the head.
+const IMPORT_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Applies the blocks queued by `moj_sendBroadcastBlock` and the backfill
+/// task to the chain, in block order.
+pub(crate) fn spawn_block_import_task(
+    ctx: RpcApiContext,
+    shutdown_token: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::task::spawn(async move {
+        let mut interval = tokio::time::interval(IMPORT_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(error) = import_ready_blocks(&ctx).await {
+                        tracing::warn!(%error, "Block import failed");
+                    }
+                }
+                _ = shutdown_token.cancelled() => {
+                    tracing::info!("Shutting down block import task");
+                    break;
+                }
+            }
+        }
+    })
+}
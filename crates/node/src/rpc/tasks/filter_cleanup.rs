@@ -4,25 +4,19 @@ use ethrex_rpc::ActiveFilters;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
-pub const FILTER_DURATION: Duration = {
-    if cfg!(test) {
-        Duration::from_secs(1)
-    } else {
-        Duration::from_secs(5 * 60)
-    }
-};
-
 pub(crate) fn spawn_filter_cleanup_task(
     active_filters: ActiveFilters,
+    filter_ttl: Duration,
+    cleanup_interval: Duration,
     shutdown_token: CancellationToken,
 ) -> JoinHandle<()> {
     tokio::task::spawn(async move {
-        let mut interval = tokio::time::interval(FILTER_DURATION);
+        let mut interval = tokio::time::interval(cleanup_interval);
         loop {
             tokio::select! {
                 _ = interval.tick() => {
                     tracing::info!("Running filter clean task");
-                    ethrex_rpc::clean_outdated_filters(active_filters.clone(), FILTER_DURATION);
+                    ethrex_rpc::clean_outdated_filters(active_filters.clone(), filter_ttl);
                     tracing::info!("Filter clean task complete");
                 }
                 _ = shutdown_token.cancelled() => {
@@ -33,3 +27,71 @@ pub(crate) fn spawn_filter_cleanup_task(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::context::{RpcApiContext, test_utils::make_ctx};
+    use mojave_rpc_core::RpcRequest;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    /// Installs an `eth_newFilter` filter and returns its id.
+    async fn new_filter(ctx: &RpcApiContext) -> u64 {
+        let request: RpcRequest = serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "method": "eth_newFilter", "params": [{ "fromBlock": "0x0" }]
+        }))
+        .unwrap();
+        let id = ethrex_rpc::map_eth_requests(&request, ctx.l1_context.clone())
+            .await
+            .unwrap();
+        u64::from_str_radix(id.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+    }
+
+    #[tokio::test]
+    async fn expired_filters_are_purged_and_fresh_ones_kept() {
+        let ctx = make_ctx(64).await;
+        let filters = ctx.l1_context.active_filters.clone();
+        let ttl = Duration::from_secs(60);
+        let stale = new_filter(&ctx).await;
+        let fresh = new_filter(&ctx).await;
+        // Back-date the stale filter's timestamp, in seconds, past the TTL.
+        filters.lock().unwrap().get_mut(&stale).unwrap().0 -= 2 * ttl.as_secs();
+
+        let token = CancellationToken::new();
+        let handle = spawn_filter_cleanup_task(
+            filters.clone(),
+            ttl,
+            Duration::from_millis(50),
+            token.clone(),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let remaining = filters.lock().unwrap();
+        assert!(!remaining.contains_key(&stale));
+        assert!(remaining.contains_key(&fresh));
+        drop(remaining);
+
+        token.cancel();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stops_promptly_on_shutdown() {
+        let token = CancellationToken::new();
+        let handle = spawn_filter_cleanup_task(
+            Arc::new(Mutex::new(HashMap::new())),
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+            token.clone(),
+        );
+
+        token.cancel();
+        tokio::time::timeout(crate::node::SHUTDOWN_TIMEOUT, handle)
+            .await
+            .expect("filter clean task outlived the shutdown timeout")
+            .unwrap();
+    }
+}
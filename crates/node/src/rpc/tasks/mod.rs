@@ -1,3 +1,7 @@
+mod block_backfill;
+mod block_import;
 mod filter_cleanup;
 
+pub(crate) use block_backfill::spawn_block_backfill_task;
+pub(crate) use block_import::spawn_block_import_task;
 pub(crate) use filter_cleanup::spawn_filter_cleanup_task;
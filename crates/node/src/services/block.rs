@@ -1,16 +1,18 @@
 use crate::rpc::context::RpcApiContext;
+use ethrex_blockchain::fork_choice::apply_fork_choice;
+use ethrex_common::types::Block;
 use mojave_client::types::SignedBlock;
-use mojave_signature::types::Verifier;
+use mojave_signature::{VerifyingKey, types::Verifier};
 use mojave_utils::{
     ordered_block::OrderedBlock,
     rpc::error::{Error, Result},
 };
 
+/// Verifies a block pushed through `moj_sendBroadcastBlock` and queues it for
+/// import.
 pub async fn ingest_signed_block(ctx: &RpcApiContext, signed: SignedBlock) -> Result<()> {
-    signed
-        .verifying_key
-        .verify(&signed.block.header.hash(), &signed.signature)
-        .map_err(|error| Error::Internal(error.to_string()))?;
+    verify_block_signature(ctx.sequencer_verifying_key.as_ref(), &signed)?;
+    check_reorg_depth(ctx, &signed.block).await?;
 
     let block = signed.block;
     let number = block.header.number;
@@ -20,3 +22,148 @@ pub async fn ingest_signed_block(ctx: &RpcApiContext, signed: SignedBlock) -> Re
     tracing::info!("Received the block number: {}", number);
     Ok(())
 }
+
+/// Imports the pending blocks that extend the local head, lowest first, and
+/// returns how many were imported. Stops at the first gap, which the backfill
+/// task fills.
+pub async fn import_ready_blocks(ctx: &RpcApiContext) -> Result<usize> {
+    let storage = &ctx.l1_context.storage;
+    let mut imported = 0;
+    while let Some(OrderedBlock(next)) = ctx.pending_signed_blocks.peek().await {
+        ctx.reorg_guard.ensure_active()?;
+        let head = storage
+            .get_latest_block_number()
+            .await
+            .map_err(|error| Error::Internal(error.to_string()))?;
+        if next.header.number > head + 1 {
+            break;
+        }
+        let Some(OrderedBlock(block)) = ctx.pending_signed_blocks.pop().await else {
+            break;
+        };
+
+        let number = block.header.number;
+        let hash = block.hash();
+        let canonical = storage
+            .get_block_header(number)
+            .map_err(|error| Error::Internal(error.to_string()))?;
+        if canonical.is_some_and(|header| header.hash() == hash) {
+            continue;
+        }
+        ctx.l1_context
+            .blockchain
+            .add_block(&block)
+            .await
+            .map_err(|error| {
+                Error::Internal(format!("Failed to import block {number}: {error}"))
+            })?;
+        apply_fork_choice(storage, hash, hash, hash)
+            .await
+            .map_err(|error| {
+                Error::Internal(format!("Failed to make block {number} canonical: {error}"))
+            })?;
+        tracing::info!(number, "Imported block");
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+/// Runs the reorg guard when `block` conflicts with a canonical block we
+/// already have.
+async fn check_reorg_depth(ctx: &RpcApiContext, block: &Block) -> Result<()> {
+    ctx.reorg_guard.ensure_active()?;
+
+    let storage = &ctx.l1_context.storage;
+    let head = storage
+        .get_latest_block_number()
+        .await
+        .map_err(|error| Error::Internal(error.to_string()))?;
+    let number = block.header.number;
+    if number > head {
+        return Ok(());
+    }
+    let canonical = storage
+        .get_block_header(number)
+        .map_err(|error| Error::Internal(error.to_string()))?;
+    match canonical {
+        Some(header) if header.hash() != block.hash() => ctx.reorg_guard.check(head, number),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the signature over the block header hash.
+///
+/// With a configured sequencer key the block must be signed by that key,
+/// whatever key the sender attached. Without one, only the attached key is
+/// checked.
+pub fn verify_block_signature(
+    sequencer_key: Option<&VerifyingKey>,
+    signed: &SignedBlock,
+) -> Result<()> {
+    let header_hash = signed.block.header.hash();
+    match sequencer_key {
+        Some(key) => key.verify(&header_hash, &signed.signature).map_err(|error| {
+            tracing::warn!(number = signed.block.header.number, %error, "Rejected block not signed by the sequencer");
+            Error::BadParams("Block is not signed by the configured sequencer".to_owned())
+        }),
+        None => signed
+            .verifying_key
+            .verify(&header_hash, &signed.signature)
+            .map_err(|error| Error::Internal(error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::context::test_utils::{OTHER_KEY, SEQUENCER_KEY, make_ctx, signed_by};
+    use ethrex_common::types::{BlockBody, BlockHeader};
+    use mojave_signature::SigningKey;
+    use std::str::FromStr;
+
+    #[test]
+    fn accepts_block_signed_by_sequencer() {
+        let sequencer = SigningKey::from_str(SEQUENCER_KEY).unwrap().verifying_key();
+        verify_block_signature(Some(&sequencer), &signed_by(SEQUENCER_KEY, 0)).unwrap();
+    }
+
+    #[test]
+    fn rejects_block_signed_by_other_key() {
+        let sequencer = SigningKey::from_str(SEQUENCER_KEY).unwrap().verifying_key();
+        let forged = signed_by(OTHER_KEY, 0);
+
+        // Self-consistent, but not from the sequencer.
+        verify_block_signature(None, &forged).unwrap();
+        let err = verify_block_signature(Some(&sequencer), &forged).unwrap_err();
+        assert!(matches!(err, Error::BadParams(_)));
+    }
+
+    #[tokio::test]
+    async fn import_skips_known_blocks_and_stops_at_gap() {
+        let ctx = make_ctx(64).await;
+        let genesis = ctx
+            .l1_context
+            .storage
+            .get_block_by_number(0)
+            .await
+            .unwrap()
+            .unwrap();
+        let ahead = BlockHeader {
+            number: 5,
+            ..Default::default()
+        };
+        ctx.pending_signed_blocks
+            .push_signed(OrderedBlock(genesis))
+            .await;
+        ctx.pending_signed_blocks
+            .push_signed(OrderedBlock(Block::new(ahead, BlockBody::default())))
+            .await;
+
+        assert_eq!(import_ready_blocks(&ctx).await.unwrap(), 0);
+
+        // The known genesis is dropped, block 5 waits for the backfill.
+        assert_eq!(ctx.pending_signed_blocks.len().await, 1);
+        let waiting = ctx.pending_signed_blocks.peek().await.unwrap();
+        assert_eq!(waiting.0.header.number, 5);
+    }
+}
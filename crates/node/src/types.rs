@@ -1,3 +1,8 @@
+use crate::{
+    reorg_guard::DEFAULT_MAX_REORG_DEPTH,
+    rpc::READ_ONLY_DENIED_METHODS,
+    utils::{DatadirLock, StartupTimer},
+};
 use clap::ValueEnum;
 use ethrex_blockchain::Blockchain;
 use ethrex_common::types::Genesis;
@@ -8,12 +13,26 @@ use ethrex_p2p::{
 };
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
-use mojave_utils::network::Network;
+use mojave_signature::VerifyingKey;
+use mojave_utils::network::{GenesisOverrides, Network};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 
+/// Blocks fetched concurrently while catching up, unless configured otherwise.
+pub const DEFAULT_SYNC_PIPELINE_DEPTH: usize = 8;
+
+/// Signed blocks held while waiting for their predecessors, unless configured
+/// otherwise.
+pub const DEFAULT_PENDING_BLOCKS_LIMIT: usize = 4096;
+
+/// Seconds an `eth_newFilter` filter lives, unless configured otherwise.
+pub const DEFAULT_FILTER_TTL_SECS: u64 = 5 * 60;
+
+/// Seconds between purges of expired filters, unless configured otherwise.
+pub const DEFAULT_FILTER_CLEANUP_INTERVAL_SECS: u64 = 5 * 60;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct NodeConfigFile {
@@ -29,6 +48,17 @@ pub enum SyncMode {
     Snap,
 }
 
+/// Block dropped when the pending block queue is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PendingBlockEviction {
+    /// The block furthest ahead of the chain head.
+    #[default]
+    Highest,
+    /// The block that has been waiting the longest.
+    Oldest,
+}
+
 impl From<SyncMode> for ethrex_p2p::sync::SyncMode {
     fn from(mode: SyncMode) -> Self {
         match mode {
@@ -43,7 +73,13 @@ impl From<SyncMode> for ethrex_p2p::sync::SyncMode {
 #[serde(deny_unknown_fields)]
 pub struct NodeOptions {
     pub network: Network,
+    pub genesis_overrides: GenesisOverrides,
     pub bootnodes: Vec<Node>,
+    /// URL serving a JSON array of bootnodes, fetched at startup.
+    pub bootnodes_url: Option<String>,
+    /// Fail startup when the network's preset bootnodes file is malformed or
+    /// `bootnodes_url` cannot be fetched.
+    pub strict_bootnodes: bool,
     pub syncmode: SyncMode,
     pub sponsorable_addresses_file_path: Option<String>,
     pub datadir: String,
@@ -63,6 +99,27 @@ pub struct NodeOptions {
     pub discovery_port: String,
     pub health_addr: String,
     pub health_port: String,
+    /// Key of the sequencer whose blocks a full node accepts.
+    pub sequencer_verifying_key: Option<VerifyingKey>,
+    /// Deepest reorg the node follows before halting block ingestion.
+    pub max_reorg_depth: u64,
+    /// Full nodes that missing blocks are fetched from. Backfill is off when empty.
+    pub sync_full_node_urls: Vec<String>,
+    /// How many missing blocks are fetched concurrently.
+    pub sync_pipeline_depth: usize,
+    /// Most signed blocks held while waiting for their predecessors.
+    pub pending_blocks_limit: usize,
+    /// Which block is dropped once `pending_blocks_limit` is reached.
+    pub pending_blocks_eviction: PendingBlockEviction,
+    /// Refuse the RPC methods in `rpc_read_only_denied_methods`.
+    pub rpc_read_only: bool,
+    /// Methods refused in read-only mode. Defaults to
+    /// [`crate::rpc::READ_ONLY_DENIED_METHODS`].
+    pub rpc_read_only_denied_methods: Vec<String>,
+    /// Seconds a filter lives before the cleanup task drops it.
+    pub filter_ttl: u64,
+    /// Seconds between runs of the filter cleanup task.
+    pub filter_cleanup_interval: u64,
 }
 
 impl Default for NodeOptions {
@@ -79,7 +136,10 @@ impl Default for NodeOptions {
             discovery_addr: Default::default(),
             discovery_port: Default::default(),
             network: Network::Mainnet,
+            genesis_overrides: GenesisOverrides::default(),
             bootnodes: Default::default(),
+            bootnodes_url: None,
+            strict_bootnodes: false,
             datadir: Default::default(),
             syncmode: Default::default(),
             sponsorable_addresses_file_path: None,
@@ -89,6 +149,19 @@ impl Default for NodeOptions {
             force: false,
             health_addr: Default::default(),
             health_port: Default::default(),
+            sequencer_verifying_key: None,
+            max_reorg_depth: DEFAULT_MAX_REORG_DEPTH,
+            sync_full_node_urls: Vec::new(),
+            sync_pipeline_depth: DEFAULT_SYNC_PIPELINE_DEPTH,
+            pending_blocks_limit: DEFAULT_PENDING_BLOCKS_LIMIT,
+            pending_blocks_eviction: PendingBlockEviction::default(),
+            rpc_read_only: false,
+            rpc_read_only_denied_methods: READ_ONLY_DENIED_METHODS
+                .iter()
+                .map(|method| method.to_string())
+                .collect(),
+            filter_ttl: DEFAULT_FILTER_TTL_SECS,
+            filter_cleanup_interval: DEFAULT_FILTER_CLEANUP_INTERVAL_SECS,
         }
     }
 }
@@ -96,6 +169,8 @@ impl Default for NodeOptions {
 #[derive(Clone)]
 pub struct MojaveNode {
     pub data_dir: String,
+    /// Held for the node's lifetime so no other process opens the same datadir.
+    pub datadir_lock: Arc<DatadirLock>,
     pub genesis: Genesis,
     pub store: Store,
     pub rollup_store: StoreRollup,
@@ -107,4 +182,5 @@ pub struct MojaveNode {
     pub peer_table: Kademlia,
     pub peer_handler: PeerHandler,
     pub p2p_context: P2PContext,
+    pub startup: StartupTimer,
 }
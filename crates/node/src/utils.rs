@@ -8,13 +8,121 @@ use ethrex_p2p::{
     types::{Node, NodeRecord},
     utils::public_key_from_signing_key,
 };
-use mojave_utils::network::{Network, parse_socket_addr};
+use mojave_utils::{
+    daemon::is_process_running,
+    network::{BOOTNODES_FETCH_TIMEOUT, Network, fetch_bootnodes, parse_socket_addr},
+};
 use secp256k1::SecretKey;
 use std::{
+    fs::OpenOptions,
+    io::{ErrorKind, Write},
     net::Ipv4Addr,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
+
+/// Files of which at least one is present in every mojave datadir.
+const DATADIR_MARKERS: [&str; 3] = ["node.key", "node_config.json", "mdbx.dat"];
+
+/// Datadir name that selects the in-memory storage engine (see `open_store`).
+const IN_MEMORY_DATADIR: &str = "memory";
+
+/// Lock file holding the pid of the process that owns the datadir.
+const DATADIR_LOCK_FILE: &str = "mojave.lock";
+
+/// Advisory lock on a datadir, released when dropped.
+///
+/// The lock file records the owner's pid. A lock left behind by a process
+/// that is no longer running is treated as stale and taken over. A lock
+/// holding our own pid is held, e.g. by another part of this process.
+#[derive(Debug)]
+pub struct DatadirLock {
+    path: Option<PathBuf>,
+}
+
+impl DatadirLock {
+    pub fn acquire(data_dir: &Path) -> Result<Self> {
+        if data_dir.ends_with(IN_MEMORY_DATADIR) {
+            return Ok(Self { path: None });
+        }
+
+        let path = data_dir.join(DATADIR_LOCK_FILE);
+        // The second attempt follows the removal of a stale lock.
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id())?;
+                    return Ok(Self { path: Some(path) });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let holder = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|content| content.trim().parse::<u32>().ok());
+                    if let Some(pid) = holder
+                        && (pid == std::process::id() || is_process_running(pid))
+                    {
+                        return Err(Error::DatadirLocked { held_by_pid: pid });
+                    }
+                    warn!(
+                        "Removing stale datadir lock {:?} (holder: {:?})",
+                        path, holder
+                    );
+                    match std::fs::remove_file(&path) {
+                        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                        _ => {}
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(Error::Custom(format!(
+            "Failed to acquire datadir lock {path:?}"
+        )))
+    }
+}
+
+impl Drop for DatadirLock {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take()
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            warn!("Failed to remove datadir lock {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Logs how long each startup phase took, ending with a readiness line.
+#[derive(Clone, Copy, Debug)]
+pub struct StartupTimer {
+    started_at: Instant,
+    phase_started_at: Instant,
+}
+
+impl StartupTimer {
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started_at: now,
+            phase_started_at: now,
+        }
+    }
+
+    /// Logs the time spent since the previous phase ended and starts the next.
+    pub fn phase(&mut self, phase: &'static str) -> Duration {
+        let elapsed = self.phase_started_at.elapsed();
+        info!(phase, ?elapsed, "Startup phase complete");
+        self.phase_started_at = Instant::now();
+        elapsed
+    }
+
+    /// Logs the total startup time.
+    pub fn ready(&self) -> Duration {
+        let total = self.started_at.elapsed();
+        info!(startup = ?total, "Node ready");
+        total
+    }
+}
 
 impl NodeConfigFile {
     pub async fn new(table: Kademlia, node_record: NodeRecord) -> Self {
@@ -92,15 +200,24 @@ pub fn generate_jwt_secret() -> String {
     hex::encode(secret)
 }
 
+/// Resolves `data_dir` to an absolute path and makes sure it exists.
+///
+/// Relative paths are taken relative to the current working directory, not
+/// `$HOME`, so `--datadir .mojave/node` lands in `./.mojave/node`.
 pub async fn resolve_data_dir(data_dir: &str) -> Result<(PathBuf, String)> {
-    let path = match std::env::home_dir() {
-        Some(home) => home.join(data_dir),
-        None => PathBuf::from(".").join(data_dir),
-    };
+    let path = std::path::absolute(data_dir)?;
+    if let Some(legacy) = legacy_data_dir(Path::new(data_dir), &path, std::env::home_dir()) {
+        warn!(
+            "Found an existing datadir at {legacy:?}, but {data_dir:?} now resolves to {path:?}. \
+             Relative datadirs used to be taken from $HOME; pass --datadir {} to keep using it",
+            legacy.display()
+        );
+    }
 
-    // Create the directory in full recursion.
-    if let Some(parent) = path.parent() {
-        tokio::fs::create_dir_all(parent).await?;
+    // Create the datadir itself, including any missing parents. The in-memory
+    // store has nothing on disk.
+    if !path.ends_with(IN_MEMORY_DATADIR) {
+        tokio::fs::create_dir_all(&path).await?;
     }
 
     let s = path
@@ -110,24 +227,122 @@ pub async fn resolve_data_dir(data_dir: &str) -> Result<(PathBuf, String)> {
     Ok((path, s))
 }
 
+/// Where a relative `data_dir` was resolved before it was taken relative to the
+/// working directory, when a datadir exists there and not at `resolved`.
+fn legacy_data_dir(data_dir: &Path, resolved: &Path, home: Option<PathBuf>) -> Option<PathBuf> {
+    if data_dir.is_absolute() || is_mojave_datadir(resolved) {
+        return None;
+    }
+    let legacy = home?.join(data_dir);
+    (legacy != resolved && is_mojave_datadir(&legacy)).then_some(legacy)
+}
+
+/// Returns true if `path` contains any of the files a mojave node writes to its datadir.
+pub fn is_mojave_datadir(path: &Path) -> bool {
+    DATADIR_MARKERS
+        .iter()
+        .any(|marker| path.join(marker).exists())
+}
+
+/// Removes the database at `data_dir`.
+///
+/// Without `force` the removal must be confirmed on an interactive terminal and
+/// is refused otherwise. A missing or empty `data_dir`, such as a fresh one
+/// [`resolve_data_dir`] just created, has nothing to remove. Directories that
+/// don't look like a mojave datadir are never removed.
+pub async fn remove_db(data_dir: &Path, force: bool) -> Result<()> {
+    if !tokio::fs::try_exists(data_dir).await?
+        || tokio::fs::read_dir(data_dir)
+            .await?
+            .next_entry()
+            .await?
+            .is_none()
+    {
+        info!("No database found at {:?}, nothing to remove", data_dir);
+        return Ok(());
+    }
+
+    if !is_mojave_datadir(data_dir) {
+        return Err(Error::RefuseRemoveDatabase(format!(
+            "{data_dir:?} does not look like a mojave datadir"
+        )));
+    }
+
+    // Refuse to wipe a datadir another process is using.
+    drop(DatadirLock::acquire(data_dir)?);
+
+    if force {
+        warn!("--force is set, removing the database at {:?}", data_dir);
+    } else if !confirm_remove_db(data_dir)? {
+        return Err(Error::RefuseRemoveDatabase(
+            "removal was not confirmed".to_string(),
+        ));
+    }
+
+    tokio::fs::remove_dir_all(data_dir)
+        .await
+        .map_err(Error::ForceRemoveDatabase)?;
+    info!("Removed the database at {:?}", data_dir);
+    Ok(())
+}
+
+fn confirm_remove_db(data_dir: &Path) -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Err(Error::RefuseRemoveDatabase(
+            "confirmation required but stdin is not interactive, pass --force to skip it"
+                .to_string(),
+        ));
+    }
+
+    print!("Remove the database at {}? [y/N] ", data_dir.display());
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Collects the bootnodes to dial, including any published at
+/// `bootnodes_url`. With `strict`, a malformed preset bootnodes file or a
+/// failed fetch is an error instead of silently contributing no bootnodes.
 pub async fn get_bootnodes(
     mut bootnodes: Vec<Node>,
     network: &Network,
     data_dir: &str,
-) -> Vec<Node> {
+    bootnodes_url: Option<&str>,
+    strict: bool,
+) -> Result<Vec<Node>> {
     const NODE_CONFIG_FILE: &str = "node_config.json";
+    let preset = || {
+        if strict {
+            network.try_get_bootnodes()
+        } else {
+            Ok(network.get_bootnodes())
+        }
+    };
     match network {
         Network::Mainnet => {
             tracing::info!("Adding mainnet preset bootnodes");
-            bootnodes.extend(network.get_bootnodes());
+            bootnodes.extend(preset()?);
         }
         Network::Testnet => {
             tracing::info!("Adding testnet preset bootnodes");
-            bootnodes.extend(network.get_bootnodes());
+            bootnodes.extend(preset()?);
         }
         _ => {}
     }
 
+    if let Some(url) = bootnodes_url {
+        tracing::info!(url, "Fetching published bootnodes");
+        match fetch_bootnodes(url, BOOTNODES_FETCH_TIMEOUT).await {
+            Ok(published) => bootnodes.extend(published),
+            Err(e) if strict => return Err(e.into()),
+            Err(e) => tracing::warn!("Could not fetch bootnodes: {e}"),
+        }
+    }
+
     if bootnodes.is_empty() {
         tracing::warn!(
             "No bootnodes specified. This node will not be able to connect to the network."
@@ -143,7 +358,7 @@ pub async fn get_bootnodes(
         Err(e) => tracing::error!("Could not read from peers file: {e}"),
     };
 
-    bootnodes
+    Ok(bootnodes)
 }
 
 pub async fn get_local_p2p_node(
@@ -254,8 +469,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn resolve_data_dir_creates_parent_dirs_and_returns_full_path_string() {
-        // it creates parent dirs only, so use "parent/child" form to test
+    async fn resolve_data_dir_creates_datadir_and_returns_full_path_string() {
         let suffix = format!(
             "mojave_ut_parent_{}",
             SystemTime::now()
@@ -265,14 +479,124 @@ mod tests {
         );
         let data_dir = format!("{suffix}/child");
 
-        let (_, full) = resolve_data_dir(&data_dir).await.expect("ok");
-        // the full is in the form of $HOME/…/suffix/child
+        let (path, full) = resolve_data_dir(&data_dir).await.expect("ok");
+        // the full is in the form of $CWD/suffix/child
         let full_path = PathBuf::from(&full);
-        let parent = full_path.parent().expect("has parent");
-        assert!(parent.exists());
+        assert_eq!(full_path, path);
+        assert_eq!(full_path, std::env::current_dir().unwrap().join(&data_dir));
+        assert!(full_path.is_dir());
 
         // cleanup
-        let _ = fs::remove_dir_all(parent).await;
+        let _ = fs::remove_dir_all(full_path.parent().expect("has parent")).await;
+    }
+
+    #[tokio::test]
+    async fn datadir_lock_rejects_live_holder_and_takes_over_stale_lock() {
+        let tmp = unique_path("datadir_lock");
+        fs::create_dir_all(&tmp).await.unwrap();
+        let lock_path = tmp.join(DATADIR_LOCK_FILE);
+
+        // Held by another live process.
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        fs::write(&lock_path, child.id().to_string()).await.unwrap();
+        let err = DatadirLock::acquire(&tmp).unwrap_err();
+        assert!(matches!(err, Error::DatadirLocked { held_by_pid } if held_by_pid == child.id()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        // The holder is gone, so the lock is stale and can be taken over.
+        let lock = DatadirLock::acquire(&tmp).unwrap();
+        let content = fs::read_to_string(&lock_path).await.unwrap();
+        assert_eq!(content, std::process::id().to_string());
+
+        // Our own pid holds the lock too.
+        let err = DatadirLock::acquire(&tmp).unwrap_err();
+        assert!(
+            matches!(err, Error::DatadirLocked { held_by_pid } if held_by_pid == std::process::id())
+        );
+        drop(lock);
+        assert!(!lock_path.exists());
+
+        let _ = fs::remove_dir_all(&tmp).await;
+    }
+
+    #[tokio::test]
+    async fn remove_db_refuses_unrelated_directory() {
+        let tmp = unique_path("remove_db_unrelated");
+        fs::create_dir_all(&tmp).await.unwrap();
+        fs::write(tmp.join("important.txt"), b"keep me")
+            .await
+            .unwrap();
+
+        let err = remove_db(&tmp, true).await.unwrap_err();
+        assert!(matches!(err, Error::RefuseRemoveDatabase(_)));
+        assert!(tmp.join("important.txt").exists());
+
+        let _ = fs::remove_dir_all(&tmp).await;
+    }
+
+    #[tokio::test]
+    async fn remove_db_force_removes_mojave_datadir() {
+        let tmp = unique_path("remove_db_datadir");
+        fs::create_dir_all(&tmp).await.unwrap();
+        fs::write(tmp.join("node.key"), b"key").await.unwrap();
+
+        remove_db(&tmp, true).await.unwrap();
+        assert!(!tmp.exists());
+    }
+
+    #[tokio::test]
+    async fn remove_db_missing_directory_is_noop() {
+        let tmp = unique_path("remove_db_missing");
+        remove_db(&tmp, true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn legacy_datadir_under_home_is_detected() {
+        let home = unique_path("legacy_home");
+        let cwd = unique_path("legacy_cwd");
+        let data_dir = Path::new(".mojave/node");
+        let resolved = cwd.join(data_dir);
+        let legacy = home.join(data_dir);
+        fs::create_dir_all(&legacy).await.unwrap();
+
+        // Nothing to point at until the old location holds a datadir.
+        assert_eq!(
+            legacy_data_dir(data_dir, &resolved, Some(home.clone())),
+            None
+        );
+
+        fs::write(legacy.join("node.key"), b"key").await.unwrap();
+        assert_eq!(
+            legacy_data_dir(data_dir, &resolved, Some(home.clone())),
+            Some(legacy.clone())
+        );
+        assert_eq!(legacy_data_dir(&legacy, &legacy, Some(home.clone())), None);
+
+        // A datadir at the new location wins.
+        fs::create_dir_all(&resolved).await.unwrap();
+        fs::write(resolved.join("node.key"), b"key").await.unwrap();
+        assert_eq!(
+            legacy_data_dir(data_dir, &resolved, Some(home.clone())),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&home).await;
+        let _ = fs::remove_dir_all(&cwd).await;
+    }
+
+    #[tokio::test]
+    async fn remove_db_fresh_datadir_is_noop() {
+        let tmp = unique_path("remove_db_fresh");
+        let (data_dir, _) = resolve_data_dir(tmp.to_str().unwrap()).await.unwrap();
+
+        remove_db(&data_dir, true).await.unwrap();
+        assert!(data_dir.exists());
+
+        let _ = fs::remove_dir_all(&data_dir).await;
     }
 
     #[tokio::test]
@@ -296,7 +620,15 @@ mod tests {
         let tmp = unique_path("bootnodes_mainnet_dir");
         fs::create_dir_all(&tmp).await.unwrap();
 
-        let out = get_bootnodes(vec![], &Network::Mainnet, tmp.to_str().unwrap()).await;
+        let out = get_bootnodes(
+            vec![],
+            &Network::Mainnet,
+            tmp.to_str().unwrap(),
+            None,
+            false,
+        )
+        .await
+        .unwrap();
         // depend on preset being at least 1
 
         assert!(out.len() >= mojave_utils::network::MAINNET_BOOTNODES.len());
@@ -317,13 +649,47 @@ mod tests {
             vec![node.clone()],
             &Network::DefaultNet,
             tmp.to_str().unwrap(),
+            None,
+            true,
         )
-        .await;
+        .await
+        .unwrap();
         assert_eq!(out.len(), 1);
 
         let _ = fs::remove_dir_all(&tmp).await;
     }
 
+    #[tokio::test]
+    async fn get_bootnodes_unreachable_url_fails_only_when_strict() {
+        let tmp = unique_path("bootnodes_url_dir");
+        fs::create_dir_all(&tmp).await.unwrap();
+        let url = Some("http://127.0.0.1:1/bootnodes.json");
+
+        let out = get_bootnodes(
+            vec![],
+            &Network::DefaultNet,
+            tmp.to_str().unwrap(),
+            url,
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(out.is_empty());
+
+        let err = get_bootnodes(
+            vec![],
+            &Network::DefaultNet,
+            tmp.to_str().unwrap(),
+            url,
+            true,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, Error::UtilsNetworkError(_)));
+
+        let _ = fs::remove_dir_all(&tmp).await;
+    }
+
     #[tokio::test]
     async fn get_local_p2p_node_uses_local_ip_when_discovery_is_0_0_0_0() {
         let secret_key = SecretKey::new(&mut rand::thread_rng());
@@ -345,4 +711,15 @@ mod tests {
         let enode = node.enode_url();
         assert!(enode.contains(":30311"));
     }
+
+    #[test]
+    fn startup_timer_phases_add_up_to_total() {
+        let mut timer = StartupTimer::start();
+        std::thread::sleep(Duration::from_millis(5));
+        let first = timer.phase("first");
+        let second = timer.phase("second");
+
+        assert!(first >= Duration::from_millis(5));
+        assert!(timer.ready() >= first + second);
+    }
 }
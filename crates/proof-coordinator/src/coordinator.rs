@@ -1,5 +1,7 @@
 use crate::{
+    dead_letter::{DEAD_LETTER_FILE_NAME, DeadLetterQueue},
     error::{Error, Result},
+    metrics::BATCHES_DEAD_LETTERED_TOTAL,
     types::{ProofCoordinatorOptions, Request, Response},
 };
 use mojave_client::{
@@ -15,7 +17,7 @@ use ethrex_storage_rollup::StoreRollup;
 
 use guest_program::input::ProgramInput;
 
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 // TODO: replace client or use smthing else
 #[allow(dead_code)]
@@ -25,6 +27,7 @@ pub struct ProofCoordinator {
     store: Store,
     blockchain: Arc<Blockchain>,
     elasticity_multiplier: u64,
+    dead_letters: DeadLetterQueue,
 }
 
 #[allow(dead_code)]
@@ -42,6 +45,10 @@ impl ProofCoordinator {
             .prover_urls(&prover_url)
             .build()
             .map_err(Error::Client)?;
+        let dead_letters = DeadLetterQueue::open(
+            Path::new(&node.data_dir).join(DEAD_LETTER_FILE_NAME),
+            options.max_proof_attempts,
+        )?;
 
         Ok(Self {
             client,
@@ -49,15 +56,36 @@ impl ProofCoordinator {
             store: node.store,
             blockchain: node.blockchain,
             elasticity_multiplier: DEFAULT_ELASTICITY,
+            dead_letters,
         })
     }
 
-    async fn store_proof(&self, proof_response: ProofResponse, batch_number: u64) -> Result<()> {
+    /// Counts a failed proof for `batch_number`. Once the retry budget is
+    /// spent the batch is dead-lettered so it no longer holds up the pipeline;
+    /// until then the failure is returned to the caller.
+    fn record_proof_failure(&mut self, batch_number: u64, reason: String) -> Result<()> {
+        if !self.dead_letters.record_failure(batch_number, &reason)? {
+            return Err(Error::ProofFailed(batch_number, reason));
+        }
+
+        BATCHES_DEAD_LETTERED_TOTAL.inc();
+        tracing::error!(
+            batch_number,
+            %reason,
+            "Batch failed to prove too many times, moved to the dead-letter queue"
+        );
+        Ok(())
+    }
+
+    async fn store_proof(
+        &mut self,
+        proof_response: ProofResponse,
+        batch_number: u64,
+    ) -> Result<()> {
         let batch_proof = match proof_response.result {
             ProofResult::Proof(proof) => proof,
-            ProofResult::Error(err) => {
-                return Err(Error::ProofFailed(batch_number, err.to_string()));
-            }
+            ProofResult::Error(err) => return self.record_proof_failure(batch_number, err),
+            ProofResult::NotReady { .. } => return Err(Error::ProofNotReady(batch_number)),
         };
 
         let prover_type = batch_proof.prover_type();
@@ -77,6 +105,7 @@ impl ProofCoordinator {
                 .store_proof_by_batch_and_type(batch_number, prover_type, batch_proof)
                 .await?;
         }
+        self.dead_letters.clear_failures(batch_number);
 
         Ok(())
     }
@@ -159,6 +188,10 @@ impl mojave_task::Task for ProofCoordinator {
     async fn handle_request(&mut self, request: Self::Request) -> Result<Self::Response> {
         match request {
             Request::ProcessBatch(batch_number) => {
+                if self.dead_letters.contains(batch_number) {
+                    tracing::warn!(batch_number, "Skipping dead-lettered batch");
+                    return Ok(Response::Ack);
+                }
                 //let input = match self.create_prover_input(batch_number).await {
                 //    Ok(input) => input,
                 //    Err(e) => return Err(e),
@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    error::{Error, Result},
+    types::DeadLetteredBatch,
+};
+
+/// File in the datadir holding the dead-lettered batches.
+pub(crate) const DEAD_LETTER_FILE_NAME: &str = "dead_letter_batches.json";
+
+/// Lists the batches dead-lettered by the sequencer using `data_dir`.
+pub fn list_dead_lettered(data_dir: impl AsRef<Path>) -> Result<Vec<DeadLetteredBatch>> {
+    Ok(DeadLetterQueue::open(data_dir.as_ref().join(DEAD_LETTER_FILE_NAME), 1)?.list())
+}
+
+/// Batches that exhausted their proving attempts, persisted so they stay
+/// quarantined across restarts.
+pub(crate) struct DeadLetterQueue {
+    path: PathBuf,
+    batches: BTreeMap<u64, DeadLetteredBatch>,
+    /// Failed proofs tolerated for a batch before it is dead-lettered.
+    max_attempts: u32,
+    /// Failed proofs so far for batches not yet dead-lettered.
+    failed_attempts: HashMap<u64, u32>,
+}
+
+impl DeadLetterQueue {
+    /// Loads the queue from `path`, starting empty if the file doesn't exist.
+    pub(crate) fn open(path: impl AsRef<Path>, max_attempts: u32) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let batches = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<DeadLetteredBatch>>(&bytes)
+                .map_err(|error| Error::DeadLetter(format!("{}: {error}", path.display())))?
+                .into_iter()
+                .map(|batch| (batch.batch_number, batch))
+                .collect(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => {
+                return Err(Error::DeadLetter(format!("{}: {error}", path.display())));
+            }
+        };
+        Ok(Self {
+            path,
+            batches,
+            max_attempts: max_attempts.max(1),
+            failed_attempts: HashMap::new(),
+        })
+    }
+
+    pub(crate) fn contains(&self, batch_number: u64) -> bool {
+        self.batches.contains_key(&batch_number)
+    }
+
+    pub(crate) fn list(&self) -> Vec<DeadLetteredBatch> {
+        self.batches.values().cloned().collect()
+    }
+
+    /// Counts a failed proof for `batch_number` and dead-letters the batch
+    /// once `max_attempts` is reached. Returns whether it was dead-lettered.
+    pub(crate) fn record_failure(&mut self, batch_number: u64, reason: &str) -> Result<bool> {
+        let attempts = self.failed_attempts.entry(batch_number).or_default();
+        *attempts += 1;
+        let attempts = *attempts;
+        if attempts < self.max_attempts {
+            return Ok(false);
+        }
+
+        self.failed_attempts.remove(&batch_number);
+        self.insert(batch_number, attempts, reason.to_owned())?;
+        Ok(true)
+    }
+
+    /// Forgets the failed attempts of a batch that was eventually proven.
+    pub(crate) fn clear_failures(&mut self, batch_number: u64) {
+        self.failed_attempts.remove(&batch_number);
+    }
+
+    /// Quarantines `batch_number` and persists the queue.
+    fn insert(&mut self, batch_number: u64, attempts: u32, reason: String) -> Result<()> {
+        let dead_lettered_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.batches.insert(
+            batch_number,
+            DeadLetteredBatch {
+                batch_number,
+                attempts,
+                reason,
+                dead_lettered_at,
+            },
+        );
+        self.persist()
+    }
+
+    /// Writes to a temporary file first so a crash never leaves a torn file.
+    fn persist(&self) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(&self.list())
+            .map_err(|error| Error::DeadLetter(error.to_string()))?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, bytes)
+            .and_then(|()| std::fs::rename(&tmp_path, &self.path))
+            .map_err(|error| Error::DeadLetter(format!("{}: {error}", self.path.display())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "mojave_dead_letter_{name}_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn batch_is_dead_lettered_after_max_attempts() {
+        let dir = temp_dir("max_attempts");
+        let mut queue = DeadLetterQueue::open(dir.join(DEAD_LETTER_FILE_NAME), 3).unwrap();
+
+        assert!(!queue.record_failure(7, "first").unwrap());
+        assert!(!queue.record_failure(7, "second").unwrap());
+        assert!(!queue.contains(7));
+        assert!(queue.record_failure(7, "third").unwrap());
+
+        assert!(queue.contains(7));
+        let batches = list_dead_lettered(&dir).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].batch_number, 7);
+        assert_eq!(batches[0].attempts, 3);
+        assert_eq!(batches[0].reason, "third");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn proven_batch_starts_over() {
+        let dir = temp_dir("proven");
+        let mut queue = DeadLetterQueue::open(dir.join(DEAD_LETTER_FILE_NAME), 2).unwrap();
+
+        assert!(!queue.record_failure(7, "boom").unwrap());
+        queue.clear_failures(7);
+        assert!(!queue.record_failure(7, "boom").unwrap());
+        // Failures are counted per batch.
+        assert!(!queue.record_failure(8, "boom").unwrap());
+
+        assert!(queue.list().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dead_lettered_batches_survive_reopen() {
+        let dir = temp_dir("reopen");
+        let path = dir.join(DEAD_LETTER_FILE_NAME);
+        let mut queue = DeadLetterQueue::open(&path, 1).unwrap();
+        assert!(queue.record_failure(3, "boom").unwrap());
+
+        let queue = DeadLetterQueue::open(&path, 1).unwrap();
+        assert!(queue.contains(3));
+        assert!(list_dead_lettered(temp_dir("empty")).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -37,8 +37,12 @@ pub enum Error {
     BlobsBundleError(#[from] ethrex_common::types::BlobsBundleError),
     #[error("Failed to execute command: {0}")]
     Command(std::io::Error),
+    #[error("Dead letter queue error: {0}")]
+    DeadLetter(String),
     #[error("Missing blob for batch {0}")]
     MissingBlob(u64),
     #[error("Proof failed for batch {0}: {1}")]
     ProofFailed(u64, String),
+    #[error("Proof for batch {0} is not ready yet")]
+    ProofNotReady(u64),
 }
@@ -1,7 +1,10 @@
 mod coordinator;
+mod dead_letter;
 pub mod error;
+pub mod metrics;
 pub mod types;
 pub use coordinator::ProofCoordinator;
+pub use dead_letter::list_dead_lettered;
 
 pub mod prelude {
     pub use crate::{
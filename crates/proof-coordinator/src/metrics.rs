@@ -0,0 +1,13 @@
+use lazy_static::lazy_static;
+use mojave_utils::metrics::opts;
+use prometheus::{IntCounter, register_int_counter};
+
+lazy_static! {
+    /// Number of batches moved to the dead-letter queue after exhausting their
+    /// proving attempts.
+    pub static ref BATCHES_DEAD_LETTERED_TOTAL: IntCounter = register_int_counter!(opts(
+        "batches_dead_lettered_total",
+        "Number of batches quarantined after repeatedly failing to prove",
+    ))
+    .expect("batches_dead_lettered_total can be registered");
+}
@@ -1,8 +1,13 @@
 use mojave_client::types::ProofResponse;
+use serde::{Deserialize, Serialize};
+
+/// Failed proofs tolerated for a batch before it is dead-lettered.
+pub const DEFAULT_MAX_PROOF_ATTEMPTS: u32 = 3;
 
 #[derive(Debug, Clone)]
 pub struct ProofCoordinatorOptions {
     pub prover_address: String,
+    pub max_proof_attempts: u32,
 }
 pub enum Request {
     ProcessBatch(u64),
@@ -13,3 +18,14 @@ pub enum Request {
 pub enum Response {
     Ack,
 }
+
+/// A batch taken out of the proving pipeline after repeated failures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetteredBatch {
+    pub batch_number: u64,
+    pub attempts: u32,
+    /// Error reported by the last failed attempt.
+    pub reason: String,
+    /// Unix timestamp (seconds) of when the batch was quarantined.
+    pub dead_lettered_at: u64,
+}
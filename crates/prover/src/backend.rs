@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use ethrex_l2_common::prover::{BatchProof, ProofBytes, ProverType};
+use ethrex_prover_lib::{backend::Backend, prove, to_batch_proof};
+use guest_program::input::ProgramInput;
+use mojave_utils::{
+    hash::compute_keccak,
+    rpc::error::{Error, Result},
+};
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// Proving system used by the proof worker.
+#[async_trait]
+pub trait ProverBackend: Send + Sync + 'static {
+    async fn prove(&self, input: ProgramInput) -> Result<BatchProof>;
+}
+
+/// Backend selectable with `--prover.backend`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Executes the guest program without generating a real proof.
+    #[default]
+    Exec,
+    Risc0,
+    Sp1,
+    /// Executes the guest program and encodes the result for Aligned.
+    Aligned,
+    /// Returns a deterministic dummy proof without executing anything.
+    Mock,
+}
+
+impl BackendKind {
+    /// Builds the backend, failing when it was not compiled in.
+    pub fn build(self) -> Result<Arc<dyn ProverBackend>> {
+        let backend: Arc<dyn ProverBackend> = match self {
+            BackendKind::Mock => Arc::new(MockBackend),
+            BackendKind::Exec | BackendKind::Aligned => Arc::new(EthrexBackend { kind: self }),
+            #[cfg(feature = "risc0")]
+            BackendKind::Risc0 => Arc::new(EthrexBackend { kind: self }),
+            #[cfg(feature = "sp1")]
+            BackendKind::Sp1 => Arc::new(EthrexBackend { kind: self }),
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(Error::Internal(format!(
+                    "Prover backend {self} requires the `{self}` feature"
+                )));
+            }
+        };
+        Ok(backend)
+    }
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BackendKind::Exec => "exec",
+            BackendKind::Risc0 => "risc0",
+            BackendKind::Sp1 => "sp1",
+            BackendKind::Aligned => "aligned",
+            BackendKind::Mock => "mock",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "exec" => Ok(BackendKind::Exec),
+            "risc0" => Ok(BackendKind::Risc0),
+            "sp1" => Ok(BackendKind::Sp1),
+            "aligned" => Ok(BackendKind::Aligned),
+            "mock" => Ok(BackendKind::Mock),
+            other => Err(format!(
+                "unknown prover backend `{other}`, expected one of exec, risc0, sp1, aligned, mock"
+            )),
+        }
+    }
+}
+
+/// Proves with one of the ethrex prover backends.
+struct EthrexBackend {
+    kind: BackendKind,
+}
+
+impl EthrexBackend {
+    fn ethrex_backend(&self) -> Backend {
+        match self.kind {
+            #[cfg(feature = "risc0")]
+            BackendKind::Risc0 => Backend::RISC0,
+            #[cfg(feature = "sp1")]
+            BackendKind::Sp1 => Backend::SP1,
+            _ => Backend::Exec,
+        }
+    }
+}
+
+#[async_trait]
+impl ProverBackend for EthrexBackend {
+    async fn prove(&self, input: ProgramInput) -> Result<BatchProof> {
+        let backend = self.ethrex_backend();
+        let aligned_mode = self.kind == BackendKind::Aligned;
+        tokio::task::spawn_blocking(move || {
+            prove(backend, input, aligned_mode)
+                .and_then(|output| to_batch_proof(output, aligned_mode))
+                .map_err(|err| Error::Internal(format!("Error while generate proof: {err:}")))
+        })
+        .await
+        .unwrap_or_else(|err| Err(Error::Internal(format!("Proving task failed: {err}"))))
+    }
+}
+
+/// Returns a proof derived from the input's block hashes, so the same input
+/// always yields the same proof. Meant for end-to-end tests.
+pub struct MockBackend;
+
+#[async_trait]
+impl ProverBackend for MockBackend {
+    async fn prove(&self, input: ProgramInput) -> Result<BatchProof> {
+        let block_hashes: Vec<u8> = input
+            .blocks
+            .iter()
+            .flat_map(|block| block.hash().0)
+            .collect();
+        Ok(BatchProof::ProofBytes(ProofBytes {
+            prover_type: ProverType::Exec,
+            proof: compute_keccak(&block_hashes).to_vec(),
+            public_values: Vec::new(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_kind_round_trips_through_str() {
+        for kind in [
+            BackendKind::Exec,
+            BackendKind::Risc0,
+            BackendKind::Sp1,
+            BackendKind::Aligned,
+            BackendKind::Mock,
+        ] {
+            assert_eq!(kind.to_string().parse::<BackendKind>(), Ok(kind));
+        }
+        assert_eq!("SP1".parse::<BackendKind>(), Ok(BackendKind::Sp1));
+        assert!("groth16".parse::<BackendKind>().is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_backend_is_deterministic() {
+        let first = MockBackend.prove(ProgramInput::default()).await.unwrap();
+        let second = MockBackend.prove(ProgramInput::default()).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(first).unwrap(),
+            serde_json::to_value(second).unwrap()
+        );
+    }
+}
@@ -1,63 +1,373 @@
-use mojave_client::types::{JobId, ProofResponse};
+use lru::LruCache;
+use mojave_client::types::{JobId, ProofResponse, ProofResult};
 use reqwest::Url;
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
 use tokio::sync::Mutex;
 
+/// File under the jobs directory holding the submission nonces.
+const NONCES_FILE: &str = "nonces.json";
+
+/// Number of serialized proofs kept by [`JobStore`] by default.
+pub const DEFAULT_PROOF_CACHE_CAPACITY: NonZeroUsize = NonZeroUsize::new(64).unwrap();
+
 pub struct JobRecord {
     pub job_id: JobId,
     pub prover_data: mojave_client::types::ProverData,
     pub sequencer_url: Url,
 }
 
+/// Error recorded for jobs that were still queued or proving when the prover
+/// stopped. Resubmitting the proof input enqueues them again.
+pub const INTERRUPTED_JOB_ERROR: &str =
+    "Prover restarted before the job finished; resubmit the proof input";
+
 pub struct JobStore {
-    pending: Mutex<HashSet<JobId>>,
+    /// Jobs still queued or proving.
+    pending: Mutex<HashMap<JobId, PendingJob>>,
+    /// Sequence number given to the next pending job.
+    next_seq: AtomicU64,
+    /// Signers of the pending jobs that were submitted with a signature.
+    submitters: Mutex<HashMap<JobId, String>>,
     proofs: Mutex<HashMap<JobId, ProofResponse>>,
+    /// Jobs cancelled before they finished, until they are submitted again.
+    cancelled: Mutex<HashSet<JobId>>,
+    /// Serialized proof responses, least recently fetched evicted first.
+    /// Entries are dropped whenever the underlying proof changes.
+    serialized: Mutex<LruCache<JobId, Value>>,
+    /// Directory holding one file per job, when jobs are persisted.
+    dir: Option<PathBuf>,
+    /// Held while a change is written to `dir`, so files are updated in the
+    /// same order as the maps above.
+    disk: Mutex<()>,
+    /// Submission nonces reloaded from `dir`.
+    nonces: HashMap<String, u64>,
+}
+
+struct PendingJob {
+    batch_number: u64,
+    /// Orders pending jobs by when they were queued.
+    seq: u64,
+}
+
+/// On-disk form of a job. `proof` is unset while the job is pending.
+#[derive(Serialize, Deserialize)]
+struct PersistedJob {
+    job_id: JobId,
+    batch_number: u64,
+    proof: Option<ProofResponse>,
 }
 
 impl Default for JobStore {
     fn default() -> Self {
+        Self::with_cache_capacity(DEFAULT_PROOF_CACHE_CAPACITY)
+    }
+}
+
+impl JobStore {
+    pub fn with_cache_capacity(capacity: NonZeroUsize) -> Self {
         JobStore {
-            pending: Mutex::new(HashSet::new()),
+            pending: Mutex::new(HashMap::new()),
+            next_seq: AtomicU64::new(0),
+            submitters: Mutex::new(HashMap::new()),
             proofs: Mutex::new(HashMap::new()),
+            cancelled: Mutex::new(HashSet::new()),
+            serialized: Mutex::new(LruCache::new(capacity)),
+            dir: None,
+            disk: Mutex::new(()),
+            nonces: HashMap::new(),
         }
     }
-}
 
-impl JobStore {
+    /// Opens a store that persists jobs under `dir`, reloading the jobs
+    /// written by a previous run.
+    ///
+    /// Finished jobs keep their proofs. Jobs that were still pending are
+    /// recorded as failed with [`INTERRUPTED_JOB_ERROR`], since their inputs
+    /// are not persisted. This reads the whole directory, so call it before
+    /// serving or from a blocking task.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let nonces_path = dir.join(NONCES_FILE);
+        let nonces = match fs::read(&nonces_path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|error| {
+                tracing::warn!(path = %nonces_path.display(), %error, "Skipping unreadable submission nonces");
+                HashMap::new()
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error),
+        };
+
+        let mut proofs = HashMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") || path == nonces_path {
+                continue;
+            }
+            let job: PersistedJob = match serde_json::from_slice(&fs::read(&path)?) {
+                Ok(job) => job,
+                Err(error) => {
+                    tracing::warn!(path = %path.display(), %error, "Skipping unreadable persisted job");
+                    continue;
+                }
+            };
+            let job = match job.proof {
+                Some(_) => job,
+                None => {
+                    tracing::info!(job_id = %job.job_id, batch_number = job.batch_number, "Marking interrupted job for resubmission");
+                    let proof = ProofResponse {
+                        job_id: job.job_id.clone(),
+                        batch_number: job.batch_number,
+                        result: ProofResult::Error(INTERRUPTED_JOB_ERROR.to_owned()),
+                    };
+                    // Rewrite it so it is not reloaded as pending again.
+                    let job = PersistedJob {
+                        proof: Some(proof),
+                        ..job
+                    };
+                    write_job(&dir, &job)?;
+                    job
+                }
+            };
+            proofs.extend(job.proof.map(|proof| (job.job_id, proof)));
+        }
+        tracing::info!(dir = %dir.display(), jobs = proofs.len(), "Loaded persisted jobs");
+
+        Ok(JobStore {
+            proofs: Mutex::new(proofs),
+            dir: Some(dir),
+            nonces,
+            ..Self::default()
+        })
+    }
+
+    /// Runs `op` on the jobs directory from the blocking pool, if jobs are
+    /// persisted. Failures are logged, the in-memory store stays
+    /// authoritative. Callers hold `disk`.
+    async fn on_disk<F>(&self, what: &str, op: F)
+    where
+        F: FnOnce(&Path) -> io::Result<()> + Send + 'static,
+    {
+        let Some(dir) = self.dir.clone() else {
+            return;
+        };
+        let result = tokio::task::spawn_blocking(move || op(&dir))
+            .await
+            .map_err(io::Error::other)
+            .and_then(|result| result);
+        if let Err(error) = result {
+            tracing::warn!(%error, "Failed to persist {what}");
+        }
+    }
+
+    async fn persist(&self, job_id: &JobId, batch_number: u64, proof: Option<&ProofResponse>) {
+        let job = PersistedJob {
+            job_id: job_id.clone(),
+            batch_number,
+            proof: proof.cloned(),
+        };
+        self.on_disk(&format!("job {job_id}"), move |dir| write_job(dir, &job))
+            .await;
+    }
+
+    async fn unpersist(&self, job_id: &JobId) {
+        let path_id = job_id.clone();
+        self.on_disk(
+            &format!("removal of job {job_id}"),
+            move |dir| match fs::remove_file(job_path(dir, &path_id)) {
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            },
+        )
+        .await;
+    }
+
+    /// Submission nonces persisted by a previous run.
+    pub fn persisted_nonces(&self) -> HashMap<String, u64> {
+        self.nonces.clone()
+    }
+
+    /// Writes the submission nonces next to the jobs.
+    pub async fn persist_nonces(&self, nonces: &HashMap<String, u64>) {
+        let _disk = self.disk.lock().await;
+        let bytes = match serde_json::to_vec(nonces) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                tracing::warn!(%error, "Failed to serialize submission nonces");
+                return;
+            }
+        };
+        self.on_disk("submission nonces", move |dir| {
+            write_atomically(&dir.join(NONCES_FILE), &bytes)
+        })
+        .await;
+    }
+
     pub async fn already_requested(&self, job_id: &JobId) -> bool {
-        if self.pending.lock().await.contains(job_id) {
+        if self.pending.lock().await.contains_key(job_id) {
             true
         } else {
             self.proofs.lock().await.contains_key(job_id)
         }
     }
 
+    pub async fn is_pending(&self, job_id: &JobId) -> bool {
+        self.pending.lock().await.contains_key(job_id)
+    }
+
+    pub async fn pending_batch_number(&self, job_id: &JobId) -> Option<u64> {
+        self.pending
+            .lock()
+            .await
+            .get(job_id)
+            .map(|job| job.batch_number)
+    }
+
     pub async fn get_pending_jobs(&self) -> Vec<JobId> {
         let g = self.pending.lock().await;
-        g.iter().cloned().collect()
+        g.keys().cloned().collect()
     }
 
-    pub async fn insert_job(&self, job_id: JobId) {
-        self.pending.lock().await.insert(job_id.to_owned());
+    pub async fn insert_job(&self, job_id: JobId, batch_number: u64) {
+        let _disk = self.disk.lock().await;
+        self.cancelled.lock().await.remove(&job_id);
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        self.pending
+            .lock()
+            .await
+            .insert(job_id.clone(), PendingJob { batch_number, seq });
+        self.persist(&job_id, batch_number, None).await;
+    }
+
+    /// Number of pending jobs queued before `job_id`, not counting
+    /// `running`. `None` when the job is not pending.
+    pub async fn queue_position(&self, job_id: &JobId, running: Option<&JobId>) -> Option<usize> {
+        let pending = self.pending.lock().await;
+        let seq = pending.get(job_id)?.seq;
+        let position = pending
+            .iter()
+            .filter(|(id, job)| job.seq < seq && Some(*id) != running)
+            .count();
+        Some(position)
+    }
+
+    /// Records who signed the submission of a pending job.
+    pub async fn set_submitter(&self, job_id: &JobId, submitter: String) {
+        self.submitters
+            .lock()
+            .await
+            .insert(job_id.clone(), submitter);
+    }
+
+    pub async fn submitter(&self, job_id: &JobId) -> Option<String> {
+        self.submitters.lock().await.get(job_id).cloned()
+    }
+
+    pub async fn remove_pending(&self, job_id: &JobId) -> bool {
+        let _disk = self.disk.lock().await;
+        self.submitters.lock().await.remove(job_id);
+        let removed = self.pending.lock().await.remove(job_id).is_some();
+        if removed {
+            self.unpersist(job_id).await;
+        }
+        removed
+    }
+
+    /// Drops the pending job and remembers it was cancelled.
+    pub async fn mark_cancelled(&self, job_id: &JobId) {
+        self.remove_pending(job_id).await;
+        self.cancelled.lock().await.insert(job_id.clone());
+    }
+
+    pub async fn is_cancelled(&self, job_id: &JobId) -> bool {
+        self.cancelled.lock().await.contains(job_id)
     }
 
     pub async fn get_proof_by_id(&self, job_id: &JobId) -> Option<ProofResponse> {
         self.proofs.lock().await.get(job_id).cloned()
     }
 
+    /// Returns the proof response as JSON, serializing it only on the first
+    /// fetch after it was stored.
+    pub async fn get_serialized_proof(
+        &self,
+        job_id: &JobId,
+    ) -> Result<Option<Value>, serde_json::Error> {
+        if let Some(value) = self.serialized.lock().await.get(job_id) {
+            return Ok(Some(value.clone()));
+        }
+        let Some(proof_response) = self.get_proof_by_id(job_id).await else {
+            return Ok(None);
+        };
+        let value = serde_json::to_value(&proof_response)?;
+        self.serialized
+            .lock()
+            .await
+            .put(job_id.to_owned(), value.clone());
+        Ok(Some(value))
+    }
+
+    pub async fn remove_proof(&self, job_id: &JobId) -> Option<ProofResponse> {
+        let _disk = self.disk.lock().await;
+        self.serialized.lock().await.pop(job_id);
+        let removed = self.proofs.lock().await.remove(job_id);
+        if removed.is_some() {
+            self.unpersist(job_id).await;
+        }
+        removed
+    }
+
     pub async fn upsert_proof(&self, job_id: &JobId, proof_response: ProofResponse) {
+        let _disk = self.disk.lock().await;
+        self.submitters.lock().await.remove(job_id);
         self.pending.lock().await.remove(job_id);
+        self.serialized.lock().await.pop(job_id);
+        let batch_number = proof_response.batch_number;
         self.proofs
             .lock()
             .await
-            .insert(job_id.to_owned(), proof_response);
+            .insert(job_id.to_owned(), proof_response.clone());
+        self.persist(job_id, batch_number, Some(&proof_response))
+            .await;
     }
 }
 
+fn job_path(dir: &Path, job_id: &JobId) -> PathBuf {
+    dir.join(format!("{job_id}.json"))
+}
+
+fn write_job(dir: &Path, job: &PersistedJob) -> io::Result<()> {
+    write_atomically(&job_path(dir, &job.job_id), &serde_json::to_vec(job)?)
+}
+
+/// Writes and syncs the file next to `path` before renaming it into place,
+/// so a crash never leaves a truncated or unsynced file behind.
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    // Persist the rename itself.
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mojave_client::types::{ProofResponse, ProofResult};
 
     fn make_proof(job_id: JobId) -> ProofResponse {
         ProofResponse {
@@ -74,7 +384,7 @@ mod tests {
         let job1 = JobId::from("aa");
         let job2 = JobId::from("bb");
 
-        store.insert_job(job1.clone()).await;
+        store.insert_job(job1.clone(), 1).await;
         assert!(store.already_requested(&job1).await);
 
         // if it’s in proofs set, also returns true
@@ -90,23 +400,46 @@ mod tests {
         let job2 = JobId::from("baa2b1b");
         let job3 = JobId::from("cac3c3c");
 
-        store.insert_job(job1.clone()).await;
-        store.insert_job(job2.clone()).await;
-        store.insert_job(job3.clone()).await;
+        store.insert_job(job1.clone(), 1).await;
+        store.insert_job(job2.clone(), 1).await;
+        store.insert_job(job3.clone(), 1).await;
         // duplicate insert. should be no effect
-        store.insert_job(job2.clone()).await;
+        store.insert_job(job2.clone(), 1).await;
 
         let mut got = store.get_pending_jobs().await;
         got.sort_unstable();
         assert_eq!(got, vec![job1, job2, job3]);
     }
 
+    #[tokio::test]
+    async fn queue_position_skips_running_and_finished_jobs() {
+        let store = JobStore::default();
+        for id in ["a", "b", "c"] {
+            store.insert_job(id.into(), 1).await;
+        }
+
+        assert_eq!(store.queue_position(&"c".into(), None).await, Some(2));
+        assert_eq!(
+            store.queue_position(&"c".into(), Some(&"a".into())).await,
+            Some(1)
+        );
+
+        store
+            .upsert_proof(&"b".into(), make_proof("b".into()))
+            .await;
+        assert_eq!(
+            store.queue_position(&"c".into(), Some(&"a".into())).await,
+            Some(0)
+        );
+        assert_eq!(store.queue_position(&"b".into(), None).await, None);
+    }
+
     #[tokio::test]
     async fn upsert_proof_moves_from_pending_to_proofs() {
         let store = JobStore::default();
 
         let job = JobId::from("job-1");
-        store.insert_job(job.clone()).await;
+        store.insert_job(job.clone(), 1).await;
         store.upsert_proof(&job, make_proof(job.clone())).await;
 
         // removed from pending
@@ -119,9 +452,95 @@ mod tests {
         assert_eq!(proof_response.job_id, job);
     }
 
+    #[tokio::test]
+    async fn remove_proof_drops_stored_proof() {
+        let store = JobStore::default();
+
+        let job = JobId::from("job-1");
+        store.upsert_proof(&job, make_proof(job.clone())).await;
+        assert!(store.remove_proof(&job).await.is_some());
+        assert!(!store.already_requested(&job).await);
+        assert!(store.remove_proof(&job).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn serialized_proof_is_cached_until_replaced() {
+        let store = JobStore::with_cache_capacity(NonZeroUsize::new(1).unwrap());
+        let job = JobId::from("job-1");
+        store.upsert_proof(&job, make_proof(job.clone())).await;
+
+        let first = store.get_serialized_proof(&job).await.unwrap().unwrap();
+        assert!(store.serialized.lock().await.contains(&job));
+        assert_eq!(
+            store.get_serialized_proof(&job).await.unwrap(),
+            Some(first.clone())
+        );
+
+        // Replacing the proof evicts the stale serialization.
+        let mut replaced = make_proof(job.clone());
+        replaced.batch_number = 2;
+        store.upsert_proof(&job, replaced).await;
+        assert!(!store.serialized.lock().await.contains(&job));
+        let second = store.get_serialized_proof(&job).await.unwrap().unwrap();
+        assert_ne!(first, second);
+
+        assert!(
+            store
+                .get_serialized_proof(&"missing".into())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[tokio::test]
     async fn get_proof_by_id_none_when_absent() {
         let store = JobStore::default();
         assert!(store.get_proof_by_id(&"missing".into()).await.is_none());
     }
+
+    #[tokio::test]
+    async fn persisted_jobs_survive_reopen() {
+        let dir = std::env::temp_dir().join(format!(
+            "mojave_prover_jobs_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+
+        let done = JobId::from("done");
+        let interrupted = JobId::from("interrupted");
+        let dropped = JobId::from("dropped");
+        {
+            let store = JobStore::open(&dir).unwrap();
+            store.insert_job(done.clone(), 1).await;
+            let mut proof = make_proof(done.clone());
+            proof.result = ProofResult::Error("proof bytes".into());
+            store.upsert_proof(&done, proof).await;
+            store.insert_job(interrupted.clone(), 2).await;
+            store.insert_job(dropped.clone(), 3).await;
+            assert!(store.remove_pending(&dropped).await);
+            store
+                .persist_nonces(&HashMap::from([("alice".to_owned(), 7)]))
+                .await;
+        }
+
+        let store = JobStore::open(&dir).unwrap();
+        let proof = store.get_proof_by_id(&done).await.expect("proof reloaded");
+        assert_eq!(proof.batch_number, 1);
+        assert!(matches!(proof.result, ProofResult::Error(ref e) if e == "proof bytes"));
+
+        assert!(store.get_pending_jobs().await.is_empty());
+        let proof = store.get_proof_by_id(&interrupted).await.unwrap();
+        assert_eq!(proof.batch_number, 2);
+        assert!(matches!(proof.result, ProofResult::Error(ref e) if e == INTERRUPTED_JOB_ERROR));
+        assert!(!store.already_requested(&dropped).await);
+        assert_eq!(
+            store.persisted_nonces(),
+            HashMap::from([("alice".to_owned(), 7)])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,3 +1,4 @@
+pub mod backend;
 pub mod job;
 mod rpc;
 pub mod services;
@@ -1,69 +1,139 @@
 use crate::{
-    job::{JobRecord, JobStore},
-    rpc::{ProverRpcContext, tasks::spawn_proof_worker},
+    backend::BackendKind,
+    job::JobStore,
+    rpc::{ProverRpcContext, handlers},
 };
+use axum::{http::header, routing::get};
 use mojave_rpc_server::{RpcRegistry, RpcService};
+use mojave_signature::VerifyingKey;
 use mojave_utils::rpc::error::{Error, Result};
 
-use std::{collections::HashSet, sync::Arc};
-use tokio::{
-    net::TcpListener,
-    sync::{Mutex, mpsc},
-};
+use std::{collections::HashSet, path::PathBuf, str::FromStr, sync::Arc};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+/// Serves the prover RPC API until the server fails or, when given,
+/// `shutdown_token` is cancelled. Jobs are persisted under `jobs_dir` when
+/// set, so finished proofs survive a restart. When `trusted_keys` is not
+/// empty, only proof inputs signed by one of those verifying keys are
+/// accepted.
 pub async fn start_api(
-    aligned_mode: bool,
+    backend: BackendKind,
     http_addr: &str,
     _private_key: &str,
     queue_capacity: usize,
+    jobs_dir: Option<PathBuf>,
+    trusted_keys: &[String],
+    shutdown_token: Option<CancellationToken>,
 ) -> Result<()> {
-    let (job_sender, job_receiver) = mpsc::channel::<JobRecord>(queue_capacity);
+    let trusted_signers = trusted_keys
+        .iter()
+        .map(|key| {
+            VerifyingKey::from_str(key)
+                .map(|key| key.to_address())
+                .map_err(|error| Error::Internal(format!("Invalid trusted key {key}: {error}")))
+        })
+        .collect::<Result<HashSet<_>>>()?;
+
     // use dummy publisher for now
     let publisher = Arc::new(
         mojave_msgio::dummy::Dummy::new()
             .await
             .map_err(|e| Error::Internal(e.to_string()))?,
     );
-    let context = Arc::new(ProverRpcContext {
-        aligned_mode,
-        job_store: JobStore::default(),
-        sender: job_sender,
+    let job_store = match jobs_dir {
+        Some(dir) => {
+            let path = dir.clone();
+            tokio::task::spawn_blocking(move || JobStore::open(path))
+                .await
+                .map_err(|error| Error::Internal(error.to_string()))?
+                .map_err(|error| {
+                    Error::Internal(format!(
+                        "Failed to open job store at {}: {error}",
+                        dir.display()
+                    ))
+                })?
+        }
+        None => JobStore::default(),
+    };
+    // Spawns the proof worker task alongside the context.
+    let context = ProverRpcContext::new(
+        backend.build()?,
         publisher,
-        sent_ids: Mutex::new(HashSet::new()),
-    });
-    tracing::info!(aligned_mode = %aligned_mode, "Prover RPC context initialized");
+        queue_capacity,
+        job_store,
+        trusted_signers,
+    );
+    tracing::info!(%backend, trusted_keys = trusted_keys.len(), "Prover RPC context initialized");
 
     let mut registry: RpcRegistry<Arc<ProverRpcContext>> = RpcRegistry::new();
-    crate::rpc::handlers::register_moj_sendProofInput(&mut registry);
-    crate::rpc::handlers::register_moj_getPendingJobIds(&mut registry);
-    crate::rpc::handlers::register_moj_getProof(&mut registry);
-    let service = RpcService::new(context.clone(), registry).with_permissive_cors();
-    let http_router = service.router();
+    mojave_rpc_macros::register_all!(
+        registry,
+        [
+            handlers::send_proof_input,
+            handlers::get_pending_job_ids,
+            handlers::get_proof,
+            handlers::resubmit_proof_input,
+            handlers::cancel_job,
+            handlers::get_job_status,
+        ]
+    );
+    let service = RpcService::new(context, registry).with_permissive_cors();
+    let http_router = service.router().route(
+        "/metrics",
+        get(|| async {
+            (
+                [(header::CONTENT_TYPE, mojave_utils::metrics::CONTENT_TYPE)],
+                mojave_utils::metrics::encode(),
+            )
+        }),
+    );
     let http_listener = TcpListener::bind(http_addr)
         .await
         .map_err(|error| Error::Internal(error.to_string()))?;
     tracing::info!(addr = %http_addr, "HTTP server bound");
-    let http_server = axum::serve(http_listener, http_router).into_future();
+    let http_server = axum::serve(http_listener, http_router)
+        .with_graceful_shutdown(async move {
+            match shutdown_token {
+                Some(token) => token.cancelled_owned().await,
+                None => std::future::pending().await,
+            }
+        })
+        .into_future();
     info!("Starting HTTP server at {http_addr}");
 
-    // Start the proof worker in the background.
-    let proof_worker_handle = spawn_proof_worker(context, job_receiver);
-    tracing::info!("Proof worker task spawned");
-
-    let _ = tokio::try_join!(
-        async {
-            http_server
-                .await
-                .map_err(|e| Error::Internal(e.to_string()))
-        },
-        async {
-            proof_worker_handle
-                .await
-                .map_err(|e| Error::Internal(e.to_string()))
-        }
-    )
-    .inspect_err(|e| tracing::error!("Error shutting down server:{e:?}"));
+    let _ = http_server
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))
+        .inspect_err(|e| tracing::error!("Error shutting down server:{e:?}"));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn start_api_stops_on_shutdown_token() {
+        let token = CancellationToken::new();
+        let server = tokio::spawn(start_api(
+            BackendKind::Mock,
+            "127.0.0.1:0",
+            "",
+            4,
+            None,
+            &[],
+            Some(token.clone()),
+        ));
+
+        token.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("server stops after cancellation")
+            .unwrap();
+        assert!(result.is_ok());
+    }
+}
@@ -1,14 +1,97 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
+use mojave_client::types::JobId;
 use mojave_msgio::types::Publisher;
-use tokio::sync::{Mutex, mpsc};
+use mojave_task::{Task, TaskHandle};
+use tokio::sync::{Mutex, Notify};
+use tokio_util::sync::CancellationToken;
 
-use crate::{job::JobStore, rpc::types::JobRecord};
+use crate::{
+    backend::ProverBackend,
+    job::JobStore,
+    rpc::tasks::{ProofWorker, spawn_proof_driver},
+};
 
 pub struct ProverRpcContext {
-    pub aligned_mode: bool,
+    pub backend: Arc<dyn ProverBackend>,
     pub job_store: JobStore,
-    pub sender: mpsc::Sender<JobRecord>,
+    pub worker: TaskHandle<ProofWorker>,
     pub publisher: Arc<dyn Publisher>,
     pub sent_ids: Mutex<HashSet<String>>,
+    /// Addresses of the keys allowed to sign proof inputs. When empty, any
+    /// valid signature is accepted, as are unsigned inputs.
+    pub trusted_signers: HashSet<String>,
+    /// Highest proof-input nonce accepted from each submitter.
+    pub submission_nonces: Mutex<HashMap<String, u64>>,
+    /// Job currently being proven. Kept outside the worker so it can be
+    /// cancelled while the worker is busy proving it.
+    pub(crate) running_job: Mutex<Option<RunningJob>>,
+    /// Wakes the proof driver when a job is queued or the running one ends.
+    pub(crate) job_ready: Arc<Notify>,
+}
+
+pub(crate) struct RunningJob {
+    pub(crate) job_id: JobId,
+    /// Unix timestamp in seconds at which proving started.
+    pub(crate) started_at: u64,
+    pub(crate) cancel: CancellationToken,
+}
+
+impl ProverRpcContext {
+    /// Creates the context and spawns its proof worker, along with the
+    /// driver that starts each queued job as soon as the worker is free.
+    pub fn new(
+        backend: Arc<dyn ProverBackend>,
+        publisher: Arc<dyn Publisher>,
+        queue_capacity: usize,
+        job_store: JobStore,
+        trusted_signers: HashSet<String>,
+    ) -> Arc<Self> {
+        let ctx = Self::with_worker(
+            backend,
+            publisher,
+            queue_capacity,
+            job_store,
+            trusted_signers,
+            Task::spawn,
+        );
+        spawn_proof_driver(Arc::downgrade(&ctx), ctx.job_ready.clone());
+        ctx
+    }
+
+    /// Creates the context, letting the caller decide how the worker is spawned.
+    pub(crate) fn with_worker<F>(
+        backend: Arc<dyn ProverBackend>,
+        publisher: Arc<dyn Publisher>,
+        queue_capacity: usize,
+        job_store: JobStore,
+        trusted_signers: HashSet<String>,
+        spawn: F,
+    ) -> Arc<Self>
+    where
+        F: FnOnce(ProofWorker) -> TaskHandle<ProofWorker>,
+    {
+        let submission_nonces = Mutex::new(job_store.persisted_nonces());
+        Arc::new_cyclic(|ctx| Self {
+            backend,
+            job_store,
+            worker: spawn(ProofWorker::new(ctx.clone(), queue_capacity)),
+            publisher,
+            sent_ids: Mutex::new(HashSet::new()),
+            trusted_signers,
+            submission_nonces,
+            running_job: Mutex::new(None),
+            job_ready: Arc::new(Notify::new()),
+        })
+    }
+}
+
+impl Drop for ProverRpcContext {
+    fn drop(&mut self) {
+        // Lets the proof driver see the context is gone and stop.
+        self.job_ready.notify_one();
+    }
 }
@@ -1,10 +1,15 @@
 use mojave_client::types::JobId;
 
 use crate::{
-    rpc::{ProverRpcContext, types::SendProofInputParam},
+    rpc::{
+        ProverRpcContext,
+        types::{CancelJobRequest, SendProofInputParam},
+    },
     services::jobs::{
-        enqueue_proof_input, get_pending_job_ids as jobs_get_pending_job_ids,
-        get_proof as get_proof_by_id,
+        cancel_job as jobs_cancel_job, check_submission_nonce, check_trusted_signer,
+        enqueue_proof_input, get_job_state, get_pending_job_ids as jobs_get_pending_job_ids,
+        get_serialized_proof, resubmit_proof_input as jobs_resubmit_proof_input,
+        verify_cancel_signature, verify_proof_input_signature,
     },
 };
 use std::sync::Arc;
@@ -25,39 +30,88 @@ pub async fn send_proof_input(
     ctx: Arc<ProverRpcContext>,
     params: SendProofInputParam,
 ) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
-    use SendProofInputParam::*;
-    let (prover_data, sequencer_addr) = match params {
-        Object(obj) => (obj.prover_data, obj.sequencer_addr),
-        Tuple((pd, url)) => (pd, url),
-    };
-    let job_id = enqueue_proof_input(&ctx, prover_data, sequencer_addr).await?;
+    let (prover_data, sequencer_addr, nonce, signature) = params.into_parts();
+    let signer =
+        verify_proof_input_signature(&prover_data, &sequencer_addr, nonce, signature.as_ref())?;
+    check_trusted_signer(&ctx, signer.as_deref())?;
+    let reservation = check_submission_nonce(&ctx, signer.as_deref(), nonce).await?;
+    let job_id = enqueue_proof_input(&ctx, prover_data, sequencer_addr, signer).await?;
+    if let Some(reservation) = reservation {
+        reservation.commit().await;
+    }
     Ok(serde_json::json!(job_id))
 }
 
+#[mojave_rpc_macros::rpc(namespace = "moj", method = "resubmitProofInput")]
+pub async fn resubmit_proof_input(
+    ctx: Arc<ProverRpcContext>,
+    params: SendProofInputParam,
+) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
+    let (prover_data, sequencer_addr, nonce, signature) = params.into_parts();
+    let signer =
+        verify_proof_input_signature(&prover_data, &sequencer_addr, nonce, signature.as_ref())?;
+    check_trusted_signer(&ctx, signer.as_deref())?;
+    let reservation = check_submission_nonce(&ctx, signer.as_deref(), nonce).await?;
+    let job_id = jobs_resubmit_proof_input(&ctx, prover_data, sequencer_addr, signer).await?;
+    if let Some(reservation) = reservation {
+        reservation.commit().await;
+    }
+    Ok(serde_json::json!(job_id))
+}
+
+#[mojave_rpc_macros::rpc(namespace = "moj", method = "cancelJob")]
+pub async fn cancel_job(
+    ctx: Arc<ProverRpcContext>,
+    params: CancelJobRequest,
+) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
+    let signer = verify_cancel_signature(&params.job_id, params.nonce, &params.signature)?;
+    let reservation = check_submission_nonce(&ctx, Some(&signer), Some(params.nonce)).await?;
+    let state = jobs_cancel_job(&ctx, &params.job_id, &signer).await?;
+    if let Some(reservation) = reservation {
+        reservation.commit().await;
+    }
+    serde_json::to_value(state).map_err(|e| mojave_rpc_core::RpcErr::Internal(e.to_string()))
+}
+
+#[mojave_rpc_macros::rpc(namespace = "moj", method = "getJobStatus")]
+pub async fn get_job_status(
+    ctx: Arc<ProverRpcContext>,
+    job_id: JobId,
+) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
+    let state = get_job_state(&ctx, &job_id).await?;
+    serde_json::to_value(state).map_err(|e| mojave_rpc_core::RpcErr::Internal(e.to_string()))
+}
+
 #[mojave_rpc_macros::rpc(namespace = "moj", method = "getProof")]
 pub async fn get_proof(
     ctx: Arc<ProverRpcContext>,
     job_id: JobId,
 ) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
-    let proof = get_proof_by_id(&ctx, &job_id).await?;
-    let proof = serde_json::to_value(proof)
-        .map_err(|e| mojave_rpc_core::RpcErr::Internal(e.to_string()))?;
-    Ok(proof)
+    get_serialized_proof(&ctx, &job_id).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        job::{JobRecord, JobStore},
-        rpc::{ProverRpcContext, types::SendProofInputRequest},
+        backend::{MockBackend, ProverBackend},
+        job::JobStore,
+        rpc::{
+            ProverRpcContext,
+            tasks::{JobState, ProverRequest, wait_for_proof},
+            types::{CancelJobRequest, SendProofInputRequest},
+        },
     };
     use guest_program::input::ProgramInput;
-    use mojave_client::types::{ProofResponse, ProofResult, ProverData};
+    use mojave_client::types::{
+        CancelJobMessage, ProofInputMessage, ProofResponse, ProofResult, ProverData,
+        RequestSignature,
+    };
     use mojave_msgio::{dummy::Dummy as MsgioPublisher, types::Publisher};
+    use mojave_signature::{SigningKey, types::Signer};
+    use mojave_task::Task;
     use reqwest::Url;
-    use std::{collections::HashSet, sync::Arc};
-    use tokio::sync::{Mutex, mpsc};
+    use std::{str::FromStr, sync::Arc};
 
     fn dummy_prover_data() -> ProverData {
         ProverData {
@@ -66,62 +120,171 @@ mod tests {
         }
     }
 
-    async fn make_ctx(capacity: usize) -> (Arc<ProverRpcContext>, mpsc::Receiver<JobRecord>) {
-        let (tx, rx) = mpsc::channel::<JobRecord>(capacity);
+    async fn make_ctx(capacity: usize) -> Arc<ProverRpcContext> {
+        make_ctx_trusting(capacity, &[]).await
+    }
+
+    async fn make_ctx_trusting(capacity: usize, signers: &[&str]) -> Arc<ProverRpcContext> {
         let publisher: Arc<dyn Publisher> = Arc::new(MsgioPublisher::new().await.unwrap());
-        let ctx = Arc::new(ProverRpcContext {
-            aligned_mode: false,
-            job_store: JobStore::default(),
-            sender: tx,
+        ProverRpcContext::with_worker(
+            Arc::new(MockBackend),
             publisher,
-            sent_ids: Mutex::new(HashSet::new()),
-        });
-        (ctx, rx)
+            capacity,
+            JobStore::default(),
+            signers.iter().map(|signer| signer.to_string()).collect(),
+            Task::spawn,
+        )
+    }
+
+    async fn job_state(ctx: &ProverRpcContext, job_id: serde_json::Value) -> JobState {
+        let job_id: JobId = serde_json::from_value(job_id).unwrap();
+        ctx.worker
+            .request(ProverRequest::QueryStatus(job_id))
+            .await
+            .unwrap()
     }
 
     #[tokio::test]
     async fn send_proof_input_accepts_tuple_and_emits_record() {
-        let (ctx, mut rx) = make_ctx(8).await;
+        let ctx = make_ctx(8).await;
         let url = Url::parse("http://localhost:1234").unwrap();
 
-        super::send_proof_input(
+        let job_id = super::send_proof_input(
             ctx.clone(),
-            SendProofInputParam::Tuple((dummy_prover_data(), url.clone())),
+            SendProofInputParam::Tuple((dummy_prover_data(), url)),
         )
         .await
         .unwrap();
 
-        let rec = rx.recv().await.expect("record sent");
-
-        assert_eq!(rec.sequencer_url, url);
-        assert!(!rec.job_id.is_empty());
+        assert!(!job_id.as_str().unwrap().is_empty());
+        assert_eq!(
+            job_state(&ctx, job_id).await,
+            JobState::Queued { position: 0 }
+        );
     }
 
     #[tokio::test]
     async fn send_proof_input_accepts_object_and_emits_record() {
-        let (ctx, mut rx) = make_ctx(8).await;
+        let ctx = make_ctx(8).await;
         let url = Url::parse("http://localhost:4321").unwrap();
 
-        super::send_proof_input(
+        let job_id = super::send_proof_input(
             ctx.clone(),
             SendProofInputParam::Object(SendProofInputRequest {
                 prover_data: dummy_prover_data(),
-                sequencer_addr: url.clone(),
+                sequencer_addr: url,
+                nonce: None,
+                signature: None,
             }),
         )
         .await
         .unwrap();
 
-        let rec = rx.recv().await.expect("record sent");
+        assert!(!job_id.as_str().unwrap().is_empty());
+        assert_eq!(
+            job_state(&ctx, job_id).await,
+            JobState::Queued { position: 0 }
+        );
+    }
+
+    /// Address of [`signing_key`].
+    const SIGNER: &str = "f39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+
+    fn now_millis() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    /// Anvil account 0.
+    fn signing_key() -> SigningKey {
+        SigningKey::from_str("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+            .unwrap()
+    }
+
+    fn signed_cancel(job_id: &JobId, key: &SigningKey, nonce: u64) -> CancelJobRequest {
+        CancelJobRequest {
+            job_id: job_id.clone(),
+            nonce,
+            signature: RequestSignature {
+                signature: key.sign(&CancelJobMessage { job_id, nonce }).unwrap(),
+                verifying_key: key.verifying_key(),
+            },
+        }
+    }
+
+    fn signed_submission(url: &Url, nonce: u64) -> SendProofInputParam {
+        let key = signing_key();
+        let prover_data = dummy_prover_data();
+        let signature = key
+            .sign(&ProofInputMessage {
+                prover_data: &prover_data,
+                sequencer_addr: url.as_str(),
+                nonce: Some(nonce),
+            })
+            .unwrap();
+        SendProofInputParam::Object(SendProofInputRequest {
+            prover_data,
+            sequencer_addr: url.clone(),
+            nonce: Some(nonce),
+            signature: Some(RequestSignature {
+                signature,
+                verifying_key: key.verifying_key(),
+            }),
+        })
+    }
+
+    #[tokio::test]
+    async fn send_proof_input_rejects_replayed_nonce() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:4321").unwrap();
+        let nonce = now_millis();
+
+        super::send_proof_input(ctx.clone(), signed_submission(&url, nonce))
+            .await
+            .unwrap();
+        let err = super::send_proof_input(ctx.clone(), signed_submission(&url, nonce))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Nonce already used")
+        );
+    }
+
+    #[tokio::test]
+    async fn send_proof_input_only_accepts_trusted_signers() {
+        let url = Url::parse("http://localhost:4321").unwrap();
+
+        let ctx = make_ctx_trusting(8, &[SIGNER]).await;
+        let err = super::send_proof_input(
+            ctx.clone(),
+            SendProofInputParam::Tuple((dummy_prover_data(), url.clone())),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Proof input must be signed")
+        );
+        super::send_proof_input(ctx, signed_submission(&url, now_millis()))
+            .await
+            .unwrap();
 
-        assert_eq!(rec.sequencer_url, url);
-        assert!(!rec.job_id.is_empty());
+        let ctx = make_ctx_trusting(8, &["70997970c51812dc3a010c7d01b50e0d17dc79c8"]).await;
+        let err = super::send_proof_input(ctx.clone(), signed_submission(&url, now_millis()))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Untrusted proof input signer")
+        );
+        assert!(ctx.job_store.get_pending_jobs().await.is_empty());
     }
 
     #[tokio::test]
     async fn send_proof_input_idempotency_scoped_by_context() {
-        let (ctx_a, _rx_a) = make_ctx(8).await;
-        let (ctx_b, _rx_b) = make_ctx(8).await;
+        let ctx_a = make_ctx(8).await;
+        let ctx_b = make_ctx(8).await;
         let url = Url::parse("http://localhost:1234").unwrap();
 
         super::send_proof_input(
@@ -149,12 +312,38 @@ mod tests {
         assert!(different_ctx_req_res.is_ok());
     }
 
+    #[tokio::test]
+    async fn resubmit_proof_input_returns_existing_job_id() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+
+        let first = super::send_proof_input(
+            ctx.clone(),
+            SendProofInputParam::Tuple((dummy_prover_data(), url.clone())),
+        )
+        .await
+        .unwrap();
+        let resubmitted = super::resubmit_proof_input(
+            ctx.clone(),
+            SendProofInputParam::Tuple((dummy_prover_data(), url)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(first, resubmitted);
+        assert_eq!(ctx.job_store.get_pending_jobs().await.len(), 1);
+        assert_eq!(
+            job_state(&ctx, first).await,
+            JobState::Queued { position: 0 }
+        );
+    }
+
     #[tokio::test]
     async fn get_pending_job_ids_returns_json_array_of_ids() {
-        let (ctx, _rx) = make_ctx(1).await;
-        ctx.job_store.insert_job("abbaa12".into()).await;
-        ctx.job_store.insert_job("baa2b1b".into()).await;
-        ctx.job_store.insert_job("cac3c3c".into()).await;
+        let ctx = make_ctx(1).await;
+        ctx.job_store.insert_job("abbaa12".into(), 0).await;
+        ctx.job_store.insert_job("baa2b1b".into(), 0).await;
+        ctx.job_store.insert_job("cac3c3c".into(), 0).await;
 
         let val1 = super::get_pending_job_ids(ctx.clone(), ()).await.unwrap();
         let arr1 = val1.as_array().unwrap();
@@ -174,7 +363,7 @@ mod tests {
 
     #[tokio::test]
     async fn get_proof_serializes_proof_to_json() {
-        let (ctx, _rx) = make_ctx(1).await;
+        let ctx = make_ctx(1).await;
         let job_id = JobId::from("job-1");
         let expected = ProofResponse {
             job_id: job_id.clone(),
@@ -186,4 +375,155 @@ mod tests {
         let val = super::get_proof(ctx, job_id).await.unwrap();
         assert_eq!(val, serde_json::to_value(&expected).unwrap());
     }
+
+    #[tokio::test]
+    async fn mock_backend_proves_submitted_input() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let job_id = super::send_proof_input(
+            ctx.clone(),
+            SendProofInputParam::Tuple((dummy_prover_data(), url)),
+        )
+        .await
+        .unwrap();
+        let job_id: JobId = serde_json::from_value(job_id).unwrap();
+
+        let not_ready = super::get_proof(ctx.clone(), job_id.clone()).await.unwrap();
+        assert!(matches!(
+            serde_json::from_value::<ProofResponse>(not_ready)
+                .unwrap()
+                .result,
+            ProofResult::NotReady { .. }
+        ));
+
+        let state = ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        assert!(matches!(state, JobState::Proving { .. }));
+        wait_for_proof(&ctx, &job_id).await;
+
+        let proof = super::get_proof(ctx, job_id.clone()).await.unwrap();
+        let proof: ProofResponse = serde_json::from_value(proof).unwrap();
+        assert_eq!(proof.job_id, job_id);
+        let ProofResult::Proof(proof) = proof.result else {
+            panic!("expected a proof");
+        };
+        let expected = MockBackend.prove(dummy_prover_data().input).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(proof).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_job_requires_the_submitters_signature() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let nonce = now_millis();
+        let job_id = super::send_proof_input(ctx.clone(), signed_submission(&url, nonce))
+            .await
+            .unwrap();
+        let job_id: JobId = serde_json::from_value(job_id).unwrap();
+
+        // Anvil account 1.
+        let other = SigningKey::from_str(
+            "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+        )
+        .unwrap();
+        let err = super::cancel_job(ctx.clone(), signed_cancel(&job_id, &other, nonce))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Only the job's submitter can cancel it")
+        );
+
+        // A signature over another job does not verify.
+        let mut forged = signed_cancel(&"other".into(), &signing_key(), nonce + 1);
+        forged.job_id = job_id.clone();
+        let err = super::cancel_job(ctx.clone(), forged).await.unwrap_err();
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Invalid cancellation signature")
+        );
+
+        let state = super::cancel_job(
+            ctx.clone(),
+            signed_cancel(&job_id, &signing_key(), nonce + 1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(state, serde_json::json!("cancelled"));
+
+        // Cancelling again reports the same state instead of failing.
+        let state = super::cancel_job(ctx, signed_cancel(&job_id, &signing_key(), nonce + 2))
+            .await
+            .unwrap();
+        assert_eq!(state, serde_json::json!("cancelled"));
+    }
+
+    #[tokio::test]
+    async fn cancel_job_rejects_a_replayed_cancellation_after_resubmit() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let nonce = now_millis();
+        let job_id = super::send_proof_input(ctx.clone(), signed_submission(&url, nonce))
+            .await
+            .unwrap();
+        let job_id: JobId = serde_json::from_value(job_id).unwrap();
+
+        let captured = signed_cancel(&job_id, &signing_key(), nonce + 1);
+        let replayed = signed_cancel(&job_id, &signing_key(), nonce + 1);
+        let state = super::cancel_job(ctx.clone(), captured).await.unwrap();
+        assert_eq!(state, serde_json::json!("cancelled"));
+
+        super::resubmit_proof_input(ctx.clone(), signed_submission(&url, nonce + 2))
+            .await
+            .unwrap();
+        let err = super::cancel_job(ctx.clone(), replayed).await.unwrap_err();
+        assert!(
+            matches!(err, mojave_rpc_core::RpcErr::BadParams(ref msg) if msg == "Nonce already used")
+        );
+        assert!(ctx.job_store.is_pending(&job_id).await);
+    }
+
+    #[tokio::test]
+    async fn get_job_status_reports_each_state() {
+        let ctx = make_ctx(8).await;
+        let status = |job_id: &str| super::get_job_status(ctx.clone(), job_id.into());
+
+        for id in ["queued-a", "queued-b"] {
+            ctx.job_store.insert_job(id.into(), 1).await;
+        }
+        assert_eq!(
+            status("queued-b").await.unwrap(),
+            serde_json::json!({ "queued": { "position": 1 } })
+        );
+
+        *ctx.running_job.lock().await = Some(crate::rpc::context::RunningJob {
+            job_id: "proving".into(),
+            started_at: 42,
+            cancel: Default::default(),
+        });
+        assert_eq!(
+            status("proving").await.unwrap(),
+            serde_json::json!({ "proving": { "startedAt": 42 } })
+        );
+
+        ctx.job_store
+            .upsert_proof(
+                &"failed".into(),
+                ProofResponse {
+                    job_id: "failed".into(),
+                    batch_number: 1,
+                    result: ProofResult::Error("boom".into()),
+                },
+            )
+            .await;
+        assert_eq!(
+            status("failed").await.unwrap(),
+            serde_json::json!({ "failed": "boom" })
+        );
+
+        assert_eq!(
+            status("missing").await.unwrap(),
+            serde_json::json!("notFound")
+        );
+    }
 }
@@ -1,7 +1,7 @@
 mod api;
 pub mod context;
 mod handlers;
-mod tasks;
+pub(crate) mod tasks;
 mod types;
 
 pub use api::start_api;
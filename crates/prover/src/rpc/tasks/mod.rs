@@ -1,3 +1,6 @@
 mod proof_worker;
 
-pub(crate) use proof_worker::spawn_proof_worker;
+#[cfg(test)]
+pub(crate) use proof_worker::wait_for_proof;
+pub use proof_worker::{JobState, ProofWorker, ProverRequest};
+pub(crate) use proof_worker::{finished_state, proof_message_id, spawn_proof_driver};
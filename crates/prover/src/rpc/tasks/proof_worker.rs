@@ -1,96 +1,576 @@
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use ethrex_prover_lib::{backend::Backend, prove, to_batch_proof};
-use ethrex_rpc::RpcErr;
-use mojave_client::types::{ProofResponse, ProofResult};
+use mojave_client::types::{JobId, ProofResponse, ProofResult};
 use mojave_msgio::types::{Message, MessageHeader, MessageKind};
-use mojave_utils::hash;
-use tokio::{sync::mpsc, task::JoinHandle};
+use mojave_task::Task;
+use mojave_utils::{
+    hash,
+    rpc::error::{Error, Result},
+};
+use serde::Serialize;
+use tokio::{sync::Notify, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 
-use crate::rpc::{ProverRpcContext, types::JobRecord};
+use crate::rpc::{ProverRpcContext, context::RunningJob, types::JobRecord};
 
-pub(crate) fn spawn_proof_worker(
-    ctx: Arc<ProverRpcContext>,
-    mut receiver: mpsc::Receiver<JobRecord>,
+/// Message id under which the proof response for `job_id` is published.
+pub(crate) fn proof_message_id(job_id: &JobId) -> String {
+    hex::encode(hash::compute_keccak(job_id.as_str().as_bytes()))
+}
+
+pub enum ProverRequest {
+    /// Append a job to the proving queue.
+    SubmitJob(JobRecord),
+    /// Drop a job that is still queued. Other jobs are left untouched and
+    /// their state is returned.
+    CancelJob(JobId),
+    QueryStatus(JobId),
+    /// Start proving the job at the head of the queue, unless a proof is
+    /// already running.
+    ProveNext,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum JobState {
+    Queued {
+        position: usize,
+    },
+    /// Being proven since `started_at`, a Unix timestamp in seconds.
+    Proving {
+        started_at: u64,
+    },
+    Proven,
+    Failed(String),
+    Cancelled,
+    NotFound,
+}
+
+/// Proof job processor driven by the `mojave-task` runtime.
+///
+/// Submissions land in a bounded queue. [`ProverRequest::ProveNext`] starts
+/// the head of the queue on its own task, so the worker keeps answering
+/// requests while it is proven; one job is proven at a time.
+pub struct ProofWorker {
+    ctx: Weak<ProverRpcContext>,
+    queue: VecDeque<JobRecord>,
+    queue_capacity: usize,
+}
+
+impl Task for ProofWorker {
+    type Request = ProverRequest;
+    type Response = JobState;
+    type Error = Error;
+
+    async fn handle_request(&mut self, request: Self::Request) -> Result<Self::Response> {
+        match request {
+            ProverRequest::SubmitJob(job) => self.submit(job),
+            ProverRequest::CancelJob(job_id) => Ok(self.cancel(&job_id).await),
+            ProverRequest::QueryStatus(job_id) => Ok(self.status(&job_id).await),
+            ProverRequest::ProveNext => Ok(self.prove_next().await),
+        }
+    }
+
+    async fn on_shutdown(&mut self) -> Result<()> {
+        tracing::info!(queued = self.queue.len(), "Proof worker stopping");
+        Ok(())
+    }
+}
+
+impl ProofWorker {
+    pub fn new(ctx: Weak<ProverRpcContext>, queue_capacity: usize) -> Self {
+        Self {
+            ctx,
+            queue: VecDeque::with_capacity(queue_capacity),
+            queue_capacity,
+        }
+    }
+
+    fn submit(&mut self, job: JobRecord) -> Result<JobState> {
+        if self.queue.len() >= self.queue_capacity {
+            return Err(Error::Internal(format!(
+                "Proof queue is full (capacity {})",
+                self.queue_capacity
+            )));
+        }
+        tracing::debug!(job_id = %job.job_id, "Worker received job");
+        self.queue.push_back(job);
+        if let Some(ctx) = self.ctx.upgrade() {
+            ctx.job_ready.notify_one();
+        }
+        Ok(JobState::Queued {
+            position: self.queue.len() - 1,
+        })
+    }
+
+    async fn cancel(&mut self, job_id: &JobId) -> JobState {
+        let Some(index) = self.queue.iter().position(|job| &job.job_id == job_id) else {
+            return self.status(job_id).await;
+        };
+        self.queue.remove(index);
+        if let Some(ctx) = self.ctx.upgrade() {
+            ctx.job_store.mark_cancelled(job_id).await;
+        }
+        tracing::info!(job_id = %job_id, "Proof job cancelled");
+        JobState::Cancelled
+    }
+
+    async fn status(&self, job_id: &JobId) -> JobState {
+        if let Some(position) = self.queue.iter().position(|job| &job.job_id == job_id) {
+            return JobState::Queued { position };
+        }
+        match self.ctx.upgrade() {
+            Some(ctx) => finished_state(&ctx, job_id).await,
+            None => JobState::NotFound,
+        }
+    }
+
+    /// Starts proving the head of the queue and returns
+    /// [`JobState::Proving`] for the running job, or [`JobState::NotFound`]
+    /// when the queue is empty.
+    async fn prove_next(&mut self) -> JobState {
+        let Some(ctx) = self.ctx.upgrade() else {
+            return JobState::NotFound;
+        };
+        let mut running = ctx.running_job.lock().await;
+        if let Some(job) = running.as_ref() {
+            return JobState::Proving {
+                started_at: job.started_at,
+            };
+        }
+        let Some(job) = self.queue.pop_front() else {
+            return JobState::NotFound;
+        };
+
+        let cancel = CancellationToken::new();
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        *running = Some(RunningJob {
+            job_id: job.job_id.clone(),
+            started_at,
+            cancel: cancel.clone(),
+        });
+        drop(running);
+
+        tokio::spawn(run_job(ctx.clone(), job, cancel));
+        JobState::Proving { started_at }
+    }
+}
+
+/// State of a job that is neither queued nor proving.
+pub(crate) async fn finished_state(ctx: &ProverRpcContext, job_id: &JobId) -> JobState {
+    if ctx.job_store.is_cancelled(job_id).await {
+        return JobState::Cancelled;
+    }
+    match ctx.job_store.get_proof_by_id(job_id).await {
+        Some(ProofResponse {
+            result: ProofResult::Proof(_),
+            ..
+        }) => JobState::Proven,
+        Some(ProofResponse {
+            result: ProofResult::Error(error),
+            ..
+        }) => JobState::Failed(error),
+        Some(ProofResponse {
+            result: ProofResult::NotReady { .. },
+            ..
+        })
+        | None => JobState::NotFound,
+    }
+}
+
+/// Starts the next queued job each time `job_ready` is notified, i.e. when a
+/// job is submitted or the running one ends. Stops once the context is gone.
+pub(crate) fn spawn_proof_driver(
+    ctx: Weak<ProverRpcContext>,
+    job_ready: Arc<Notify>,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
-        tracing::info!("Proof worker started");
         loop {
-            match receiver.recv().await {
-                Some(job) => {
-                    tracing::debug!(job_id = %job.job_id.as_ref(), "Worker received job");
-
-                    let batch_number = job.prover_data.batch_number;
-                    let program_input = job.prover_data.input;
-                    let try_generate_proof = prove(Backend::Exec, program_input, ctx.aligned_mode)
-                        .and_then(|output| to_batch_proof(output, ctx.aligned_mode))
-                        .map_err(|err| {
-                            RpcErr::Internal(format!("Error while generate proof: {err:}"))
-                        });
-
-                    let result = match try_generate_proof {
-                        Ok(proof) => {
-                            tracing::info!(job_id = %job.job_id.as_ref(), %batch_number, "Proof generated");
-                            ProofResult::Proof(proof)
-                        }
-                        Err(e) => {
-                            tracing::error!(job_id = %job.job_id.as_ref(), %batch_number, error = %e, "Proof generation failed");
-                            ProofResult::Error(e.to_string())
-                        }
-                    };
-
-                    let proof_response = ProofResponse {
-                        job_id: job.job_id,
-                        batch_number,
-                        result,
-                    };
-
-                    ctx.job_store
-                        .upsert_proof(&proof_response.job_id, proof_response.clone())
-                        .await;
-
-                    let msg_id = hex::encode(hash::compute_keccak(
-                        proof_response.job_id.as_str().as_bytes(),
-                    ));
-
-                    // TODO: change this in memory dedup in future
-                    {
-                        let mut g = ctx.sent_ids.lock().await;
-                        if g.contains(&msg_id) {
-                            tracing::warn!(%msg_id, "duplicate proof publish suppressed");
-                            continue;
-                        }
-                        g.insert(msg_id.clone());
-                    }
-
-                    let msg = Message {
-                        header: MessageHeader {
-                            version: 1,
-                            kind: MessageKind::ProofResponse,
-                            message_id: msg_id,
-                            // Sequence number is currently unused; always set to 1 as a placeholder.
-                            seq: 1,
-                        },
-                        body: &proof_response,
-                    };
-
-                    let msg_byte = match bincode::serialize(&msg) {
-                        Ok(byte) => byte,
-                        Err(e) => {
-                            tracing::error!(error = %e, "Failed to serialize envelope");
-                            continue;
-                        }
-                    };
-
-                    if let Err(error) = ctx.publisher.publish(msg_byte.into()).await {
-                        tracing::error!(error = ?error, "Failed to publish proof response");
-                    }
-                }
-                None => {
-                    tracing::info!("Proof worker channel closed; stopping");
-                    break;
-                }
+            job_ready.notified().await;
+            let Some(ctx) = ctx.upgrade() else {
+                break;
+            };
+            if let Err(error) = ctx.worker.request(ProverRequest::ProveNext).await {
+                tracing::warn!(%error, "Failed to start the next proof job");
             }
         }
     })
 }
+
+/// Proves the running job, then frees the worker for the next one.
+async fn run_job(ctx: Arc<ProverRpcContext>, job: JobRecord, cancel: CancellationToken) {
+    prove_job(&ctx, job, cancel).await;
+
+    *ctx.running_job.lock().await = None;
+    ctx.job_ready.notify_one();
+}
+
+/// Proves `job` and stores and publishes the result, unless `cancel` fires
+/// first. Backends cannot be interrupted mid-proof, so a cancelled proof is
+/// still awaited and its result discarded. That keeps the next job from
+/// starting while the prover is busy.
+async fn prove_job(
+    ctx: &Arc<ProverRpcContext>,
+    job: JobRecord,
+    cancel: CancellationToken,
+) -> JobState {
+    if cancel.is_cancelled() {
+        return JobState::Cancelled;
+    }
+
+    let batch_number = job.prover_data.batch_number;
+    let prove = ctx.backend.prove(job.prover_data.input);
+    tokio::pin!(prove);
+    let try_generate_proof = tokio::select! {
+        biased;
+
+        _ = cancel.cancelled() => {
+            tracing::info!(job_id = %job.job_id, "Waiting for the cancelled proof to stop");
+            let _ = prove.await;
+            return JobState::Cancelled;
+        }
+        result = &mut prove => result,
+    };
+
+    let (result, state) = match try_generate_proof {
+        Ok(proof) => {
+            tracing::info!(job_id = %job.job_id.as_ref(), %batch_number, "Proof generated");
+            (ProofResult::Proof(proof), JobState::Proven)
+        }
+        Err(e) => {
+            tracing::error!(job_id = %job.job_id.as_ref(), %batch_number, error = %e, "Proof generation failed");
+            (
+                ProofResult::Error(e.to_string()),
+                JobState::Failed(e.to_string()),
+            )
+        }
+    };
+
+    let proof_response = ProofResponse {
+        job_id: job.job_id,
+        batch_number,
+        result,
+    };
+
+    ctx.job_store
+        .upsert_proof(&proof_response.job_id, proof_response.clone())
+        .await;
+
+    publish_proof(ctx, &proof_response).await;
+    state
+}
+
+async fn publish_proof(ctx: &ProverRpcContext, proof_response: &ProofResponse) {
+    let msg_id = proof_message_id(&proof_response.job_id);
+
+    // TODO: change this in memory dedup in future
+    {
+        let mut g = ctx.sent_ids.lock().await;
+        if g.contains(&msg_id) {
+            tracing::warn!(%msg_id, "duplicate proof publish suppressed");
+            return;
+        }
+        g.insert(msg_id.clone());
+    }
+
+    let msg = Message {
+        header: MessageHeader {
+            version: 1,
+            kind: MessageKind::ProofResponse,
+            message_id: msg_id,
+            // Sequence number is currently unused; always set to 1 as a placeholder.
+            seq: 1,
+        },
+        body: proof_response,
+    };
+
+    let msg_byte = match bincode::serialize(&msg) {
+        Ok(byte) => byte,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to serialize envelope");
+            return;
+        }
+    };
+
+    if let Err(error) = ctx.publisher.publish(msg_byte.into()).await {
+        tracing::error!(error = ?error, "Failed to publish proof response");
+    }
+}
+
+/// Waits for the proof of `job_id` to be stored.
+#[cfg(test)]
+pub(crate) async fn wait_for_proof(ctx: &ProverRpcContext, job_id: &JobId) -> ProofResponse {
+    tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            if let Some(proof) = ctx.job_store.get_proof_by_id(job_id).await {
+                return proof;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("job was not proven in time")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::{MockBackend, ProverBackend},
+        job::JobStore,
+    };
+    use async_trait::async_trait;
+    use ethrex_l2_common::prover::BatchProof;
+    use guest_program::input::ProgramInput;
+    use mojave_client::types::ProverData;
+    use reqwest::Url;
+
+    /// Proves like [`MockBackend`] once released.
+    struct GatedBackend(Arc<Notify>);
+
+    #[async_trait]
+    impl ProverBackend for GatedBackend {
+        async fn prove(&self, input: ProgramInput) -> Result<BatchProof> {
+            self.0.notified().await;
+            MockBackend.prove(input).await
+        }
+    }
+
+    fn record(job_id: &str) -> JobRecord {
+        JobRecord {
+            job_id: job_id.into(),
+            prover_data: ProverData {
+                batch_number: 1,
+                input: ProgramInput::default(),
+            },
+            sequencer_url: Url::parse("http://localhost:1234").unwrap(),
+        }
+    }
+
+    async fn make_ctx(capacity: usize) -> Arc<ProverRpcContext> {
+        let publisher = Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap());
+        ProverRpcContext::with_worker(
+            Arc::new(MockBackend),
+            publisher,
+            capacity,
+            JobStore::default(),
+            Default::default(),
+            Task::spawn,
+        )
+    }
+
+    #[tokio::test]
+    async fn submitted_jobs_report_queue_position() {
+        let ctx = make_ctx(4).await;
+        for id in ["a", "b"] {
+            ctx.worker
+                .request(ProverRequest::SubmitJob(record(id)))
+                .await
+                .unwrap();
+        }
+
+        let state = ctx
+            .worker
+            .request(ProverRequest::QueryStatus("b".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::Queued { position: 1 });
+
+        let state = ctx
+            .worker
+            .request(ProverRequest::QueryStatus("missing".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::NotFound);
+    }
+
+    #[tokio::test]
+    async fn submit_rejects_when_queue_is_full() {
+        let ctx = make_ctx(1).await;
+        ctx.worker
+            .request(ProverRequest::SubmitJob(record("a")))
+            .await
+            .unwrap();
+
+        let err = ctx
+            .worker
+            .request(ProverRequest::SubmitJob(record("b")))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("queue is full"));
+    }
+
+    #[tokio::test]
+    async fn cancel_removes_queued_job() {
+        let ctx = make_ctx(4).await;
+        ctx.job_store.insert_job("a".into(), 1).await;
+        ctx.worker
+            .request(ProverRequest::SubmitJob(record("a")))
+            .await
+            .unwrap();
+
+        let state = ctx
+            .worker
+            .request(ProverRequest::CancelJob("a".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::Cancelled);
+        assert!(!ctx.job_store.is_pending(&"a".into()).await);
+
+        // Nothing left to prove.
+        let state = ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        assert_eq!(state, JobState::NotFound);
+    }
+
+    #[tokio::test]
+    async fn cancel_of_finished_job_reports_its_state() {
+        let ctx = make_ctx(4).await;
+        ctx.job_store
+            .upsert_proof(
+                &"a".into(),
+                ProofResponse {
+                    job_id: "a".into(),
+                    batch_number: 1,
+                    result: ProofResult::Error("boom".into()),
+                },
+            )
+            .await;
+
+        let state = ctx
+            .worker
+            .request(ProverRequest::CancelJob("a".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::Failed("boom".into()));
+    }
+
+    #[tokio::test]
+    async fn cancelled_running_job_discards_its_proof() {
+        let ctx = make_ctx(4).await;
+        ctx.job_store.insert_job("a".into(), 1).await;
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let state = prove_job(&ctx, record("a"), cancel).await;
+
+        assert_eq!(state, JobState::Cancelled);
+        assert!(ctx.job_store.get_proof_by_id(&"a".into()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn worker_answers_while_a_job_is_proving() {
+        let release = Arc::new(Notify::new());
+        let publisher = Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap());
+        let ctx = ProverRpcContext::with_worker(
+            Arc::new(GatedBackend(release.clone())),
+            publisher,
+            4,
+            JobStore::default(),
+            Default::default(),
+            Task::spawn,
+        );
+        for id in ["a", "b"] {
+            ctx.job_store.insert_job(id.into(), 1).await;
+            ctx.worker
+                .request(ProverRequest::SubmitJob(record(id)))
+                .await
+                .unwrap();
+        }
+
+        let state = ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        assert!(matches!(state, JobState::Proving { .. }));
+
+        // The worker keeps serving requests and does not start "b" yet.
+        let state = ctx
+            .worker
+            .request(ProverRequest::QueryStatus("b".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::Queued { position: 0 });
+        let state = ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        assert!(matches!(state, JobState::Proving { .. }));
+
+        release.notify_one();
+        wait_for_proof(&ctx, &"a".into()).await;
+    }
+
+    #[tokio::test]
+    async fn next_job_waits_for_the_cancelled_proof_to_stop() {
+        let release = Arc::new(Notify::new());
+        let publisher = Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap());
+        let ctx = ProverRpcContext::with_worker(
+            Arc::new(GatedBackend(release.clone())),
+            publisher,
+            4,
+            JobStore::default(),
+            Default::default(),
+            Task::spawn,
+        );
+        for id in ["a", "b"] {
+            ctx.job_store.insert_job(id.into(), 1).await;
+            ctx.worker
+                .request(ProverRequest::SubmitJob(record(id)))
+                .await
+                .unwrap();
+        }
+        ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        ctx.running_job
+            .lock()
+            .await
+            .as_ref()
+            .unwrap()
+            .cancel
+            .cancel();
+
+        // "a" is still being proven, so "b" cannot start.
+        tokio::task::yield_now().await;
+        ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        let state = ctx
+            .worker
+            .request(ProverRequest::QueryStatus("b".into()))
+            .await
+            .unwrap();
+        assert_eq!(state, JobState::Queued { position: 0 });
+
+        release.notify_one();
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while ctx.running_job.lock().await.is_some() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(ctx.job_store.get_proof_by_id(&"a".into()).await.is_none());
+        let state = ctx.worker.request(ProverRequest::ProveNext).await.unwrap();
+        assert!(matches!(state, JobState::Proving { .. }));
+    }
+
+    #[tokio::test]
+    async fn submitted_jobs_are_proven_without_polling() {
+        let publisher = Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap());
+        let ctx = ProverRpcContext::new(
+            Arc::new(MockBackend),
+            publisher,
+            4,
+            JobStore::default(),
+            Default::default(),
+        );
+        for id in ["a", "b"] {
+            ctx.job_store.insert_job(id.into(), 1).await;
+            ctx.worker
+                .request(ProverRequest::SubmitJob(record(id)))
+                .await
+                .unwrap();
+        }
+
+        wait_for_proof(&ctx, &"a".into()).await;
+        wait_for_proof(&ctx, &"b".into()).await;
+    }
+}
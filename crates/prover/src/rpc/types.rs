@@ -1,4 +1,4 @@
-use mojave_client::types::ProverData;
+use mojave_client::types::{JobId, ProverData, RequestSignature};
 use reqwest::Url;
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -6,6 +6,13 @@ use reqwest::Url;
 pub struct SendProofInputRequest {
     pub prover_data: ProverData,
     pub sequencer_addr: Url,
+    /// Replay-protection nonce, see [`crate::services::jobs::check_submission_nonce`].
+    /// Optional so submitters that predate it keep working.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// See [`crate::services::jobs::verify_proof_input_signature`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<RequestSignature>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -15,6 +22,32 @@ pub enum SendProofInputParam {
     Tuple((ProverData, Url)),
 }
 
+impl SendProofInputParam {
+    /// Splits the params into the input, sequencer address, nonce and
+    /// signature. The tuple form carries neither of the last two.
+    pub fn into_parts(self) -> (ProverData, Url, Option<u64>, Option<RequestSignature>) {
+        match self {
+            SendProofInputParam::Object(obj) => (
+                obj.prover_data,
+                obj.sequencer_addr,
+                obj.nonce,
+                obj.signature,
+            ),
+            SendProofInputParam::Tuple((prover_data, url)) => (prover_data, url, None, None),
+        }
+    }
+}
+
+/// Params of `moj_cancelJob`, signed by the key that submitted the job.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CancelJobRequest {
+    pub job_id: JobId,
+    /// Replay-protection nonce, see [`crate::services::jobs::check_submission_nonce`].
+    pub nonce: u64,
+    pub signature: RequestSignature,
+}
+
 pub use crate::job::JobRecord;
 
 #[cfg(test)]
@@ -73,6 +106,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn trailing_nonce_deserializes() {
+        let url = "http://127.0.0.1:1234";
+        let payload = json!([dummy_prover_data(), url, 42]);
+
+        match serde_json::from_value::<SendProofInputParam>(payload).unwrap() {
+            SendProofInputParam::Object(o) => assert_eq!(o.nonce, Some(42)),
+            _ => panic!("expected object"),
+        }
+    }
+
     #[test]
     fn invalid_shape_fails_fast() {
         let cases = vec![
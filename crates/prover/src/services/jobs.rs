@@ -1,16 +1,178 @@
-use crate::{job::JobRecord, rpc::ProverRpcContext};
+use crate::{
+    job::{JobRecord, JobStore},
+    rpc::{
+        ProverRpcContext,
+        tasks::{JobState, ProverRequest, finished_state, proof_message_id},
+    },
+};
 use guest_program::input::ProgramInput;
-use mojave_client::types::{JobId, ProofResponse, ProverData};
+use mojave_client::types::{
+    CancelJobMessage, JobId, ProofInputMessage, ProofResponse, ProofResult, ProverData,
+    RequestSignature,
+};
+use mojave_signature::types::Verifier;
 use mojave_utils::{
     hash::compute_keccak,
     rpc::error::{Error, Result},
 };
 use reqwest::Url;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::MutexGuard;
+
+/// Oldest proof-input nonce accepted, measured against the prover's clock.
+/// Bounds replays of captured submissions after a restart without persisted
+/// jobs, when the per signer high-water marks are gone.
+pub const MAX_NONCE_AGE: Duration = Duration::from_secs(5 * 60);
+
+/// How far ahead of the prover's clock a nonce may be. A nonce far in the
+/// future would lock its signer out until the clock caught up with it.
+pub const MAX_NONCE_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// A nonce that passed [`check_submission_nonce`]. It is only recorded by
+/// [`NonceReservation::commit`], so a submission that fails later can be
+/// retried with the same nonce. Other submissions wait until it is dropped.
+pub struct NonceReservation<'a> {
+    nonces: MutexGuard<'a, HashMap<String, u64>>,
+    job_store: &'a JobStore,
+    signer: String,
+    nonce: u64,
+}
+
+impl NonceReservation<'_> {
+    pub async fn commit(mut self) {
+        self.nonces.insert(self.signer, self.nonce);
+        self.job_store.persist_nonces(&self.nonces).await;
+    }
+}
 
+/// Rejects a signed proof-input submission or job cancellation whose nonce is
+/// missing, was already used, or is too far from the prover's clock.
+///
+/// Nonces are Unix timestamps in milliseconds that must strictly increase per
+/// signer, as returned by [`verify_proof_input_signature`]. Unsigned
+/// submissions have no authenticated sender to track and are let through.
+pub async fn check_submission_nonce<'a>(
+    ctx: &'a ProverRpcContext,
+    signer: Option<&str>,
+    nonce: Option<u64>,
+) -> Result<Option<NonceReservation<'a>>> {
+    let Some(signer) = signer else {
+        return Ok(None);
+    };
+    let Some(nonce) = nonce else {
+        tracing::warn!(signer = %signer, "Rejected signed proof input without a nonce");
+        return Err(Error::BadParams(
+            "Signed proof input requires a nonce".to_owned(),
+        ));
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+    if nonce < now.saturating_sub(MAX_NONCE_AGE.as_millis() as u64) {
+        tracing::warn!(signer = %signer, nonce, "Rejected stale proof input nonce");
+        return Err(Error::BadParams("Stale nonce".to_owned()));
+    }
+    if nonce > now.saturating_add(MAX_NONCE_SKEW.as_millis() as u64) {
+        tracing::warn!(signer = %signer, nonce, "Rejected proof input nonce from the future");
+        return Err(Error::BadParams("Nonce too far in the future".to_owned()));
+    }
+
+    let nonces = ctx.submission_nonces.lock().await;
+    if let Some(&last) = nonces.get(signer)
+        && nonce <= last
+    {
+        tracing::warn!(signer = %signer, nonce, last, "Rejected replayed proof input nonce");
+        return Err(Error::BadParams("Nonce already used".to_owned()));
+    }
+    Ok(Some(NonceReservation {
+        nonces,
+        job_store: &ctx.job_store,
+        signer: signer.to_owned(),
+        nonce,
+    }))
+}
+
+/// Rejects proof inputs not signed by one of the prover's trusted signers.
+/// Without any configured, every verified signer is accepted, and so are
+/// unsigned inputs.
+pub fn check_trusted_signer(ctx: &ProverRpcContext, signer: Option<&str>) -> Result<()> {
+    if ctx.trusted_signers.is_empty() {
+        return Ok(());
+    }
+    match signer {
+        Some(signer) if ctx.trusted_signers.contains(signer) => Ok(()),
+        Some(signer) => {
+            tracing::warn!(signer = %signer, "Rejected proof input from untrusted signer");
+            Err(Error::BadParams("Untrusted proof input signer".to_owned()))
+        }
+        None => {
+            tracing::warn!("Rejected unsigned proof input");
+            Err(Error::BadParams("Proof input must be signed".to_owned()))
+        }
+    }
+}
+
+/// Verifies the signature attached to a proof input and returns the
+/// signer's address, or `None` for unsigned submissions.
+pub fn verify_proof_input_signature(
+    prover_data: &ProverData,
+    sequencer_addr: &Url,
+    nonce: Option<u64>,
+    signature: Option<&RequestSignature>,
+) -> Result<Option<String>> {
+    let Some(RequestSignature {
+        signature,
+        verifying_key,
+    }) = signature
+    else {
+        return Ok(None);
+    };
+
+    let message = ProofInputMessage {
+        prover_data,
+        sequencer_addr: sequencer_addr.as_str(),
+        nonce,
+    };
+    verifying_key.verify(&message, signature).map_err(|error| {
+        tracing::warn!(sequencer = %sequencer_addr, %error, "Rejected proof input with invalid signature");
+        Error::BadParams("Invalid proof input signature".to_owned())
+    })?;
+    Ok(Some(verifying_key.to_address()))
+}
+
+/// Verifies the signature on a job cancellation and returns the signer's
+/// address. The nonce is checked separately by [`check_submission_nonce`].
+pub fn verify_cancel_signature(
+    job_id: &JobId,
+    nonce: u64,
+    signature: &RequestSignature,
+) -> Result<String> {
+    let RequestSignature {
+        signature,
+        verifying_key,
+    } = signature;
+    verifying_key
+        .verify(&CancelJobMessage { job_id, nonce }, signature)
+        .map_err(|error| {
+            tracing::warn!(job_id = %job_id, %error, "Rejected cancellation with invalid signature");
+            Error::BadParams("Invalid cancellation signature".to_owned())
+        })?;
+    Ok(verifying_key.to_address())
+}
+
+/// Queues a proof job for the input. `submitter` is the address that signed
+/// the submission, the only one allowed to cancel the job.
 pub async fn enqueue_proof_input(
     ctx: &ProverRpcContext,
     prover_data: ProverData,
     sequencer_addr: Url,
+    submitter: Option<String>,
 ) -> Result<JobId> {
     let job_id = calculate_job_id(&prover_data.input)?;
     tracing::debug!(job_id = %job_id, sequencer = %sequencer_addr, "Parsed proof input");
@@ -19,44 +181,165 @@ pub async fn enqueue_proof_input(
         return Err(Error::BadParams("This batch already requested".to_owned()));
     }
 
+    let batch_number = prover_data.batch_number;
     let record = JobRecord {
         job_id: job_id.clone(),
         prover_data,
         sequencer_url: sequencer_addr,
     };
-    ctx.job_store.insert_job(job_id.clone()).await;
-    ctx.sender
-        .send(record)
-        .await
-        .map_err(|e| Error::Internal(format!("Error sending job to channel: {e}")))?;
+    ctx.job_store.insert_job(job_id.clone(), batch_number).await;
+    if let Some(submitter) = submitter {
+        ctx.job_store.set_submitter(&job_id, submitter).await;
+    }
+    if let Err(e) = ctx.worker.request(ProverRequest::SubmitJob(record)).await {
+        ctx.job_store.remove_pending(&job_id).await;
+        return Err(Error::Internal(format!(
+            "Error submitting job to proof worker: {e}"
+        )));
+    }
     Ok(job_id)
 }
 
+/// Ensures a proof job exists for the given input and returns its id.
+///
+/// Pending jobs and successfully proven jobs are left untouched. Jobs whose
+/// proof failed, or which the prover has no record of, are enqueued again.
+pub async fn resubmit_proof_input(
+    ctx: &ProverRpcContext,
+    prover_data: ProverData,
+    sequencer_addr: Url,
+    submitter: Option<String>,
+) -> Result<JobId> {
+    let job_id = calculate_job_id(&prover_data.input)?;
+    if ctx.job_store.is_pending(&job_id).await {
+        tracing::debug!(job_id = %job_id, "Resubmitted job is still pending");
+        return Ok(job_id);
+    }
+
+    match ctx.job_store.get_proof_by_id(&job_id).await {
+        Some(ProofResponse {
+            result: ProofResult::Proof(_),
+            ..
+        }) => {
+            tracing::debug!(job_id = %job_id, "Resubmitted job is already proven");
+            return Ok(job_id);
+        }
+        Some(_) => {
+            tracing::info!(job_id = %job_id, batch_number = prover_data.batch_number, "Re-enqueueing failed proof job");
+            ctx.job_store.remove_proof(&job_id).await;
+            ctx.sent_ids.lock().await.remove(&proof_message_id(&job_id));
+        }
+        None => {
+            tracing::info!(job_id = %job_id, batch_number = prover_data.batch_number, "Enqueueing unknown proof job");
+        }
+    }
+
+    enqueue_proof_input(ctx, prover_data, sequencer_addr, submitter).await
+}
+
+/// Cancels a queued or running job on behalf of `signer`, who must have
+/// signed its submission. Finished and unknown jobs are left untouched and
+/// their current state is returned.
+pub async fn cancel_job(ctx: &ProverRpcContext, job_id: &JobId, signer: &str) -> Result<JobState> {
+    if ctx.job_store.is_pending(job_id).await
+        && ctx.job_store.submitter(job_id).await.as_deref() != Some(signer)
+    {
+        tracing::warn!(job_id = %job_id, signer = %signer, "Rejected cancellation by a non-submitter");
+        return Err(Error::BadParams(
+            "Only the job's submitter can cancel it".to_owned(),
+        ));
+    }
+
+    // The worker does not track the running job, so cancel it here.
+    if let Some(running) = ctx.running_job.lock().await.as_ref()
+        && &running.job_id == job_id
+        && !running.cancel.is_cancelled()
+    {
+        running.cancel.cancel();
+        ctx.job_store.mark_cancelled(job_id).await;
+        tracing::info!(job_id = %job_id, "Running proof job cancelled");
+        return Ok(JobState::Cancelled);
+    }
+
+    ctx.worker
+        .request(ProverRequest::CancelJob(job_id.clone()))
+        .await
+        .map_err(|e| Error::Internal(format!("Error cancelling job: {e}")))
+}
+
+/// Returns where the job is: queued with its queue position, being proven,
+/// or finished. Answered from the job store without going through the worker.
+pub async fn get_job_state(ctx: &ProverRpcContext, job_id: &JobId) -> Result<JobState> {
+    let running_id = match ctx.running_job.lock().await.as_ref() {
+        Some(running) if &running.job_id == job_id && !running.cancel.is_cancelled() => {
+            return Ok(JobState::Proving {
+                started_at: running.started_at,
+            });
+        }
+        running => running.map(|running| running.job_id.clone()),
+    };
+
+    if let Some(position) = ctx
+        .job_store
+        .queue_position(job_id, running_id.as_ref())
+        .await
+    {
+        return Ok(JobState::Queued { position });
+    }
+    Ok(finished_state(ctx, job_id).await)
+}
+
 #[inline]
 pub async fn get_pending_job_ids(ctx: &ProverRpcContext) -> Result<Vec<JobId>> {
     Ok(ctx.job_store.get_pending_jobs().await)
 }
 
+/// Returns the finished proof, or a [`ProofResult::NotReady`] response while
+/// the job is still queued or proving. Unknown jobs are an error.
 pub async fn get_proof(ctx: &ProverRpcContext, job_id: &JobId) -> Result<ProofResponse> {
-    ctx.job_store
-        .get_proof_by_id(job_id)
+    match ctx.job_store.get_proof_by_id(job_id).await {
+        Some(proof_response) => Ok(proof_response),
+        None => not_ready_response(ctx, job_id).await,
+    }
+}
+
+/// Like [`get_proof`], but returns the JSON response, served from the job
+/// store's cache once a proof has been fetched.
+pub async fn get_serialized_proof(ctx: &ProverRpcContext, job_id: &JobId) -> Result<Value> {
+    let cached = ctx
+        .job_store
+        .get_serialized_proof(job_id)
+        .await
+        .map_err(|e| Error::Internal(e.to_string()))?;
+    match cached {
+        Some(value) => Ok(value),
+        None => serde_json::to_value(not_ready_response(ctx, job_id).await?)
+            .map_err(|e| Error::Internal(e.to_string())),
+    }
+}
+
+async fn not_ready_response(ctx: &ProverRpcContext, job_id: &JobId) -> Result<ProofResponse> {
+    let batch_number = ctx
+        .job_store
+        .pending_batch_number(job_id)
         .await
         .ok_or(Error::Internal(format!(
             "No proof exist with job id {job_id}"
-        )))
+        )))?;
+    Ok(ProofResponse {
+        job_id: job_id.clone(),
+        batch_number,
+        result: ProofResult::NotReady { progress: None },
+    })
 }
 
+/// Job ids are the hash of the whole input, so resubmitting the same input
+/// finds the existing job while a changed input for the same blocks does not.
 fn calculate_job_id(prover_input: &ProgramInput) -> Result<JobId> {
-    let mut block_hashes: Vec<String> = prover_input
-        .blocks
-        .iter()
-        .map(|b| b.hash().to_string())
-        .collect();
-    block_hashes.sort_unstable();
-    let serialized_block_hashes = bincode::serialize(&block_hashes)
+    let serialized_input = bincode::serialize(prover_input)
         .map_err(|err| Error::Internal(format!("Error to serialize program input: {err}")))?;
 
-    let job_id = hex::encode(compute_keccak(&serialized_block_hashes));
+    let job_id = hex::encode(compute_keccak(&serialized_input));
     tracing::trace!(job_id = %job_id, "Calculated job_id");
     Ok(job_id.into())
 }
@@ -66,13 +349,10 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
-    use crate::{
-        job::{JobRecord, JobStore},
-        rpc::ProverRpcContext,
-    };
+    use crate::{backend::MockBackend, rpc::ProverRpcContext};
     use guest_program::input::ProgramInput;
     use mojave_client::types::{ProofResponse, ProofResult, ProverData};
-    use tokio::sync::{Mutex, mpsc};
+    use mojave_task::Task;
 
     fn dummy_data() -> ProverData {
         ProverData {
@@ -81,32 +361,35 @@ mod tests {
         }
     }
 
-    async fn make_ctx(cap: usize) -> (ProverRpcContext, mpsc::Receiver<JobRecord>) {
-        let (tx, rx) = mpsc::channel::<JobRecord>(cap);
-        (
-            ProverRpcContext {
-                aligned_mode: false,
-                job_store: JobStore::default(),
-                sender: tx,
-                publisher: Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap()),
-                sent_ids: Mutex::new(std::collections::HashSet::new()),
-            },
-            rx,
+    async fn make_ctx(cap: usize) -> Arc<ProverRpcContext> {
+        let publisher = Arc::new(mojave_msgio::dummy::Dummy::new().await.unwrap());
+        ProverRpcContext::with_worker(
+            Arc::new(MockBackend),
+            publisher,
+            cap,
+            JobStore::default(),
+            Default::default(),
+            Task::spawn,
         )
     }
 
+    async fn job_state(ctx: &ProverRpcContext, job_id: &JobId) -> JobState {
+        get_job_state(ctx, job_id).await.unwrap()
+    }
+
     #[tokio::test]
     async fn enqueue_proof_input_enqueues_and_returns_job_id() {
-        let (ctx, mut rx) = make_ctx(8).await;
+        let ctx = make_ctx(8).await;
         let url = Url::parse("http://localhost:1234").unwrap();
 
-        let job_id = enqueue_proof_input(&ctx, dummy_data(), url.clone())
+        let job_id = enqueue_proof_input(&ctx, dummy_data(), url, None)
             .await
             .unwrap();
 
-        let rec = rx.recv().await.unwrap();
-        assert_eq!(rec.job_id, job_id);
-        assert_eq!(rec.sequencer_url, url);
+        assert_eq!(
+            job_state(&ctx, &job_id).await,
+            JobState::Queued { position: 0 }
+        );
 
         let mut list = ctx.job_store.get_pending_jobs().await;
         assert_eq!(list.pop().unwrap(), job_id);
@@ -114,20 +397,206 @@ mod tests {
 
     #[tokio::test]
     async fn enqueue_proof_input_rejects_duplicate() {
-        let (ctx, _rx) = make_ctx(8).await;
+        let ctx = make_ctx(8).await;
         let url = Url::parse("http://localhost:1234").unwrap();
 
-        let _enqueue = enqueue_proof_input(&ctx, dummy_data(), url.clone()).await;
-        let enqueue_duplicate = enqueue_proof_input(&ctx, dummy_data(), url).await;
+        let _enqueue = enqueue_proof_input(&ctx, dummy_data(), url.clone(), None).await;
+        let enqueue_duplicate = enqueue_proof_input(&ctx, dummy_data(), url, None).await;
 
         assert!(
             matches!(enqueue_duplicate.unwrap_err(), Error::BadParams(ref msg) if msg == "This batch already requested")
         );
     }
 
+    #[tokio::test]
+    async fn enqueue_proof_input_rolls_back_when_queue_is_full() {
+        let ctx = make_ctx(0).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+
+        let err = enqueue_proof_input(&ctx, dummy_data(), url, None)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Internal(ref msg) if msg.contains("queue is full")));
+        assert!(ctx.job_store.get_pending_jobs().await.is_empty());
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+    }
+
+    async fn accept_nonce(ctx: &ProverRpcContext, signer: &str, nonce: u64) -> Result<()> {
+        check_submission_nonce(ctx, Some(signer), Some(nonce))
+            .await?
+            .unwrap()
+            .commit()
+            .await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn check_submission_nonce_rejects_replay() {
+        let ctx = make_ctx(8).await;
+        let nonce = now_millis();
+
+        accept_nonce(&ctx, "alice", nonce).await.unwrap();
+        let replayed = accept_nonce(&ctx, "alice", nonce).await;
+        let older = accept_nonce(&ctx, "alice", nonce - 1).await;
+
+        assert!(
+            matches!(replayed.unwrap_err(), Error::BadParams(ref msg) if msg == "Nonce already used")
+        );
+        assert!(
+            matches!(older.unwrap_err(), Error::BadParams(ref msg) if msg == "Nonce already used")
+        );
+        accept_nonce(&ctx, "alice", nonce + 1).await.unwrap();
+
+        // Nonces are tracked per signer.
+        accept_nonce(&ctx, "bob", nonce).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn check_submission_nonce_rejects_stale_and_future() {
+        let ctx = make_ctx(8).await;
+        let stale = now_millis() - MAX_NONCE_AGE.as_millis() as u64 - 1_000;
+        let future = now_millis() + MAX_NONCE_SKEW.as_millis() as u64 + 60_000;
+
+        let err = accept_nonce(&ctx, "alice", stale).await.unwrap_err();
+        assert!(matches!(err, Error::BadParams(ref msg) if msg == "Stale nonce"));
+        let err = accept_nonce(&ctx, "alice", future).await.unwrap_err();
+        assert!(matches!(err, Error::BadParams(ref msg) if msg == "Nonce too far in the future"));
+        assert!(ctx.submission_nonces.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_submission_nonce_requires_nonce_only_when_signed() {
+        let ctx = make_ctx(8).await;
+
+        let err = check_submission_nonce(&ctx, Some("alice"), None)
+            .await
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, Error::BadParams(ref msg) if msg == "Signed proof input requires a nonce")
+        );
+        assert!(
+            check_submission_nonce(&ctx, None, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn uncommitted_nonce_can_be_reused() {
+        let ctx = make_ctx(8).await;
+        let nonce = now_millis();
+
+        let reservation = check_submission_nonce(&ctx, Some("alice"), Some(nonce))
+            .await
+            .unwrap();
+        // The submission failed, so the nonce is never committed.
+        drop(reservation);
+
+        accept_nonce(&ctx, "alice", nonce).await.unwrap();
+    }
+
+    #[test]
+    fn verify_proof_input_signature_round_trip() {
+        use mojave_signature::{SigningKey, types::Signer};
+        use std::str::FromStr;
+
+        // Anvil account 0.
+        let key = SigningKey::from_str(
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let data = dummy_data();
+        let signature = RequestSignature {
+            signature: key
+                .sign(&ProofInputMessage {
+                    prover_data: &data,
+                    sequencer_addr: url.as_str(),
+                    nonce: Some(7),
+                })
+                .unwrap(),
+            verifying_key: key.verifying_key(),
+        };
+
+        let signer = verify_proof_input_signature(&data, &url, Some(7), Some(&signature)).unwrap();
+        assert_eq!(
+            signer.as_deref(),
+            Some("f39fd6e51aad88f6f4ce6ab8827279cfffb92266")
+        );
+
+        // Any change to the signed fields invalidates the signature.
+        let err = verify_proof_input_signature(&data, &url, Some(8), Some(&signature)).unwrap_err();
+        assert!(matches!(err, Error::BadParams(ref msg) if msg == "Invalid proof input signature"));
+
+        let unsigned = verify_proof_input_signature(&data, &url, Some(7), None).unwrap();
+        assert!(unsigned.is_none());
+    }
+
+    #[tokio::test]
+    async fn resubmit_proof_input_is_idempotent() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+
+        let first = resubmit_proof_input(&ctx, dummy_data(), url.clone(), None)
+            .await
+            .unwrap();
+        let second = resubmit_proof_input(&ctx, dummy_data(), url, None)
+            .await
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(
+            job_state(&ctx, &first).await,
+            JobState::Queued { position: 0 }
+        );
+        assert_eq!(ctx.job_store.get_pending_jobs().await, vec![first]);
+    }
+
+    #[tokio::test]
+    async fn resubmit_proof_input_requeues_failed_job() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+
+        let job_id = enqueue_proof_input(&ctx, dummy_data(), url.clone(), Some("alice".into()))
+            .await
+            .unwrap();
+        cancel_job(&ctx, &job_id, "alice").await.unwrap();
+        ctx.job_store
+            .upsert_proof(
+                &job_id,
+                ProofResponse {
+                    job_id: job_id.clone(),
+                    batch_number: 0,
+                    result: ProofResult::Error("dummy".into()),
+                },
+            )
+            .await;
+
+        let resubmitted = resubmit_proof_input(&ctx, dummy_data(), url, None)
+            .await
+            .unwrap();
+
+        assert_eq!(resubmitted, job_id);
+        assert_eq!(
+            job_state(&ctx, &job_id).await,
+            JobState::Queued { position: 0 }
+        );
+        assert!(ctx.job_store.is_pending(&job_id).await);
+        assert!(ctx.job_store.get_proof_by_id(&job_id).await.is_none());
+    }
+
     #[tokio::test]
     async fn get_proof_returns_existing_or_err() {
-        let (ctx, _rx) = make_ctx(8).await;
+        let ctx = make_ctx(8).await;
         let job_id = JobId::from("job-1");
 
         let expected = ProofResponse {
@@ -147,6 +616,25 @@ mod tests {
         assert!(s.contains("no proof"));
     }
 
+    #[tokio::test]
+    async fn get_proof_reports_pending_job_as_not_ready() {
+        let ctx = make_ctx(8).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let mut data = dummy_data();
+        data.batch_number = 3;
+        let job_id = enqueue_proof_input(&ctx, data, url, None).await.unwrap();
+
+        let response = get_proof(&ctx, &job_id).await.unwrap();
+        assert_eq!(response.batch_number, 3);
+        assert!(matches!(
+            response.result,
+            ProofResult::NotReady { progress: None }
+        ));
+
+        let value = get_serialized_proof(&ctx, &job_id).await.unwrap();
+        assert_eq!(value, serde_json::to_value(&response).unwrap());
+    }
+
     #[tokio::test]
     async fn calculate_job_id_is_stable_for_same_input() {
         let input = ProgramInput::default();
@@ -154,4 +642,57 @@ mod tests {
         let b = super::calculate_job_id(&input).unwrap();
         assert_eq!(a, b);
     }
+
+    #[tokio::test]
+    async fn calculate_job_id_covers_the_whole_input() {
+        let input = ProgramInput::default();
+        let changed = ProgramInput {
+            elasticity_multiplier: input.elasticity_multiplier + 1,
+            ..ProgramInput::default()
+        };
+        assert_ne!(
+            super::calculate_job_id(&input).unwrap(),
+            super::calculate_job_id(&changed).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_job_interrupts_running_job() {
+        let ctx = make_ctx(4).await;
+        ctx.job_store.insert_job("a".into(), 1).await;
+        ctx.job_store
+            .set_submitter(&"a".into(), "alice".into())
+            .await;
+        let cancel = tokio_util::sync::CancellationToken::new();
+        *ctx.running_job.lock().await = Some(crate::rpc::context::RunningJob {
+            job_id: "a".into(),
+            started_at: 0,
+            cancel: cancel.clone(),
+        });
+
+        let state = cancel_job(&ctx, &"a".into(), "alice").await.unwrap();
+
+        assert_eq!(state, JobState::Cancelled);
+        assert!(cancel.is_cancelled());
+        // Reported as cancelled while the backend is still winding down.
+        assert_eq!(job_state(&ctx, &"a".into()).await, JobState::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn cancel_job_requires_the_submitter() {
+        let ctx = make_ctx(4).await;
+        let url = Url::parse("http://localhost:1234").unwrap();
+        let job_id = enqueue_proof_input(&ctx, dummy_data(), url, Some("alice".into()))
+            .await
+            .unwrap();
+
+        let err = cancel_job(&ctx, &job_id, "mallory").await.unwrap_err();
+        assert!(
+            matches!(err, Error::BadParams(ref msg) if msg == "Only the job's submitter can cancel it")
+        );
+        assert!(ctx.job_store.is_pending(&job_id).await);
+
+        let state = cancel_job(&ctx, &job_id, "alice").await.unwrap();
+        assert_eq!(state, JobState::Cancelled);
+    }
 }
@@ -0,0 +1,134 @@
+use crate::RpcErr;
+use serde_json::Value;
+use std::fmt;
+
+/// Carries an [`RpcErr`] through `anyhow` error chains.
+///
+/// `RpcErr` is defined upstream, so it cannot be converted into
+/// `anyhow::Error` directly. The wrapper renders the variant (e.g.
+/// `MethodNotFound("eth_foo")`) and can be recovered with
+/// `anyhow::Error::downcast_ref::<RpcError>()`.
+#[derive(Debug)]
+pub struct RpcError(pub RpcErr);
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<RpcErr> for RpcError {
+    fn from(value: RpcErr) -> Self {
+        Self(value)
+    }
+}
+
+/// Converts an [`RpcErr`] into an `anyhow::Error` without flattening it to a string.
+pub trait IntoAnyhow {
+    fn into_anyhow(self) -> anyhow::Error;
+}
+
+impl IntoAnyhow for RpcErr {
+    fn into_anyhow(self) -> anyhow::Error {
+        anyhow::Error::new(RpcError(self))
+    }
+}
+
+/// Lifts `Result<T, RpcErr>` into `anyhow::Result<T>`, so `.context(...)` can follow.
+pub trait RpcResultExt<T> {
+    fn into_anyhow(self) -> anyhow::Result<T>;
+}
+
+impl<T> RpcResultExt<T> for Result<T, RpcErr> {
+    fn into_anyhow(self) -> anyhow::Result<T> {
+        self.map_err(IntoAnyhow::into_anyhow)
+    }
+}
+
+/// An [`RpcErr`] plus the machine-readable `data` member of the JSON-RPC
+/// error object, e.g. which parameter failed validation.
+#[derive(Debug)]
+pub struct DetailedRpcErr {
+    pub error: RpcErr,
+    pub data: Value,
+}
+
+impl fmt::Display for DetailedRpcErr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} ({})", self.error, self.data)
+    }
+}
+
+impl std::error::Error for DetailedRpcErr {}
+
+/// Attaches `data` to an [`RpcErr`].
+pub trait WithData {
+    fn with_data(self, data: Value) -> DetailedRpcErr;
+}
+
+impl WithData for RpcErr {
+    fn with_data(self, data: Value) -> DetailedRpcErr {
+        DetailedRpcErr { error: self, data }
+    }
+}
+
+/// Error of a registry handler, with or without `data` for the error object.
+#[derive(Debug)]
+pub enum RpcHandlerError {
+    Plain(RpcErr),
+    Detailed(DetailedRpcErr),
+}
+
+impl RpcHandlerError {
+    /// The error without its `data`.
+    pub fn rpc_err(&self) -> &RpcErr {
+        match self {
+            Self::Plain(error) => error,
+            Self::Detailed(detailed) => &detailed.error,
+        }
+    }
+}
+
+impl fmt::Display for RpcHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Plain(error) => write!(f, "{error:?}"),
+            Self::Detailed(detailed) => detailed.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for RpcHandlerError {}
+
+impl From<RpcErr> for RpcHandlerError {
+    fn from(value: RpcErr) -> Self {
+        Self::Plain(value)
+    }
+}
+
+impl From<DetailedRpcErr> for RpcHandlerError {
+    fn from(value: DetailedRpcErr) -> Self {
+        Self::Detailed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn into_anyhow_keeps_variant_in_chain() {
+        let result: Result<(), RpcErr> = Err(RpcErr::MethodNotFound("eth_foo".into()));
+        let err = result.into_anyhow().context("calling node").unwrap_err();
+
+        assert_eq!(err.to_string(), "calling node");
+        let chain = format!("{err:#}");
+        assert!(chain.contains("MethodNotFound(\"eth_foo\")"), "{chain}");
+
+        let inner = err.downcast_ref::<RpcError>().expect("RpcError in chain");
+        assert!(matches!(inner.0, RpcErr::MethodNotFound(ref m) if m == "eth_foo"));
+    }
+}
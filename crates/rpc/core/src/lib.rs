@@ -1,10 +1,14 @@
+pub mod error;
 pub mod types;
 pub mod utils;
 
-pub use crate::types::RpcErrorResponse;
+pub use crate::{
+    error::{DetailedRpcErr, IntoAnyhow, RpcError, RpcHandlerError, RpcResultExt, WithData},
+    types::{RpcErrorObject, RpcErrorResponse},
+};
 pub use ethrex_rpc::utils::{RpcErr, RpcRequest, RpcRequestId, RpcSuccessResponse};
 
 pub mod prelude {
-    pub use crate::{types::*, utils::*};
+    pub use crate::{error::*, types::*, utils::*};
     pub use ethrex_rpc::utils::{RpcErr, RpcRequest, RpcRequestId};
 }
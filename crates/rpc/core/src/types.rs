@@ -1,9 +1,16 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
 use ethrex_rpc::RpcErrorMetadata;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::RpcRequestId;
+use crate::{
+    RpcErr, RpcRequestId,
+    error::{DetailedRpcErr, RpcHandlerError},
+};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+/// Method prefix before the first `_`, e.g. `eth` in `eth_chainId`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub enum Namespace {
     #[serde(rename = "debug")]
     Debug,
@@ -17,21 +24,153 @@ pub enum Namespace {
     TxPool,
     #[serde(rename = "web3")]
     Web3,
+    /// Any other prefix, e.g. for extensions such as `mojaveExt_*`.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl Namespace {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Namespace::Debug => "debug",
+            Namespace::Eth => "eth",
+            Namespace::Mojave => "moj",
+            Namespace::Net => "net",
+            Namespace::TxPool => "txpool",
+            Namespace::Web3 => "web3",
+            Namespace::Custom(namespace) => namespace,
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Namespace {
+    fn from(value: &str) -> Self {
+        match value {
+            "debug" => Namespace::Debug,
+            "eth" => Namespace::Eth,
+            "moj" => Namespace::Mojave,
+            "net" => Namespace::Net,
+            "txpool" => Namespace::TxPool,
+            "web3" => Namespace::Web3,
+            other => Namespace::Custom(other.to_owned()),
+        }
+    }
+}
+
+impl FromStr for Namespace {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
 }
 
 #[derive(Eq, PartialEq, Serialize, Deserialize)]
 pub enum MojaveRequestMethods {
+    #[serde(rename = "eth_blockNumber")]
+    BlockNumber,
+    #[serde(rename = "eth_getBlockByNumber")]
+    GetBlockByNumber,
     #[serde(rename = "moj_getPendingJobIds")]
     GetPendingJobIds,
     #[serde(rename = "moj_getProof")]
     GetProof,
+    #[serde(rename = "eth_getTransactionCount")]
+    GetTransactionCount,
+    #[serde(rename = "moj_resubmitProofInput")]
+    ResubmitProofInput,
     #[serde(rename = "moj_sendProofInput")]
     SendProofInput,
+    #[serde(rename = "eth_sendRawTransaction")]
+    SendRawTransaction,
 }
 
 #[derive(Serialize)]
 pub struct RpcErrorResponse {
     pub jsonrpc: String,
     pub id: Option<RpcRequestId>,
-    pub error: RpcErrorMetadata,
+    pub error: RpcErrorObject,
+}
+
+/// JSON-RPC error object. Unlike [`RpcErrorMetadata`], `data` can hold any
+/// JSON value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcErrorObject {
+    pub code: i32,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl From<RpcErrorMetadata> for RpcErrorObject {
+    fn from(value: RpcErrorMetadata) -> Self {
+        Self {
+            code: value.code,
+            message: value.message,
+            data: value.data.map(Value::String),
+        }
+    }
+}
+
+impl From<RpcErr> for RpcErrorObject {
+    fn from(value: RpcErr) -> Self {
+        RpcErrorMetadata::from(value).into()
+    }
+}
+
+impl From<DetailedRpcErr> for RpcErrorObject {
+    fn from(value: DetailedRpcErr) -> Self {
+        Self {
+            data: Some(value.data),
+            ..value.error.into()
+        }
+    }
+}
+
+impl From<RpcHandlerError> for RpcErrorObject {
+    fn from(value: RpcHandlerError) -> Self {
+        match value {
+            RpcHandlerError::Plain(error) => error.into(),
+            RpcHandlerError::Detailed(detailed) => detailed.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_round_trips_through_str() {
+        for namespace in ["debug", "eth", "moj", "net", "txpool", "web3", "mojaveExt"] {
+            assert_eq!(Namespace::from(namespace).to_string(), namespace);
+        }
+        assert_eq!(Namespace::from("moj"), Namespace::Mojave);
+        assert_eq!(
+            "mojaveExt".parse::<Namespace>(),
+            Ok(Namespace::Custom("mojaveExt".to_owned()))
+        );
+    }
+
+    #[test]
+    fn namespace_serializes_as_its_prefix() {
+        assert_eq!(
+            serde_json::to_value(Namespace::Mojave).unwrap(),
+            serde_json::json!("moj")
+        );
+        assert_eq!(
+            serde_json::to_value(Namespace::Custom("mojaveExt".to_owned())).unwrap(),
+            serde_json::json!("mojaveExt")
+        );
+        assert_eq!(
+            serde_json::from_value::<Namespace>(serde_json::json!("eth")).unwrap(),
+            Namespace::Eth
+        );
+    }
 }
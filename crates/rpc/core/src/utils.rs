@@ -1,10 +1,22 @@
 use crate::{
     RpcErr, RpcRequest, RpcRequestId, RpcSuccessResponse,
-    types::{Namespace, RpcErrorResponse},
+    types::{Namespace, RpcErrorObject, RpcErrorResponse},
 };
 use serde_json::Value;
 
-pub fn rpc_response(id: RpcRequestId, result: Result<Value, RpcErr>) -> Result<Value, RpcErr> {
+/// JSON-RPC 2.0 error code for a body that is not valid JSON.
+pub const PARSE_ERROR_CODE: i32 = -32700;
+
+/// JSON-RPC 2.0 error code for a body that is JSON but not a valid request.
+pub const INVALID_REQUEST_CODE: i32 = -32600;
+
+/// Shapes a handler result into a JSON-RPC response. Errors may be an
+/// [`RpcErr`] or, to fill the error object's `data`, a
+/// [`crate::DetailedRpcErr`] or [`crate::RpcHandlerError`].
+pub fn rpc_response<E>(id: RpcRequestId, result: Result<Value, E>) -> Result<Value, RpcErr>
+where
+    E: Into<RpcErrorObject>,
+{
     match result {
         Ok(value) => rpc_response_success(id, value),
         Err(e) => rpc_response_error(Some(id), e),
@@ -19,7 +31,10 @@ pub fn rpc_response_success(id: RpcRequestId, result: Value) -> Result<Value, Rp
     })?)
 }
 
-pub fn rpc_response_error(id: Option<RpcRequestId>, error: RpcErr) -> Result<Value, RpcErr> {
+pub fn rpc_response_error(
+    id: Option<RpcRequestId>,
+    error: impl Into<RpcErrorObject>,
+) -> Result<Value, RpcErr> {
     Ok(serde_json::to_value(RpcErrorResponse {
         jsonrpc: "2.0".to_string(),
         id,
@@ -27,26 +42,52 @@ pub fn rpc_response_error(id: Option<RpcRequestId>, error: RpcErr) -> Result<Val
     })?)
 }
 
+/// Builds a `-32700 Parse error` response. No id is echoed since the body
+/// could not be read.
+pub fn rpc_response_parse_error(message: impl Into<String>) -> Result<Value, RpcErr> {
+    Ok(serde_json::to_value(RpcErrorResponse {
+        jsonrpc: "2.0".to_string(),
+        id: None,
+        error: RpcErrorObject {
+            code: PARSE_ERROR_CODE,
+            data: None,
+            message: message.into(),
+        },
+    })?)
+}
+
+/// Builds a `-32600 Invalid Request` response. `id` is echoed when it could
+/// be read from the rejected request.
+pub fn rpc_response_invalid_request(
+    id: Option<RpcRequestId>,
+    message: impl Into<String>,
+) -> Result<Value, RpcErr> {
+    Ok(serde_json::to_value(RpcErrorResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        error: RpcErrorObject {
+            code: INVALID_REQUEST_CODE,
+            data: None,
+            message: message.into(),
+        },
+    })?)
+}
+
+/// Namespace of the request's method, i.e. its prefix before the first `_`.
+/// Prefixes that are not a known [`Namespace`] resolve to
+/// [`Namespace::Custom`].
 pub fn resolve_namespace(req: &RpcRequest) -> Result<Namespace, RpcErr> {
     let req_method = req.method.replace('\"', "");
-    let mut parts = req_method.split('_');
-    let Some(namespace) = parts.next() else {
-        return Err(RpcErr::MethodNotFound(req.method.clone()));
-    };
-    match namespace {
-        "debug" => Ok(Namespace::Debug),
-        "eth" => Ok(Namespace::Eth),
-        "moj" => Ok(Namespace::Mojave),
-        "net" => Ok(Namespace::Net),
-        "txpool" => Ok(Namespace::TxPool),
-        "web3" => Ok(Namespace::Web3),
-        _others => Err(RpcErr::MethodNotFound(req.method.clone())),
+    match req_method.split_once('_') {
+        Some((namespace, _)) if !namespace.is_empty() => Ok(namespace.into()),
+        _ => Err(RpcErr::MethodNotFound(req.method.clone())),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::WithData;
     use serde_json::json;
 
     #[test]
@@ -70,6 +111,74 @@ mod tests {
         assert!(obj.get("result").is_none());
     }
 
+    #[test]
+    fn detailed_error_serializes_data() {
+        let error = RpcErr::BadParams("invalid address".into())
+            .with_data(json!({ "field": "to", "reason": "not hex" }));
+        let out = rpc_response(RpcRequestId::Number(3), Err::<Value, _>(error)).unwrap();
+
+        assert_eq!(out["id"].as_i64(), Some(3));
+        assert_eq!(
+            out["error"]["data"],
+            json!({ "field": "to", "reason": "not hex" })
+        );
+        let plain = rpc_response_error(None, RpcErr::BadParams("invalid address".into())).unwrap();
+        assert_eq!(out["error"]["code"], plain["error"]["code"]);
+        assert_eq!(out["error"]["message"], plain["error"]["message"]);
+    }
+
+    #[test]
+    fn error_without_data_omits_field() {
+        let out = rpc_response_error(None, RpcErr::MethodNotFound("eth_foo".into())).unwrap();
+        assert!(out["error"].get("data").is_none());
+    }
+
+    #[test]
+    fn parse_error_uses_spec_code() {
+        let out = rpc_response_parse_error("Parse error").unwrap();
+        assert!(out["id"].is_null());
+        assert_eq!(out["error"]["code"].as_i64(), Some(PARSE_ERROR_CODE as i64));
+        assert_eq!(out["error"]["message"].as_str(), Some("Parse error"));
+    }
+
+    #[test]
+    fn invalid_request_uses_spec_code() {
+        let out = rpc_response_invalid_request(None, "batch too large").unwrap();
+        assert!(out["id"].is_null());
+        assert_eq!(
+            out["error"]["code"].as_i64(),
+            Some(INVALID_REQUEST_CODE as i64)
+        );
+        assert_eq!(out["error"]["message"].as_str(), Some("batch too large"));
+    }
+
+    fn request(method: &str) -> RpcRequest {
+        serde_json::from_value(json!({
+            "jsonrpc": "2.0", "id": 1, "method": method, "params": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_namespace_falls_back_to_custom_prefix() {
+        assert_eq!(
+            resolve_namespace(&request("eth_chainId")).unwrap(),
+            Namespace::Eth
+        );
+        assert_eq!(
+            resolve_namespace(&request("mojaveExt_status")).unwrap(),
+            Namespace::Custom("mojaveExt".to_owned())
+        );
+        assert!(matches!(
+            resolve_namespace(&request("nounderscore")),
+            Err(RpcErr::MethodNotFound(_))
+        ));
+        assert!(matches!(
+            resolve_namespace(&request("_leading")),
+            Err(RpcErr::MethodNotFound(_))
+        ));
+    }
+
     #[test]
     fn error_without_id_sets_null_id() {
         let out = rpc_response_error(None, RpcErr::BadParams("y".into())).unwrap();
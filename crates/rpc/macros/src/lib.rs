@@ -1,8 +1,13 @@
 #![doc = include_str!("../../../../docs/rpc/macros.md")]
 use proc_macro::TokenStream;
-use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
 use quote::{ToTokens, format_ident, quote};
-use syn::{FnArg, ItemFn, Lit, PatType, Type, parse_macro_input, parse_str};
+use syn::{
+    Expr, FnArg, ItemFn, Lit, PatType, Path, Token, Type, bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_str,
+    punctuated::Punctuated,
+};
 
 #[derive(Debug)]
 enum ParseError {
@@ -11,18 +16,18 @@ enum ParseError {
     InvalidFormat(String),
 }
 
-fn parse_attr_tokens_panic(ts: TokenStream2) -> (String, String) {
-    match parse_attr_tokens(ts) {
-        Ok(result) => result,
-        Err(ParseError::MissingNamespace) => {
-            panic!("#[rpc] requires namespace = \"..\"")
-        }
-        Err(ParseError::MissingMethod) => {
-            panic!("#[rpc] requires method = \"..\"")
-        }
-        Err(ParseError::InvalidFormat(msg)) => {
-            panic!("#[rpc] attribute format error: {msg}")
-        }
+/// Parses the attribute, reporting errors on the attribute tokens.
+fn parse_attr(ts: TokenStream2) -> syn::Result<(String, String)> {
+    let message = match parse_attr_tokens(ts.clone()) {
+        Ok(result) => return Ok(result),
+        Err(ParseError::MissingNamespace) => "#[rpc] requires namespace = \"..\"".to_owned(),
+        Err(ParseError::MissingMethod) => "#[rpc] requires method = \"..\"".to_owned(),
+        Err(ParseError::InvalidFormat(msg)) => format!("#[rpc] attribute format error: {msg}"),
+    };
+    if ts.is_empty() {
+        Err(syn::Error::new(Span::call_site(), message))
+    } else {
+        Err(syn::Error::new_spanned(ts, message))
     }
 }
 
@@ -91,94 +96,195 @@ where
     ))
 }
 
-fn extract_args_types(input: &ItemFn) -> (Type, Type) {
+/// Returns the context type and, for two-argument handlers, the params type.
+fn extract_args_types(input: &ItemFn) -> syn::Result<(Type, Option<Type>)> {
     let mut it = input.sig.inputs.iter();
 
-    let ctx_arg = it
-        .next()
-        .expect("RPC handler must take 2 arguments: (ctx, params)");
-    let params_arg = it
-        .next()
-        .expect("RPC handler must take 2 arguments: (ctx, params)");
-
-    if it.next().is_some() {
-        panic!("RPC handler must take exactly two arguments: (ctx, params)");
+    let ctx_arg = it.next().ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.sig,
+            "RPC handler must take 1 or 2 arguments: (ctx) or (ctx, params)",
+        )
+    })?;
+    let params_arg = it.next();
+
+    if let Some(extra) = it.next() {
+        return Err(syn::Error::new_spanned(
+            extra,
+            "RPC handler must take at most two arguments: (ctx, params)",
+        ));
     }
 
-    let extract_type = |arg: &FnArg| -> Type {
+    let extract_type = |arg: &FnArg| -> syn::Result<Type> {
         match arg {
-            FnArg::Typed(PatType { ty, .. }) => (**ty).clone(),
-            _ => panic!("unsupported argument type"),
+            FnArg::Typed(PatType { ty, .. }) => Ok((**ty).clone()),
+            _ => Err(syn::Error::new_spanned(arg, "unsupported argument type")),
         }
     };
 
-    let ctx_ty = extract_type(ctx_arg);
-    let params_ty = extract_type(params_arg);
+    let ctx_ty = extract_type(ctx_arg)?;
+    let params_ty = params_arg.map(extract_type).transpose()?;
 
-    (ctx_ty, params_ty)
+    Ok((ctx_ty, params_ty))
 }
 
 fn generate_params_parsing(params_type: &Type) -> proc_macro2::TokenStream {
     quote! {
-        let params: #params_type = {
-            match &req.params {
-                None => serde_json::from_value(serde_json::Value::Null)
-                    .map_err(|e| mojave_rpc_core::RpcErr::BadParams(format!("Invalid params: {}", e)))?,
-                Some(vec) => {
-                    match vec.len() {
-                        0 => serde_json::from_value(serde_json::Value::Null)
-                            .map_err(|e| mojave_rpc_core::RpcErr::BadParams(format!("Invalid params: {}", e)))?,
-                        1 => serde_json::from_value::<#params_type>(vec[0].clone())
-                            .map_err(|e| mojave_rpc_core::RpcErr::BadParams(format!("Invalid params: {}", e)))?,
-                        _ => {
-                            let val = serde_json::Value::Array(vec.clone());
-                            serde_json::from_value::<#params_type>(val)
-                                .map_err(|e| mojave_rpc_core::RpcErr::BadParams(format!("Invalid params: {}", e)))?
-                        }
-                    }
-                }
-            }
+        let invalid_params = |e: serde_json::Error| -> mojave_rpc_core::RpcErr {
+            mojave_rpc_core::RpcErr::BadParams(format!("Invalid params: {}", e))
         };
+        // The server hands a bare object over as a single element array, so it
+        // goes straight into the params type as well.
+        let params: #params_type = match req.params.as_deref() {
+            None | Some([]) => serde_json::from_value(serde_json::Value::Null),
+            Some([value]) => serde_json::from_value::<#params_type>(value.clone()),
+            Some(values) => {
+                serde_json::from_value::<#params_type>(serde_json::Value::Array(values.to_vec()))
+            }
+        }
+        .map_err(invalid_params)?;
     }
 }
 
-#[proc_macro_attribute]
-pub fn rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as ItemFn);
-    let (namespace, method) = parse_attr_tokens_panic(attr.into());
+/// Name of the hidden registrar alias keyed by the handler's own name, which
+/// lets `register_all!` find the registrar without knowing the namespace.
+fn handler_registrar_ident(fn_name: &proc_macro2::Ident) -> proc_macro2::Ident {
+    format_ident!("__rpc_register_{}", fn_name)
+}
+
+/// `moj`/`getProof` becomes `MOJ_GETPROOF_METHOD`.
+fn method_const_ident(namespace: &str, method: &str) -> proc_macro2::Ident {
+    format_ident!(
+        "{}_{}_METHOD",
+        namespace.to_uppercase(),
+        method.to_uppercase()
+    )
+}
 
+fn expand_rpc(namespace: &str, method: &str, input: &ItemFn) -> syn::Result<TokenStream2> {
     let fn_name = &input.sig.ident;
     let vis = &input.vis;
 
-    let (ctx_type, params_type) = extract_args_types(&input);
+    let (ctx_type, params_type) = extract_args_types(input)?;
 
     let register_fn = format_ident!("register_{}_{}", namespace, method);
     let full_method = format!("{namespace}_{method}");
+    let method_const = method_const_ident(namespace, method);
+    let handler_registrar = handler_registrar_ident(fn_name);
+
+    // Single-argument handlers never look at `req.params`. The handler's error
+    // type only needs to implement `Into<RpcHandlerError>`, as `RpcErr` and
+    // `DetailedRpcErr` do.
+    let call = match &params_type {
+        Some(params_type) => {
+            let params_parsing = generate_params_parsing(params_type);
+            quote! {
+                #params_parsing
+                #fn_name(ctx, params).await.map_err(::core::convert::Into::into)
+            }
+        }
+        None => quote! {
+            let _ = req;
+            #fn_name(ctx).await.map_err(::core::convert::Into::into)
+        },
+    };
 
-    let params_parsing = generate_params_parsing(&params_type);
-
-    let expanded = quote! {
+    Ok(quote! {
         #input
 
+        #[allow(dead_code)]
+        #vis const #method_const: &str = #full_method;
+
         #[allow(non_snake_case)]
         #vis fn #register_fn(registry: &mut mojave_rpc_server::RpcRegistry<#ctx_type>) {
-            registry.register_fn(#full_method, |req, ctx| {
+            registry.register_fn(#method_const, |req, ctx| {
                 Box::pin(async move {
-                    #params_parsing
-                    #fn_name(ctx, params).await
+                    #call
                 })
             });
         }
-    };
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #vis fn #handler_registrar(registry: &mut mojave_rpc_server::RpcRegistry<#ctx_type>) {
+            #register_fn(registry)
+        }
+    })
+}
+
+/// `registry, [handler, module::handler, ...]`
+struct RegisterAll {
+    registry: Expr,
+    handlers: Punctuated<Path, Token![,]>,
+}
+
+impl Parse for RegisterAll {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let registry = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        bracketed!(content in input);
+        let handlers = content.parse_terminated(Path::parse, Token![,])?;
+        // Allow a trailing comma after the list.
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self { registry, handlers })
+    }
+}
+
+fn expand_register_all(input: RegisterAll) -> TokenStream2 {
+    let registry = input.registry;
+    let calls = input.handlers.into_iter().map(|mut handler| {
+        if let Some(last) = handler.segments.last_mut() {
+            last.ident = handler_registrar_ident(&last.ident);
+        }
+        quote! { #handler(registry); }
+    });
+
+    quote! {
+        {
+            let registry: &mut mojave_rpc_server::RpcRegistry<_> = &mut #registry;
+            #(#calls)*
+        }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as ItemFn);
+    let expanded = parse_attr(attr.into())
+        .and_then(|(namespace, method)| expand_rpc(&namespace, &method, &input))
+        .unwrap_or_else(|error| {
+            // Keep the handler itself so the error isn't buried under
+            // follow-up "cannot find function" errors.
+            let error = error.to_compile_error();
+            quote! {
+                #input
+                #error
+            }
+        });
 
     TokenStream::from(expanded)
 }
 
+/// Registers every listed `#[rpc]` handler, referenced by the handler
+/// function's own path, instead of calling each `register_*` function by hand.
+///
+/// ```ignore
+/// register_all!(registry, [get_pending_job_ids, handlers::get_proof]);
+/// ```
+#[proc_macro]
+pub fn register_all(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as RegisterAll);
+    TokenStream::from(expand_register_all(input))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        ParseError, extract_args_types, generate_params_parsing, parse_attr_tokens,
-        parse_attr_tokens_panic,
+        ParseError, RegisterAll, expand_register_all, expand_rpc, extract_args_types,
+        generate_params_parsing, method_const_ident, parse_attr, parse_attr_tokens,
     };
     use proc_macro2::TokenStream as TokenStream2;
     use quote::{format_ident, quote};
@@ -187,7 +293,7 @@ mod tests {
     #[test]
     fn parse_attr_ok() {
         let ts: TokenStream2 = syn::parse_quote! { namespace = "moj", method = "getJobId" };
-        let (ns, m) = parse_attr_tokens_panic(ts);
+        let (ns, m) = parse_attr(ts).unwrap();
         assert_eq!(ns, "moj");
         assert_eq!(m, "getJobId");
     }
@@ -195,7 +301,7 @@ mod tests {
     #[test]
     fn parse_attr_different_order() {
         let ts: TokenStream2 = syn::parse_quote! { method = "submitJob", namespace = "worker" };
-        let (ns, m) = parse_attr_tokens_panic(ts);
+        let (ns, m) = parse_attr(ts).unwrap();
         assert_eq!(ns, "worker");
         assert_eq!(m, "submitJob");
     }
@@ -204,7 +310,7 @@ mod tests {
     fn parse_attr_with_extra_fields() {
         let ts: TokenStream2 =
             syn::parse_quote! { namespace = "test", method = "call", extra = "ignored" };
-        let (ns, m) = parse_attr_tokens_panic(ts);
+        let (ns, m) = parse_attr(ts).unwrap();
         assert_eq!(ns, "test");
         assert_eq!(m, "call");
     }
@@ -224,17 +330,24 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "#[rpc] requires namespace")]
-    fn parse_attr_panic_missing_namespace() {
-        let ts: TokenStream2 = syn::parse_quote! { method = "test" };
-        parse_attr_tokens_panic(ts);
+    fn parse_attr_error_missing_namespace() {
+        let ts: TokenStream2 = syn::parse_quote! { namepsace = "x", method = "test" };
+        let err = parse_attr(ts).unwrap_err();
+        assert!(err.to_string().contains("#[rpc] requires namespace"));
+        assert!(err.to_compile_error().to_string().contains("compile_error"));
     }
 
     #[test]
-    #[should_panic(expected = "#[rpc] requires method")]
-    fn parse_attr_panic_missing_method() {
+    fn parse_attr_error_missing_method() {
         let ts: TokenStream2 = syn::parse_quote! { namespace = "test" };
-        parse_attr_tokens_panic(ts);
+        let err = parse_attr(ts).unwrap_err();
+        assert!(err.to_string().contains("#[rpc] requires method"));
+    }
+
+    #[test]
+    fn parse_attr_error_empty() {
+        let err = parse_attr(TokenStream2::new()).unwrap_err();
+        assert!(err.to_string().contains("#[rpc] requires namespace"));
     }
 
     #[test]
@@ -248,7 +361,8 @@ mod tests {
             }
         };
 
-        let (ctx_type, params_type) = extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+        let params_type = params_type.unwrap();
 
         let expected_ctx: Type = parse_quote! { std::sync::Arc<TestContext> };
         let expected_params: Type = parse_quote! { String };
@@ -274,7 +388,8 @@ mod tests {
             }
         };
 
-        let (ctx_type, params_type) = extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+        let params_type = params_type.unwrap();
 
         let expected_ctx: Type = parse_quote! { Arc<Context> };
         let expected_params: Type = parse_quote! { () };
@@ -300,7 +415,8 @@ mod tests {
             }
         };
 
-        let (ctx_type, params_type) = extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+        let params_type = params_type.unwrap();
 
         let expected_ctx: Type = parse_quote! { Context };
         let expected_params: Type = parse_quote! { (String, u32) };
@@ -316,19 +432,39 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "RPC handler must take 2 arguments")]
-    fn extract_args_types_too_few_args() {
+    fn extract_args_types_ctx_only() {
         let input: ItemFn = parse_quote! {
             async fn test_handler(ctx: Context) -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
                 Ok(serde_json::Value::Null)
             }
         };
 
-        extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+
+        let expected_ctx: Type = parse_quote! { Context };
+        assert_eq!(
+            quote!(#ctx_type).to_string(),
+            quote!(#expected_ctx).to_string()
+        );
+        assert!(params_type.is_none());
+    }
+
+    #[test]
+    fn extract_args_types_no_args() {
+        let input: ItemFn = parse_quote! {
+            async fn test_handler() -> Result<serde_json::Value, mojave_rpc_core::RpcErr> {
+                Ok(serde_json::Value::Null)
+            }
+        };
+
+        let err = extract_args_types(&input).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("RPC handler must take 1 or 2 arguments")
+        );
     }
 
     #[test]
-    #[should_panic(expected = "RPC handler must take exactly two arguments")]
     fn extract_args_types_too_many_args() {
         let input: ItemFn = parse_quote! {
             async fn test_handler(
@@ -340,7 +476,11 @@ mod tests {
             }
         };
 
-        extract_args_types(&input);
+        let err = extract_args_types(&input).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("RPC handler must take at most two arguments")
+        );
     }
 
     #[test]
@@ -380,7 +520,7 @@ mod tests {
             ))
             .unwrap();
 
-            let (ns, m) = parse_attr_tokens_panic(ts);
+            let (ns, m) = parse_attr(ts).unwrap();
             assert_eq!(ns, namespace);
             assert_eq!(m, method);
         }
@@ -391,7 +531,7 @@ mod tests {
         let ts: TokenStream2 = syn::parse_quote! {
             namespace = "test-ns", method = "method_with_underscores"
         };
-        let (ns, m) = parse_attr_tokens_panic(ts);
+        let (ns, m) = parse_attr(ts).unwrap();
         assert_eq!(ns, "test-ns");
         assert_eq!(m, "method_with_underscores");
     }
@@ -407,7 +547,8 @@ mod tests {
             }
         };
 
-        let (ctx_type, params_type) = extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+        let params_type = params_type.unwrap();
 
         let expected_ctx: Type = parse_quote! { std::sync::Arc<dyn MyTrait + Send + Sync> };
         let expected_params: Type = parse_quote! { Vec<HashMap<String, serde_json::Value>> };
@@ -433,7 +574,8 @@ mod tests {
             }
         };
 
-        let (ctx_type, params_type) = extract_args_types(&input);
+        let (ctx_type, params_type) = extract_args_types(&input).unwrap();
+        let params_type = params_type.unwrap();
 
         let expected_ctx: Type = parse_quote! { Arc<Context<'a>> };
         let expected_params: Type = parse_quote! { &'a str };
@@ -463,7 +605,7 @@ mod tests {
             ))
             .unwrap();
 
-            let (ns, m) = parse_attr_tokens_panic(ts);
+            let (ns, m) = parse_attr(ts).unwrap();
             assert_eq!(ns, namespace);
             assert_eq!(m, method);
 
@@ -497,10 +639,9 @@ mod tests {
 
         let generated_str = generated.to_string();
 
-        assert!(generated_str.contains("None =>"));
-        assert!(generated_str.contains("0 =>"));
-        assert!(generated_str.contains("1 =>"));
-        assert!(generated_str.contains("_ =>"));
+        assert!(generated_str.contains("None | Some ([]) =>"));
+        assert!(generated_str.contains("Some ([value]) =>"));
+        assert!(generated_str.contains("Some (values) =>"));
         assert!(generated_str.contains("mojave_rpc_core :: RpcErr :: BadParams"));
     }
 
@@ -526,7 +667,135 @@ mod tests {
             })
             .expect("Should parse valid function signature");
 
-            let (_ctx_type, _params_type) = extract_args_types(&input);
+            let (_ctx_type, _params_type) = extract_args_types(&input).unwrap();
+        }
+    }
+
+    #[test]
+    fn expand_two_arg_handler_parses_params() {
+        let input: ItemFn = parse_quote! {
+            pub async fn echo(ctx: Ctx, params: String) -> Result<Value, RpcErr> {
+                Ok(serde_json::json!(params))
+            }
+        };
+
+        let expanded = expand_rpc("moj", "echo", &input).unwrap().to_string();
+
+        assert!(expanded.contains("fn register_moj_echo"));
+        assert!(expanded.contains("RpcRegistry < Ctx >"));
+        assert!(expanded.contains("const MOJ_ECHO_METHOD : & str = \"moj_echo\""));
+        assert!(expanded.contains("register_fn (MOJ_ECHO_METHOD"));
+        assert!(expanded.contains("let params : String"));
+        assert!(expanded.contains("echo (ctx , params) . await . map_err"));
+    }
+
+    #[test]
+    fn expand_one_arg_handler_ignores_params() {
+        let input: ItemFn = parse_quote! {
+            pub async fn ping(ctx: Ctx) -> Result<Value, RpcErr> {
+                Ok(serde_json::json!("pong"))
+            }
+        };
+
+        let expanded = expand_rpc("moj", "ping", &input).unwrap().to_string();
+
+        assert!(expanded.contains("fn register_moj_ping"));
+        assert!(expanded.contains("RpcRegistry < Ctx >"));
+        assert!(expanded.contains("const MOJ_PING_METHOD : & str = \"moj_ping\""));
+        assert!(!expanded.contains("req . params"));
+        assert!(expanded.contains("ping (ctx) . await . map_err"));
+    }
+
+    #[test]
+    fn expand_handler_with_custom_error_converts_into_rpc_err() {
+        let input: ItemFn = parse_quote! {
+            pub async fn prove(ctx: Ctx, params: u64) -> Result<Value, ProverError> {
+                Err(ProverError::NotReady(params))
+            }
+        };
+
+        let expanded = expand_rpc("moj", "prove", &input).unwrap();
+        // The registrar must still be a valid item with the conversion in place.
+        let file: syn::File = syn::parse2(expanded.clone()).unwrap();
+        assert_eq!(file.items.len(), 4);
+
+        let expanded = expanded.to_string();
+        assert!(expanded.contains("Result < Value , ProverError >"));
+        assert!(expanded.contains(
+            "prove (ctx , params) . await . map_err (:: core :: convert :: Into :: into)"
+        ));
+        assert!(expanded.contains("-> mojave_rpc_core :: RpcErr"));
+    }
+
+    #[test]
+    fn method_constant_holds_full_method_name() {
+        for (namespace, method, expected_const) in [
+            ("moj", "getProof", "MOJ_GETPROOF_METHOD"),
+            ("eth", "chainId", "ETH_CHAINID_METHOD"),
+            ("system", "health_check", "SYSTEM_HEALTH_CHECK_METHOD"),
+        ] {
+            assert_eq!(
+                method_const_ident(namespace, method).to_string(),
+                expected_const
+            );
+
+            let input: ItemFn = parse_quote! {
+                pub async fn handler(ctx: Ctx) -> Result<Value, RpcErr> {
+                    Ok(Value::Null)
+                }
+            };
+            let file: syn::File =
+                syn::parse2(expand_rpc(namespace, method, &input).unwrap()).unwrap();
+            let value = file
+                .items
+                .iter()
+                .find_map(|item| match item {
+                    syn::Item::Const(c) if c.ident == expected_const => Some(&*c.expr),
+                    _ => None,
+                })
+                .expect("method constant is generated");
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(value),
+                ..
+            }) = value
+            else {
+                panic!("method constant is a string literal");
+            };
+            assert_eq!(value.value(), format!("{namespace}_{method}"));
         }
     }
+
+    #[test]
+    fn expand_one_arg_handler_emits_handler_registrar() {
+        let input: ItemFn = parse_quote! {
+            pub async fn ping(ctx: Ctx) -> Result<Value, RpcErr> {
+                Ok(serde_json::json!("pong"))
+            }
+        };
+
+        let expanded = expand_rpc("moj", "ping", &input).unwrap().to_string();
+
+        assert!(expanded.contains("pub fn __rpc_register_ping"));
+        assert!(expanded.contains("register_moj_ping (registry)"));
+    }
+
+    #[test]
+    fn register_all_calls_each_handler_registrar() {
+        let input: RegisterAll = parse_quote! {
+            registry, [get_job_id, handlers::send_proof_input, crate::rpc::get_proof,]
+        };
+
+        let expanded = expand_register_all(input).to_string();
+
+        assert!(expanded.contains("& mut registry"));
+        assert!(expanded.contains("__rpc_register_get_job_id (registry) ;"));
+        assert!(expanded.contains("handlers :: __rpc_register_send_proof_input (registry) ;"));
+        assert!(expanded.contains("crate :: rpc :: __rpc_register_get_proof (registry) ;"));
+    }
+
+    #[test]
+    fn register_all_requires_bracketed_handlers() {
+        let result = syn::parse2::<RegisterAll>(quote! { registry, get_job_id });
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,99 @@
+use axum::{body::Body, http::Request};
+use mojave_rpc_core::RpcErr;
+use mojave_rpc_macros::{register_all, rpc};
+use mojave_rpc_server::{RpcRegistry, RpcService};
+use serde_json::{Value, json};
+use tower::ServiceExt;
+
+#[derive(Clone)]
+struct Ctx {
+    name: &'static str,
+}
+
+#[rpc(namespace = "test", method = "name")]
+async fn name(ctx: Ctx) -> Result<Value, RpcErr> {
+    Ok(json!(ctx.name))
+}
+
+#[rpc(namespace = "test", method = "echo")]
+async fn echo(_ctx: Ctx, params: String) -> Result<Value, RpcErr> {
+    Ok(json!(params))
+}
+
+#[derive(serde::Deserialize)]
+struct Transfer {
+    from: String,
+    to: String,
+}
+
+#[rpc(namespace = "test", method = "transfer")]
+async fn transfer(_ctx: Ctx, params: Transfer) -> Result<Value, RpcErr> {
+    Ok(json!(format!("{}->{}", params.from, params.to)))
+}
+
+mod math {
+    use super::*;
+
+    #[rpc(namespace = "test", method = "add")]
+    pub async fn add(_ctx: Ctx, params: (u64, u64)) -> Result<Value, RpcErr> {
+        Ok(json!(params.0 + params.1))
+    }
+}
+
+async fn call(registry: RpcRegistry<Ctx>, method: &str, params: Value) -> Value {
+    let router = RpcService::new(Ctx { name: "mojave" }, registry).router();
+    let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+    let request = Request::post("/")
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn register_all_registers_every_listed_handler() {
+    let mut registry = RpcRegistry::new();
+    register_all!(registry, [name, echo, math::add]);
+
+    let response = call(registry.clone(), TEST_NAME_METHOD, json!([])).await;
+    assert_eq!(response["result"], json!("mojave"));
+
+    let response = call(registry.clone(), TEST_ECHO_METHOD, json!(["hello"])).await;
+    assert_eq!(response["result"], json!("hello"));
+
+    let response = call(registry, math::TEST_ADD_METHOD, json!([2, 3])).await;
+    assert_eq!(response["result"], json!(5));
+}
+
+#[tokio::test]
+async fn struct_params_deserialize_from_single_object() {
+    let mut registry = RpcRegistry::new();
+    register_all!(registry, [transfer]);
+
+    let response = call(
+        registry,
+        TEST_TRANSFER_METHOD,
+        json!([{"from": "alice", "to": "bob"}]),
+    )
+    .await;
+    assert_eq!(response["result"], json!("alice->bob"));
+}
+
+#[tokio::test]
+async fn struct_params_deserialize_from_bare_object() {
+    let mut registry = RpcRegistry::new();
+    register_all!(registry, [transfer]);
+
+    let response = call(
+        registry,
+        TEST_TRANSFER_METHOD,
+        json!({"from": "alice", "to": "bob"}),
+    )
+    .await;
+    assert_eq!(response["result"], json!("alice->bob"));
+}
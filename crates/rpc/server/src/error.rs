@@ -0,0 +1,5 @@
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Method {0} is already registered")]
+    DuplicateMethod(String),
+}
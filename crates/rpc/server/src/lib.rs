@@ -1,28 +1,73 @@
 #![doc = include_str!("../../../../docs/rpc/server.md")]
-use std::{collections::HashMap, future::Future, net::SocketAddr, pin::Pin, sync::Arc};
+mod error;
 
-use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+pub use error::RegistryError;
+
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    future::Future,
+    net::SocketAddr,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use axum::{
+    Extension, Json, Router,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
 use ethrex_rpc::RpcRequestWrapper;
+use futures::FutureExt;
 use mojave_rpc_core::{
-    RpcErr, RpcRequest,
+    RpcErr, RpcHandlerError, RpcRequest, RpcRequestId,
     types::Namespace,
-    utils::{resolve_namespace, rpc_response, rpc_response_error},
+    utils::{
+        resolve_namespace, rpc_response, rpc_response_invalid_request, rpc_response_parse_error,
+    },
 };
 use serde_json::Value;
-use tower_http::cors::CorsLayer;
+use tokio::sync::Semaphore;
+use tower_http::{compression::CompressionLayer, cors::CorsLayer};
 use tracing::info;
 
+/// Method served by [`RpcRegistry::with_rpc_methods`].
+pub const RPC_METHODS_METHOD: &str = "rpc_methods";
+
+/// Default cap on the number of requests in a single batch.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 100;
+
 pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
-pub type RpcResult = Result<Value, RpcErr>;
+/// Result of a handler. Errors convert from [`RpcErr`] and, to fill the error
+/// object's `data`, from [`mojave_rpc_core::DetailedRpcErr`].
+pub type RpcResult = Result<Value, RpcHandlerError>;
+
+/// Builds a request-scoped context from the request, its headers and the peer address.
+pub type ContextFn<C> =
+    Arc<dyn Fn(&RpcRequest, &HeaderMap, SocketAddr) -> C + Send + Sync + 'static>;
 
 type DynHandler<C> =
     Arc<dyn for<'a> Fn(&'a RpcRequest, C) -> BoxFuture<'a, RpcResult> + Send + Sync + 'static>;
 
+/// Maps method names to handlers.
+///
+/// Lookups borrow the request's method as `&str`, so dispatch neither
+/// allocates nor needs interned names; keys are stored as `Box<str>` since they
+/// never change after registration.
 #[derive(Clone)]
 pub struct RpcRegistry<C> {
-    handlers: HashMap<String, DynHandler<C>>,
+    handlers: HashMap<Box<str>, DynHandler<C>>,
     fallbacks: HashMap<Namespace, DynHandler<C>>,
+    denied: HashSet<Box<str>>,
+    slow_threshold: Option<Duration>,
+    default_timeout: Option<Duration>,
+    method_timeouts: HashMap<Box<str>, Duration>,
+    rpc_methods: bool,
 }
 
 impl<C> Default for RpcRegistry<C> {
@@ -30,6 +75,11 @@ impl<C> Default for RpcRegistry<C> {
         Self {
             handlers: HashMap::new(),
             fallbacks: HashMap::new(),
+            denied: HashSet::new(),
+            slow_threshold: None,
+            default_timeout: None,
+            method_timeouts: HashMap::new(),
+            rpc_methods: false,
         }
     }
 }
@@ -39,21 +89,115 @@ impl<C: Clone + Send + Sync + 'static> RpcRegistry<C> {
         Self::default()
     }
 
+    /// Creates a registry sized for `methods` handlers, so registering them
+    /// doesn't rehash the method table as it grows.
+    pub fn with_capacity(methods: usize) -> Self {
+        Self {
+            handlers: HashMap::with_capacity(methods),
+            ..Self::default()
+        }
+    }
+
+    /// Logs requests that take longer than `threshold` at `warn`, independent
+    /// of whether debug logging is enabled.
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// Rejects `methods` with a `-32601` method not found error before they
+    /// reach any handler or fallback, e.g. to keep a public node from
+    /// accepting transactions.
+    pub fn deny_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.denied
+            .extend(methods.into_iter().map(|method| method.as_ref().into()));
+        self
+    }
+
+    /// Fails any request whose handler runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the [`Self::with_timeout`] default for `method`.
+    pub fn with_method_timeout(mut self, method: &str, timeout: Duration) -> Self {
+        self.method_timeouts.insert(method.into(), timeout);
+        self
+    }
+
+    /// Serves [`RPC_METHODS_METHOD`], which returns [`Self::registered_methods`]
+    /// as of the time of the call.
+    pub fn with_rpc_methods(mut self) -> Self {
+        self.rpc_methods = true;
+        self
+    }
+
+    /// Sorted names of the registered methods. Namespaces served by a fallback
+    /// are listed as `<namespace>_*`.
+    pub fn registered_methods(&self) -> Vec<String> {
+        let mut methods: Vec<String> = self
+            .handlers
+            .keys()
+            .map(|method| method.to_string())
+            .chain(self.fallbacks.keys().map(|ns| format!("{ns}_*")))
+            .chain(self.rpc_methods.then(|| RPC_METHODS_METHOD.to_owned()))
+            .collect();
+        methods.sort_unstable();
+        methods.dedup();
+        methods
+    }
+
+    fn timeout_for(&self, method: &str) -> Option<Duration> {
+        self.method_timeouts
+            .get(method)
+            .copied()
+            .or(self.default_timeout)
+    }
+
+    fn is_slow(&self, duration: Duration) -> bool {
+        self.slow_threshold
+            .is_some_and(|threshold| duration > threshold)
+    }
+
+    /// Registers `f` for `method`, replacing (with a warning) any handler
+    /// already registered for it. Use [`Self::try_register_fn`] to reject
+    /// duplicates instead.
     pub fn register_fn<F>(&mut self, method: &str, f: F) -> &mut Self
     where
         F: for<'a> Fn(&'a RpcRequest, C) -> BoxFuture<'a, RpcResult> + Send + Sync + 'static,
     {
         let func: DynHandler<C> = Arc::new(move |req, ctx: C| f(req, ctx));
-        self.handlers.insert(method.to_string(), func);
+        if self.handlers.insert(method.into(), func).is_some() {
+            tracing::warn!(%method, "RPC method registered twice, replacing the previous handler");
+        }
         self
     }
 
-    pub fn register_fallback<F>(&mut self, ns: Namespace, f: F) -> &mut Self
+    /// Like [`Self::register_fn`], but fails if `method` is already registered.
+    pub fn try_register_fn<F>(&mut self, method: &str, f: F) -> Result<&mut Self, RegistryError>
+    where
+        F: for<'a> Fn(&'a RpcRequest, C) -> BoxFuture<'a, RpcResult> + Send + Sync + 'static,
+    {
+        if self.handlers.contains_key(method) {
+            return Err(RegistryError::DuplicateMethod(method.to_owned()));
+        }
+        Ok(self.register_fn(method, f))
+    }
+
+    /// Routes every method in `ns` without a dedicated handler to `f`. Any
+    /// prefix works, e.g. `register_fallback("mojaveExt", f)` for
+    /// `mojaveExt_*`.
+    pub fn register_fallback<F>(&mut self, ns: impl Into<Namespace>, f: F) -> &mut Self
     where
         F: for<'a> Fn(&'a RpcRequest, C) -> BoxFuture<'a, RpcResult> + Send + Sync + 'static,
     {
         let func: DynHandler<C> = Arc::new(move |req, ctx: C| f(req, ctx));
-        self.fallbacks.insert(ns, func);
+        self.fallbacks.insert(ns.into(), func);
         self
     }
 
@@ -65,7 +209,7 @@ impl<C: Clone + Send + Sync + 'static> RpcRegistry<C> {
         self
     }
 
-    pub fn with_fallback<F>(mut self, ns: Namespace, f: F) -> Self
+    pub fn with_fallback<F>(mut self, ns: impl Into<Namespace>, f: F) -> Self
     where
         F: for<'a> Fn(&'a RpcRequest, C) -> BoxFuture<'a, RpcResult> + Send + Sync + 'static,
     {
@@ -77,22 +221,34 @@ impl<C: Clone + Send + Sync + 'static> RpcRegistry<C> {
         tracing::debug!(method = %req.method, id = ?req.id, "Dispatching RPC request");
 
         let start = std::time::Instant::now();
-        let result = if let Some(handler) = self.handlers.get(&req.method) {
-            handler(req, ctx).await
-        } else {
-            match resolve_namespace(req) {
-                Ok(ns) => {
-                    if let Some(fallback) = self.fallbacks.get(&ns) {
-                        fallback(req, ctx).await
-                    } else {
-                        Err(RpcErr::MethodNotFound(req.method.clone()))
-                    }
+        // A panicking handler only fails its own request.
+        let handler = AssertUnwindSafe(self.route(req, ctx)).catch_unwind();
+        let outcome = match self.timeout_for(&req.method) {
+            Some(timeout) => match tokio::time::timeout(timeout, handler).await {
+                Ok(outcome) => outcome,
+                Err(_elapsed) => {
+                    tracing::warn!(method = %req.method, timeout_ms = timeout.as_millis(), "RPC handler timed out");
+                    Ok(Err(RpcErr::Internal(format!(
+                        "handler timed out after {} ms",
+                        timeout.as_millis()
+                    ))
+                    .into()))
                 }
-                Err(err) => Err(err),
+            },
+            None => handler.await,
+        };
+        let result = match outcome {
+            Ok(result) => result,
+            Err(panic) => {
+                tracing::error!(method = %req.method, panic = %panic_message(&*panic), "RPC handler panicked");
+                Err(RpcErr::Internal("handler panicked".to_string()).into())
             }
         };
 
         let duration = start.elapsed();
+        if self.is_slow(duration) {
+            tracing::warn!(method = %req.method, id = ?req.id, duration_ms = duration.as_millis(), "Slow RPC request");
+        }
         match &result {
             Ok(_) => {
                 tracing::debug!(method = %req.method, duration_ms = duration.as_millis(), "RPC request completed")
@@ -104,39 +260,140 @@ impl<C: Clone + Send + Sync + 'static> RpcRegistry<C> {
 
         result
     }
+
+    async fn route(&self, req: &RpcRequest, ctx: C) -> RpcResult {
+        if self.denied.contains(req.method.as_str()) {
+            return Err(
+                RpcErr::MethodNotFound(format!("{} (disabled on this node)", req.method)).into(),
+            );
+        }
+        if let Some(handler) = self.handlers.get(req.method.as_str()) {
+            return handler(req, ctx).await;
+        }
+        if self.rpc_methods && req.method == RPC_METHODS_METHOD {
+            return serde_json::to_value(self.registered_methods())
+                .map_err(|e| RpcErr::Internal(e.to_string()).into());
+        }
+        let ns = resolve_namespace(req)?;
+        match self.fallbacks.get(&ns) {
+            Some(fallback) => fallback(req, ctx).await,
+            None => Err(RpcErr::MethodNotFound(req.method.clone()).into()),
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// State reported by the `GET /ready` route of [`RpcService::with_health_routes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    /// Up, but still catching up and not worth routing traffic to.
+    Syncing,
+}
+
+impl Readiness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Readiness::Ready => "ready",
+            Readiness::Syncing => "syncing",
+        }
+    }
+}
+
+/// Where handlers get their context from.
+#[derive(Clone)]
+enum ContextSource<C> {
+    /// A single context cloned into every request.
+    Shared(C),
+    /// A context derived per request, e.g. from auth headers or the peer address.
+    PerRequest(ContextFn<C>),
+}
+
+impl<C: Clone> ContextSource<C> {
+    fn resolve(&self, req: &RpcRequest, headers: &HeaderMap, peer: SocketAddr) -> C {
+        match self {
+            Self::Shared(context) => context.clone(),
+            Self::PerRequest(f) => f(req, headers, peer),
+        }
+    }
 }
 
 /// Service that binds a context and registry into an Axum router.
 ///
 /// The router exposes a single POST `/` endpoint that accepts JSON-RPC 2.0
-/// single or batch requests. Attach your own layers (CORS, limits, tracing)
-/// on the returned `Router`.
+/// single or batch requests, plus `GET /health` and `GET /ready` when
+/// [`Self::with_health_routes`] is used. Attach your own layers (CORS,
+/// limits, tracing) on the returned `Router`.
 #[derive(Clone)]
 pub struct RpcService<C> {
-    context: C,
+    context: ContextSource<C>,
     registry: RpcRegistry<C>,
-    router: Router,
+    max_batch_size: usize,
+    /// Handlers allowed to run at once within a batch; `0` means no limit.
+    batch_concurrency: usize,
+    /// Gets the service itself as state in [`Self::router`], once every
+    /// setting is in place.
+    router: Router<RpcService<C>>,
 }
 
 impl<C: Clone + Send + Sync + 'static> RpcService<C> {
     pub fn new(context: C, registry: RpcRegistry<C>) -> Self {
-        let this = Self {
+        Self::from_source(ContextSource::Shared(context), registry)
+    }
+
+    /// Creates a service that derives the handler context for every request.
+    ///
+    /// The peer address is only known when the router is served with
+    /// `into_make_service_with_connect_info::<SocketAddr>()` (as [`Self::serve`]
+    /// does); otherwise `0.0.0.0:0` is passed.
+    pub fn with_context_fn<F>(registry: RpcRegistry<C>, f: F) -> Self
+    where
+        F: Fn(&RpcRequest, &HeaderMap, SocketAddr) -> C + Send + Sync + 'static,
+    {
+        Self::from_source(ContextSource::PerRequest(Arc::new(f)), registry)
+    }
+
+    fn from_source(context: ContextSource<C>, registry: RpcRegistry<C>) -> Self {
+        Self {
             context,
             registry,
-            router: Router::new(),
-        };
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            batch_concurrency: 0,
+            router: Router::new().route("/", post(handle_http::<C>)),
+        }
+    }
 
-        let router = Router::new()
-            .route("/", post(handle::<C>))
-            .with_state(this.clone());
+    /// Build an Axum router mounted at `/` with JSON-RPC 2.0 handler.
+    #[inline]
+    pub fn router(mut self) -> Router {
+        let router = std::mem::replace(&mut self.router, Router::new());
+        router.with_state(self)
+    }
 
-        Self { router, ..this }
+    /// Rejects batches with more than `max` requests with a single `-32600`
+    /// error before any of them is dispatched. Defaults to
+    /// [`DEFAULT_MAX_BATCH_SIZE`].
+    #[inline]
+    pub fn with_max_batch_size(mut self, max: usize) -> Self {
+        self.max_batch_size = max;
+        self
     }
 
-    /// Build an Axum router mounted at `/` with JSON-RPC 2.0 handler.
+    /// Runs at most `limit` handlers of a batch at the same time. Responses
+    /// still come back in request order. Unlimited by default.
     #[inline]
-    pub fn router(self) -> Router {
-        self.router
+    pub fn with_batch_concurrency(mut self, limit: usize) -> Self {
+        self.batch_concurrency = limit.max(1);
+        self
     }
 
     #[inline]
@@ -150,47 +407,147 @@ impl<C: Clone + Send + Sync + 'static> RpcService<C> {
         self.with_cors(CorsLayer::permissive())
     }
 
+    /// Serves `GET /health`, which always answers `200 {"status":"ok"}`, and
+    /// `GET /ready`, which answers `200` when `readiness` reports
+    /// [`Readiness::Ready`] and `503` otherwise, e.g. `{"status":"syncing"}`.
+    /// Neither goes through JSON-RPC. Layers added earlier do not apply to
+    /// these routes.
+    pub fn with_health_routes<F>(mut self, readiness: F) -> Self
+    where
+        F: Fn() -> Readiness + Send + Sync + 'static,
+    {
+        let readiness = Arc::new(readiness);
+        self.router = self
+            .router
+            .route(
+                "/health",
+                get(|| async { Json(serde_json::json!({ "status": "ok" })) }),
+            )
+            .route(
+                "/ready",
+                get(move || async move {
+                    let readiness = readiness();
+                    let status = match readiness {
+                        Readiness::Ready => StatusCode::OK,
+                        Readiness::Syncing => StatusCode::SERVICE_UNAVAILABLE,
+                    };
+                    (
+                        status,
+                        Json(serde_json::json!({ "status": readiness.as_str() })),
+                    )
+                }),
+            );
+        self
+    }
+
+    /// Compress responses with gzip or brotli when the client's
+    /// `Accept-Encoding` allows it.
+    #[inline]
+    pub fn with_compression(mut self) -> Self {
+        self.router = self.router.layer(CompressionLayer::new());
+        self
+    }
+
     pub async fn serve(self, addr: SocketAddr) -> Result<(), RpcErr> {
         let router = self.router();
         let listener = tokio::net::TcpListener::bind(addr)
             .await
             .map_err(|e| RpcErr::Internal(e.to_string()))?;
-        let server = axum::serve(listener, router)
-            .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
-            .into_future();
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
+        .into_future();
         info!("Starting HTTP server at {addr}");
         server.await.map_err(|e| RpcErr::Internal(e.to_string()))
     }
 }
 
+/// Axum entry point. A body made up only of notifications is answered with
+/// `204 No Content`.
+async fn handle_http<C: Clone + Send + Sync + 'static>(
+    state: State<RpcService<C>>,
+    headers: HeaderMap,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+    body: String,
+) -> Response {
+    match handle(state, headers, peer, body).await {
+        Ok(Some(res)) => res.into_response(),
+        Ok(None) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Parses and dispatches a request body. Returns `None` when there is nothing
+/// to send back, i.e. every request in the body was a notification.
 async fn handle<C: Clone + Send + Sync + 'static>(
     State(service): State<RpcService<C>>,
+    headers: HeaderMap,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
     body: String,
-) -> core::result::Result<Json<Value>, (StatusCode, Json<Value>)> {
-    let wrapper = match serde_json::from_str::<RpcRequestWrapper>(&body) {
+) -> core::result::Result<Option<Json<Value>>, (StatusCode, Json<Value>)> {
+    let peer = peer
+        .map(|Extension(ConnectInfo(addr))| addr)
+        .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 0)));
+
+    let Ok(mut value) = serde_json::from_str::<Value>(&body) else {
+        let error_response = rpc_response_parse_error("Parse error")
+            .unwrap_or_else(|_| serde_json::json!({"error": "Parse error"}));
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    };
+    // Read before notifications get their placeholder id.
+    let id = value
+        .get("id")
+        .and_then(|id| serde_json::from_value::<RpcRequestId>(id.clone()).ok());
+    // Reject an oversized batch before deserializing any of its requests.
+    if let Value::Array(requests) = &value
+        && requests.len() > service.max_batch_size
+    {
+        let error_response = rpc_response_invalid_request(
+            None,
+            format!(
+                "batch too large: {} requests, max {}",
+                requests.len(),
+                service.max_batch_size
+            ),
+        )
+        .unwrap_or_else(|_| serde_json::json!({"error": "Invalid request"}));
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+    let notifications = mark_notifications(&mut value);
+    wrap_object_params(&mut value);
+    let wrapper = match serde_json::from_value::<RpcRequestWrapper>(value) {
         Ok(wrapper) => wrapper,
-        Err(_) => {
-            let error_response =
-                rpc_response_error(None, RpcErr::BadParams("Invalid JSON".to_string()))
-                    .unwrap_or_else(|_| serde_json::json!({"error": "Parse error"}));
+        Err(e) => {
+            let error_response = rpc_response_invalid_request(id, format!("Invalid request: {e}"))
+                .unwrap_or_else(|_| serde_json::json!({"error": "Invalid request"}));
             return Err((StatusCode::BAD_REQUEST, Json(error_response)));
         }
     };
 
     let res = match wrapper {
         RpcRequestWrapper::Single(request) => {
-            let res = service
-                .registry
-                .dispatch(&request, service.context.clone())
-                .await;
+            let context = service.context.resolve(&request, &headers, peer);
+            let res = service.registry.dispatch(&request, context).await;
+            if notifications.first().copied().unwrap_or(false) {
+                return Ok(None);
+            }
             rpc_response(request.id, res)
                 .unwrap_or_else(|_| serde_json::json!({"error": "Response serialization failed"}))
         }
         RpcRequestWrapper::Multiple(requests) => {
-            let responses: Vec<_> = futures::future::join_all(requests.into_iter().map(|req| {
+            let limit = service.batch_concurrency;
+            let semaphore = (limit > 0).then(|| Semaphore::new(limit));
+            let responses = futures::future::join_all(requests.into_iter().map(|req| {
                 let registry = &service.registry;
-                let context = service.context.clone();
+                let semaphore = semaphore.as_ref();
+                let context = service.context.resolve(&req, &headers, peer);
                 async move {
+                    let _permit = match semaphore {
+                        Some(semaphore) => semaphore.acquire().await.ok(),
+                        None => None,
+                    };
                     let res = registry.dispatch(&req, context).await;
                     rpc_response(req.id, res).unwrap_or_else(
                         |_| serde_json::json!({"error": "Response serialization failed"}),
@@ -198,17 +555,65 @@ async fn handle<C: Clone + Send + Sync + 'static>(
                 }
             }))
             .await;
+            let responses: Vec<_> = responses
+                .into_iter()
+                .zip(notifications.iter().chain(std::iter::repeat(&false)))
+                .filter(|(_, is_notification)| !**is_notification)
+                .map(|(res, _)| res)
+                .collect();
+            if responses.is_empty() {
+                return Ok(None);
+            }
             serde_json::to_value(responses)
                 .unwrap_or_else(|_| serde_json::json!({"error": "Batch serialization failed"}))
         }
     };
 
-    Ok(Json(res))
+    Ok(Some(Json(res)))
+}
+
+/// Flags every request object in `value` that has no `id` member, i.e. a
+/// JSON-RPC notification, and gives it a placeholder id so it still
+/// deserializes into an [`RpcRequest`]. Flags follow the order of the batch.
+fn mark_notifications(value: &mut Value) -> Vec<bool> {
+    fn mark(request: &mut Value) -> bool {
+        match request.as_object_mut() {
+            Some(object) if !object.contains_key("id") => {
+                object.insert("id".to_owned(), Value::from(0));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    match value {
+        Value::Array(requests) => requests.iter_mut().map(mark).collect(),
+        request => vec![mark(request)],
+    }
+}
+
+/// Turns by-name params, `"params": {..}`, into a single element array, the
+/// only shape an [`RpcRequest`] holds. `#[rpc]` handlers deserialize that
+/// element into their params type, so they accept both shapes.
+fn wrap_object_params(value: &mut Value) {
+    fn wrap(request: &mut Value) {
+        if let Some(params) = request.get_mut("params")
+            && params.is_object()
+        {
+            *params = Value::Array(vec![params.take()]);
+        }
+    }
+
+    match value {
+        Value::Array(requests) => requests.iter_mut().for_each(wrap),
+        request => wrap(request),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn dispatch_hits_registered_handler() {
@@ -223,6 +628,136 @@ mod tests {
         assert_eq!(out, serde_json::json!("0x1"));
     }
 
+    fn noop_registry(methods: usize) -> RpcRegistry<()> {
+        let mut reg = RpcRegistry::with_capacity(methods);
+        for i in 0..methods {
+            reg.register_fn(&format!("moj_method{i}"), |_req, _ctx| {
+                Box::pin(async { Ok(Value::Null) })
+            });
+        }
+        reg
+    }
+
+    #[test]
+    fn with_capacity_avoids_rehashing_while_registering() {
+        let reg: RpcRegistry<()> = RpcRegistry::with_capacity(500);
+        let capacity = reg.handlers.capacity();
+        assert!(capacity >= 500);
+
+        let reg = noop_registry(500);
+        assert_eq!(reg.handlers.len(), 500);
+        assert_eq!(reg.handlers.capacity(), capacity);
+    }
+
+    /// Lookup micro-benchmark; run with
+    /// `cargo test -p mojave-rpc-server --release -- --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore = "benchmark"]
+    async fn bench_route_lookup() {
+        const LOOKUPS: u32 = 1_000_000;
+        let reg = noop_registry(500);
+        let req: RpcRequest = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","id":1,"method":"moj_method250","params":[]}"#,
+        )
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        for _ in 0..LOOKUPS {
+            reg.route(std::hint::black_box(&req), ()).await.unwrap();
+        }
+        println!("route lookup: {:?}/call", started.elapsed() / LOOKUPS);
+    }
+
+    #[test]
+    fn try_register_fn_rejects_duplicate_method() {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.try_register_fn("moj_getProof", |_req, _ctx| {
+            Box::pin(async { Ok(Value::Null) })
+        })
+        .unwrap();
+
+        let err = reg
+            .try_register_fn("moj_getProof", |_req, _ctx| {
+                Box::pin(async { Ok(Value::Bool(true)) })
+            })
+            .err()
+            .unwrap();
+        assert!(matches!(err, RegistryError::DuplicateMethod(ref m) if m == "moj_getProof"));
+        assert_eq!(reg.handlers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn denied_methods_are_rejected_before_fallback() {
+        let reg: RpcRegistry<()> = RpcRegistry::new()
+            .with_fallback(Namespace::Eth, |_req, _ctx| {
+                Box::pin(async { Ok(Value::Bool(true)) })
+            })
+            .deny_methods(["eth_sendRawTransaction"]);
+
+        let request = |method: &str| -> RpcRequest {
+            serde_json::from_value(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": method, "params": []
+            }))
+            .unwrap()
+        };
+
+        let err = reg
+            .route(&request("eth_sendRawTransaction"), ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err.rpc_err(), RpcErr::MethodNotFound(m) if m.contains("disabled")));
+        assert_eq!(
+            reg.route(&request("eth_blockNumber"), ()).await.unwrap(),
+            Value::Bool(true)
+        );
+    }
+
+    fn sleepy_registry(sleep: Duration) -> RpcRegistry<()> {
+        RpcRegistry::new().with_handler("moj_sleep", move |_req, _ctx| {
+            Box::pin(async move {
+                tokio::time::sleep(sleep).await;
+                Ok(Value::Bool(true))
+            })
+        })
+    }
+
+    fn sleep_request() -> RpcRequest {
+        serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"method":"moj_sleep","params":[]}"#)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dispatch_times_out_slow_handler() {
+        let reg = sleepy_registry(Duration::from_secs(5)).with_timeout(Duration::from_millis(20));
+
+        let err = reg.dispatch(&sleep_request(), ()).await.unwrap_err();
+        assert!(
+            matches!(err.rpc_err(), RpcErr::Internal(m) if m == "handler timed out after 20 ms")
+        );
+    }
+
+    #[tokio::test]
+    async fn method_timeout_overrides_default() {
+        let reg = sleepy_registry(Duration::from_millis(10))
+            .with_timeout(Duration::from_millis(1))
+            .with_method_timeout("moj_sleep", Duration::from_secs(5));
+
+        assert_eq!(
+            reg.dispatch(&sleep_request(), ()).await.unwrap(),
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn slow_threshold_is_opt_in() {
+        let reg: RpcRegistry<()> = RpcRegistry::new();
+        assert!(!reg.is_slow(Duration::from_secs(3600)));
+
+        let reg = reg.with_slow_threshold(Duration::from_millis(100));
+        assert!(!reg.is_slow(Duration::from_millis(100)));
+        assert!(reg.is_slow(Duration::from_millis(101)));
+    }
+
     #[tokio::test]
     async fn dispatch_uses_fallback() {
         let mut reg: RpcRegistry<()> = RpcRegistry::new();
@@ -237,6 +772,39 @@ mod tests {
         assert_eq!(out, serde_json::json!("ok"));
     }
 
+    #[tokio::test]
+    async fn dispatch_uses_custom_namespace_fallback() {
+        let reg: RpcRegistry<()> = RpcRegistry::new()
+            .with_fallback("mojaveExt", |req, _ctx| {
+                Box::pin(async move { Ok(serde_json::json!(req.method)) })
+            })
+            .with_fallback(Namespace::Eth, |_req, _ctx| {
+                Box::pin(async { Ok(serde_json::json!("eth")) })
+            });
+        let request = |method: &str| -> RpcRequest {
+            serde_json::from_value(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "method": method, "params": []
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(
+            reg.dispatch(&request("mojaveExt_status"), ())
+                .await
+                .unwrap(),
+            serde_json::json!("mojaveExt_status")
+        );
+        assert_eq!(
+            reg.dispatch(&request("eth_chainId"), ()).await.unwrap(),
+            serde_json::json!("eth")
+        );
+        assert!(matches!(
+            reg.dispatch(&request("otherExt_status"), ()).await.unwrap_err().rpc_err(),
+            RpcErr::MethodNotFound(m) if m == "otherExt_status"
+        ));
+        assert_eq!(reg.registered_methods(), ["eth_*", "mojaveExt_*"]);
+    }
+
     #[tokio::test]
     async fn dispatch_method_not_found_without_fallback() {
         let reg: RpcRegistry<()> = RpcRegistry::new();
@@ -245,12 +813,37 @@ mod tests {
         )
         .unwrap();
         let err = reg.dispatch(&req, ()).await.err().unwrap();
-        match err {
+        match err.rpc_err() {
             mojave_rpc_core::RpcErr::MethodNotFound(m) => assert_eq!(m, "eth_blockNumber"),
             _ => panic!("unexpected error"),
         }
     }
 
+    #[tokio::test]
+    async fn rpc_methods_lists_handlers_and_fallbacks() {
+        fn noop(_req: &RpcRequest, _ctx: ()) -> BoxFuture<'_, RpcResult> {
+            Box::pin(async { Ok(Value::Null) })
+        }
+        let mut reg: RpcRegistry<()> = RpcRegistry::new().with_rpc_methods();
+        reg.register_fn("moj_getProof", noop)
+            .register_fn("eth_chainId", noop)
+            .register_fallback(Namespace::Eth, noop);
+
+        let req: RpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"method":"rpc_methods","params":[]}"#)
+                .unwrap();
+        let out = reg.dispatch(&req, ()).await.unwrap();
+
+        assert_eq!(
+            out,
+            serde_json::json!(["eth_*", "eth_chainId", "moj_getProof", "rpc_methods"])
+        );
+        assert_eq!(
+            reg.registered_methods(),
+            ["eth_*", "eth_chainId", "moj_getProof", "rpc_methods"]
+        );
+    }
+
     #[tokio::test]
     async fn handle_batch_requests() {
         let mut reg: RpcRegistry<()> = RpcRegistry::new();
@@ -262,11 +855,432 @@ mod tests {
             {"jsonrpc":"2.0","id":1,"method":"moj_echo","params":["a"]},
             {"jsonrpc":"2.0","id":2,"method":"moj_echo","params":["b"]}
             ]"#;
-        let Json(val) = super::handle::<_>(axum::extract::State(service), body.into())
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(val.is_array());
+        let arr = val.as_array().unwrap();
+        assert_eq!(arr.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn handle_single_notification_returns_no_content() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        let seen = calls.clone();
+        reg.register_fn("moj_ping", move |_req, _| {
+            let seen = seen.clone();
+            Box::pin(async move {
+                seen.fetch_add(1, Ordering::SeqCst);
+                Ok(Value::Null)
+            })
+        });
+        let router = RpcService::new((), reg).router();
+
+        let response = router
+            .oneshot(
+                Request::post("/")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        r#"{"jsonrpc":"2.0","method":"moj_ping","params":[]}"#,
+                    ))
+                    .unwrap(),
+            )
             .await
             .unwrap();
-        assert!(val.is_array());
+
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn handle_batch_omits_notifications() {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_echo", |req, _| {
+            Box::pin(async move { Ok(serde_json::to_value(&req.params).unwrap()) })
+        });
+        let service = RpcService::new((), reg);
+        let body = r#"[
+            {"jsonrpc":"2.0","id":1,"method":"moj_echo","params":["a"]},
+            {"jsonrpc":"2.0","method":"moj_echo","params":["b"]},
+            {"jsonrpc":"2.0","id":3,"method":"moj_echo","params":["c"]}
+            ]"#;
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service.clone()),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
         let arr = val.as_array().unwrap();
         assert_eq!(arr.len(), 2);
+        assert_eq!(arr[0]["id"], serde_json::json!(1));
+        assert_eq!(arr[1]["id"], serde_json::json!(3));
+
+        let only_notifications = r#"[{"jsonrpc":"2.0","method":"moj_echo","params":["b"]}]"#;
+        let res = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            only_notifications.into(),
+        )
+        .await
+        .unwrap();
+        assert!(res.is_none());
+    }
+
+    #[tokio::test]
+    async fn handle_enforces_max_batch_size() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        let seen = calls.clone();
+        reg.register_fn("moj_ping", move |_req, _| {
+            let seen = seen.clone();
+            Box::pin(async move {
+                seen.fetch_add(1, Ordering::SeqCst);
+                Ok(Value::Null)
+            })
+        });
+        let service = RpcService::new((), reg).with_max_batch_size(2);
+        let batch = |n: usize| {
+            let requests: Vec<_> = (1..=n)
+                .map(|id| serde_json::json!({"jsonrpc":"2.0","id":id,"method":"moj_ping","params":[]}))
+                .collect();
+            Value::Array(requests).to_string()
+        };
+
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service.clone()),
+            HeaderMap::new(),
+            None,
+            batch(2),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(val.as_array().unwrap().len(), 2);
+
+        let (status, Json(val)) = super::handle::<_>(
+            axum::extract::State(service.clone()),
+            HeaderMap::new(),
+            None,
+            batch(3),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(val["error"]["code"].as_i64(), Some(-32600));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // The size is checked before any entry is parsed as a request.
+        let (_, Json(val)) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            "[1, 2, 3]".to_owned(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            val["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("batch too large")
+        );
+    }
+
+    #[tokio::test]
+    async fn handle_limits_batch_concurrency_and_keeps_order() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        let (running, max_seen) = (active.clone(), peak.clone());
+        reg.register_fn("moj_slowEcho", move |req, _| {
+            let (running, max_seen) = (running.clone(), max_seen.clone());
+            Box::pin(async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_seen.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(serde_json::to_value(&req.params).unwrap())
+            })
+        });
+        let service = RpcService::new((), reg).with_batch_concurrency(2);
+        let requests: Vec<_> = (0..6)
+            .map(|i| serde_json::json!({"jsonrpc":"2.0","id":i,"method":"moj_slowEcho","params":[i]}))
+            .collect();
+
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            Value::Array(requests).to_string(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let arr = val.as_array().unwrap();
+        assert_eq!(arr.len(), 6);
+        for (i, res) in arr.iter().enumerate() {
+            assert_eq!(res["id"], serde_json::json!(i));
+            assert_eq!(res["result"], serde_json::json!([i]));
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_deeply_nested_body() {
+        let service = RpcService::new((), RpcRegistry::<()>::new());
+        let body = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        let (status, Json(val)) =
+            super::handle::<_>(axum::extract::State(service), HeaderMap::new(), None, body)
+                .await
+                .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(val["error"]["code"].as_i64(), Some(-32700));
+    }
+
+    #[tokio::test]
+    async fn handle_truncated_json_is_parse_error() {
+        let service = RpcService::new((), RpcRegistry::<()>::new());
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"moj_echo","par"#;
+        let (status, Json(val)) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(val["error"]["code"].as_i64(), Some(-32700));
+        assert!(val["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn handle_invalid_envelope_is_invalid_request_with_id() {
+        let service = RpcService::new((), RpcRegistry::<()>::new());
+        let body = r#"{"id":7,"method":"moj_echo","params":[]}"#;
+        let (status, Json(val)) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(val["error"]["code"].as_i64(), Some(-32600));
+        assert_eq!(val["id"], serde_json::json!(7));
+    }
+
+    #[tokio::test]
+    async fn handler_error_data_reaches_the_response() {
+        use mojave_rpc_core::WithData;
+
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_validate", |_req, _ctx| {
+            Box::pin(async {
+                Err(RpcErr::BadParams("invalid address".into())
+                    .with_data(serde_json::json!({ "field": "to" }))
+                    .into())
+            })
+        });
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"moj_validate","params":[]}"#;
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(RpcService::new((), reg)),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(val["error"]["code"].as_i64(), Some(-32602));
+        assert_eq!(val["error"]["data"], serde_json::json!({ "field": "to" }));
+    }
+
+    #[tokio::test]
+    async fn context_fn_sees_headers_and_peer() {
+        let mut reg: RpcRegistry<String> = RpcRegistry::new();
+        reg.register_fn("moj_whoami", |_req, ctx| {
+            Box::pin(async move { Ok(serde_json::json!(ctx)) })
+        });
+        let service = RpcService::with_context_fn(reg, |_req, headers, peer| {
+            let client = headers
+                .get("x-client-id")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("anonymous");
+            format!("{client}@{peer}")
+        });
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-client-id", "alice".parse().unwrap());
+        let peer = SocketAddr::from(([10, 0, 0, 7], 4242));
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"moj_whoami","params":[]}"#;
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service),
+            headers,
+            Some(Extension(ConnectInfo(peer))),
+            body.into(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(val["result"], serde_json::json!("alice@10.0.0.7:4242"));
+    }
+
+    #[tokio::test]
+    async fn handler_panic_becomes_internal_error() {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_boom", |_req, _ctx| {
+            Box::pin(async {
+                let value: u64 = serde_json::from_str(r#""unexpected data""#).unwrap();
+                Ok(serde_json::json!(value))
+            })
+        });
+        let req: mojave_rpc_core::RpcRequest =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"method":"moj_boom","params":[]}"#)
+                .unwrap();
+        let err = reg.dispatch(&req, ()).await.unwrap_err();
+        assert!(matches!(err.rpc_err(), RpcErr::Internal(m) if m == "handler panicked"));
+    }
+
+    #[tokio::test]
+    async fn handler_panic_only_fails_its_batch_entry() {
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_boom", |_req, _ctx| {
+            Box::pin(async {
+                let value: u64 = serde_json::from_str(r#""unexpected data""#).unwrap();
+                Ok(serde_json::json!(value))
+            })
+        });
+        reg.register_fn("moj_echo", |req, _| {
+            Box::pin(async move { Ok(serde_json::to_value(&req.params).unwrap()) })
+        });
+        let service = RpcService::new((), reg);
+        let body = r#"[
+            {"jsonrpc":"2.0","id":1,"method":"moj_boom","params":[]},
+            {"jsonrpc":"2.0","id":2,"method":"moj_echo","params":["ok"]}
+            ]"#;
+
+        let Json(val) = super::handle::<_>(
+            axum::extract::State(service),
+            HeaderMap::new(),
+            None,
+            body.into(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let arr = val.as_array().unwrap();
+        assert_eq!(arr[0]["error"]["code"].as_i64(), Some(-32603));
+        assert!(
+            arr[0]["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("handler panicked")
+        );
+        assert_eq!(arr[1]["result"], serde_json::json!(["ok"]));
+    }
+
+    #[tokio::test]
+    async fn with_compression_honors_accept_encoding() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let mut reg: RpcRegistry<()> = RpcRegistry::new();
+        reg.register_fn("moj_large", |_req, _ctx| {
+            Box::pin(async { Ok(serde_json::json!("x".repeat(4096))) })
+        });
+        let router = RpcService::new((), reg).with_compression().router();
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"moj_large","params":[]}"#;
+
+        let request = |encoding: &str| {
+            Request::post("/")
+                .header("content-type", "application/json")
+                .header("accept-encoding", encoding)
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = router.clone().oneshot(request("gzip")).await.unwrap();
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+
+        let response = router.oneshot(request("identity")).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+    }
+
+    #[tokio::test]
+    async fn health_routes_are_opt_in() {
+        use axum::{body::Body, http::Request};
+        use std::sync::atomic::AtomicBool;
+        use tower::ServiceExt;
+
+        let get = |path: &str| Request::get(path).body(Body::empty()).unwrap();
+        let body_json = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&bytes).unwrap()
+        };
+
+        let router = RpcService::new((), RpcRegistry::<()>::new()).router();
+        let response = router.oneshot(get("/health")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let synced = Arc::new(AtomicBool::new(false));
+        let router = RpcService::new((), RpcRegistry::<()>::new())
+            .with_health_routes({
+                let synced = synced.clone();
+                move || {
+                    if synced.load(Ordering::Relaxed) {
+                        Readiness::Ready
+                    } else {
+                        Readiness::Syncing
+                    }
+                }
+            })
+            .router();
+
+        let response = router.clone().oneshot(get("/health")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!({ "status": "ok" })
+        );
+
+        let response = router.clone().oneshot(get("/ready")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!({ "status": "syncing" })
+        );
+
+        synced.store(true, Ordering::Relaxed);
+        let response = router.oneshot(get("/ready")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!({ "status": "ready" })
+        );
     }
 }
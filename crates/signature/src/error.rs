@@ -14,6 +14,19 @@ pub enum Error {
     #[cfg(feature = "ed25519")]
     #[error("ed25519 signature verification failed")]
     Ed25519(#[from] ed25519_dalek::SignatureError),
+    #[cfg(any(feature = "secp256k1", feature = "ed25519"))]
+    #[error("Invalid key encoding: {0}")]
+    KeyEncoding(#[from] pkcs8::Error),
+    #[error("Expected a \"{expected}\" PEM block, found \"{found}\"")]
+    PemLabel {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("Expected a {expected} key, found algorithm {found}")]
+    WrongKeyAlgorithm {
+        expected: &'static str,
+        found: String,
+    },
 }
 
 #[cfg(feature = "secp256k1")]
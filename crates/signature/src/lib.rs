@@ -2,14 +2,24 @@
 pub mod ecdsa;
 #[cfg(feature = "ed25519")]
 pub mod eddsa;
+#[cfg(any(feature = "secp256k1", feature = "ed25519"))]
+mod encoding;
 pub mod error;
 pub mod types;
 
+// Both schemes can be enabled together and are always reachable through their
+// own modules. The top-level aliases pick secp256k1 when both are enabled.
 cfg_if::cfg_if! {
     if #[cfg(feature = "secp256k1")] {
       pub use ecdsa::{SigningKey, VerifyingKey};
+
+      /// Scheme of the top-level [`SigningKey`] and [`VerifyingKey`].
+      pub const DEFAULT_SCHEME: types::SignatureScheme = types::SignatureScheme::Secp256k1;
     } else if #[cfg(feature = "ed25519")] {
       pub use eddsa::{SigningKey, VerifyingKey};
+
+      /// Scheme of the top-level [`SigningKey`] and [`VerifyingKey`].
+      pub const DEFAULT_SCHEME: types::SignatureScheme = types::SignatureScheme::Ed25519;
     }
 }
 
@@ -19,3 +29,54 @@ pub mod prelude {
         types::*,
     };
 }
+
+#[cfg(all(test, feature = "secp256k1", feature = "ed25519"))]
+mod tests {
+    use super::*;
+    use crate::types::{SignatureScheme, Signer, Verifier};
+
+    const ECDSA_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const EDDSA_KEY: [u8; 32] = [7u8; 32];
+
+    #[test]
+    fn both_schemes_sign_and_verify_in_one_binary() {
+        let msg = b"Hello World";
+
+        let ecdsa_key: ecdsa::SigningKey = ECDSA_KEY.parse().unwrap();
+        let ecdsa_signature = ecdsa_key.sign(msg).unwrap();
+        assert_eq!(ecdsa_signature.scheme, SignatureScheme::Secp256k1);
+        ecdsa_key
+            .verifying_key()
+            .verify(msg, &ecdsa_signature)
+            .unwrap();
+
+        let eddsa_key = eddsa::SigningKey::from_slice(&EDDSA_KEY).unwrap();
+        let eddsa_signature = eddsa_key.sign(msg).unwrap();
+        assert_eq!(eddsa_signature.scheme, SignatureScheme::Ed25519);
+        eddsa_key
+            .verifying_key()
+            .verify(msg, &eddsa_signature)
+            .unwrap();
+
+        // A signature from one scheme is rejected by the other.
+        assert!(
+            eddsa_key
+                .verifying_key()
+                .verify(msg, &ecdsa_signature)
+                .is_err()
+        );
+        assert!(
+            ecdsa_key
+                .verifying_key()
+                .verify(msg, &eddsa_signature)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn top_level_aliases_default_to_secp256k1() {
+        assert_eq!(DEFAULT_SCHEME, SignatureScheme::Secp256k1);
+        let key: SigningKey = ECDSA_KEY.parse().unwrap();
+        assert_eq!(key.sign(b"msg").unwrap().scheme, SignatureScheme::Secp256k1);
+    }
+}
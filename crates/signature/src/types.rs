@@ -3,9 +3,14 @@ use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 pub trait Signer: FromStr<Err = Error> + Sized {
+    /// Key that verifies this signer's signatures.
+    type VerifyingKey: Verifier;
+
     fn from_slice(slice: &[u8]) -> Result<Self, Error>;
 
     fn sign<T: Serialize>(&self, message: &T) -> Result<Signature, Error>;
+
+    fn verifying_key(&self) -> Self::VerifyingKey;
 }
 
 pub trait Verifier: FromStr<Err = Error> + Sized + Deserialize<'static> + Serialize {
@@ -26,3 +31,30 @@ pub struct Signature {
     pub bytes: Vec<u8>,
     pub scheme: SignatureScheme,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<S: Signer>(signer: &S) {
+        let msg = b"Hello World";
+        let signature = signer.sign(msg).unwrap();
+        let verifying_key = signer.verifying_key();
+        assert!(verifying_key.verify(msg, &signature).is_ok());
+        assert!(verifying_key.verify(b"Goodbye World", &signature).is_err());
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn generic_roundtrip_with_secp256k1() {
+        let signer = crate::ecdsa::SigningKey::from_slice(&[1u8; 32]).unwrap();
+        roundtrip(&signer);
+    }
+
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn generic_roundtrip_with_ed25519() {
+        let signer = crate::eddsa::SigningKey::from_slice(&[1u8; 32]).unwrap();
+        roundtrip(&signer);
+    }
+}
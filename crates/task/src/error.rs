@@ -2,6 +2,10 @@
 pub enum Error {
     #[error("Failed to send the request: {0}")]
     Send(String),
+    #[error("The task's request queue is full")]
+    QueueFull,
+    #[error("The task did not respond within {0:?}")]
+    Timeout(std::time::Duration),
     #[error("Failed to receive a response: {0}")]
     Receive(#[from] tokio::sync::oneshot::error::RecvError),
     #[error("Task error: {0}")]
@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::{
     error::Error,
     task_runner::{RequestSignal, ShutdownSignal},
     traits::Task,
 };
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{
+    mpsc::{self, error::TrySendError},
+    oneshot,
+};
 
 pub struct TaskHandle<T: Task> {
     inner: Arc<TaskHandleInner<T>>,
@@ -52,6 +55,8 @@ where
         }
     }
 
+    /// Sends a request and waits for its response. Waits for room when the
+    /// request queue is full.
     pub async fn request(&self, request: T::Request) -> Result<T::Response, Error> {
         let (sender, receiver) = oneshot::channel();
         self.inner
@@ -62,6 +67,34 @@ where
         receiver.await?.map_err(|error| Error::Task(error.into()))
     }
 
+    /// Like [`TaskHandle::request`], but fails with [`Error::QueueFull`]
+    /// instead of waiting when the request queue is full.
+    pub async fn try_request(&self, request: T::Request) -> Result<T::Response, Error> {
+        let (sender, receiver) = oneshot::channel();
+        self.inner
+            .request
+            .try_send((request, sender))
+            .map_err(|error| match error {
+                TrySendError::Full(_) => Error::QueueFull,
+                TrySendError::Closed(_) => Error::Send(error.to_string()),
+            })?;
+        receiver.await?.map_err(|error| Error::Task(error.into()))
+    }
+
+    /// Like [`TaskHandle::request`], but gives up with [`Error::Timeout`]
+    /// when the response takes longer than `timeout`, including time spent
+    /// waiting for room in the queue. The task still handles the request;
+    /// its late response is discarded.
+    pub async fn request_timeout(
+        &self,
+        request: T::Request,
+        timeout: Duration,
+    ) -> Result<T::Response, Error> {
+        tokio::time::timeout(timeout, self.request(request))
+            .await
+            .map_err(|_| Error::Timeout(timeout))?
+    }
+
     pub async fn shutdown(&self) -> Result<(), Error> {
         let (sender, receiver) = oneshot::channel();
         self.inner
@@ -72,3 +105,128 @@ where
         receiver.await?.map_err(|error| Error::Task(error.into()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+
+    /// Blocks each request until a permit is released.
+    struct Gated {
+        gate: Arc<Semaphore>,
+        started: mpsc::UnboundedSender<()>,
+    }
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("gate closed")]
+    struct GateClosed;
+
+    impl Task for Gated {
+        type Request = ();
+        type Response = ();
+        type Error = GateClosed;
+
+        async fn handle_request(&mut self, _request: ()) -> Result<(), GateClosed> {
+            let _ = self.started.send(());
+            self.gate.acquire().await.map_err(|_| GateClosed)?.forget();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn try_request_fails_when_queue_is_full() {
+        let gate = Arc::new(Semaphore::new(0));
+        let (started, mut started_rx) = mpsc::unbounded_channel();
+        let handle = Gated {
+            gate: gate.clone(),
+            started,
+        }
+        .spawn_with_capacity(1);
+
+        // The first request is being handled, the second fills the queue.
+        let running = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.request(()).await }
+        });
+        started_rx.recv().await.unwrap();
+        let queued = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.try_request(()).await }
+        });
+        tokio::task::yield_now().await;
+
+        assert!(matches!(
+            handle.try_request(()).await,
+            Err(Error::QueueFull)
+        ));
+
+        gate.add_permits(2);
+        running.await.unwrap().unwrap();
+        queued.await.unwrap().unwrap();
+
+        // Once drained, requests are accepted again.
+        gate.add_permits(1);
+        handle.try_request(()).await.unwrap();
+    }
+
+    /// Sleeps for the requested duration before answering.
+    struct Sleeper;
+
+    impl Task for Sleeper {
+        type Request = Duration;
+        type Response = Duration;
+        type Error = GateClosed;
+
+        async fn handle_request(&mut self, request: Duration) -> Result<Duration, GateClosed> {
+            tokio::time::sleep(request).await;
+            Ok(request)
+        }
+    }
+
+    #[tokio::test]
+    async fn request_timeout_elapses_and_task_keeps_serving() {
+        let handle = Sleeper.spawn();
+
+        let timeout = Duration::from_millis(20);
+        let result = handle
+            .request_timeout(Duration::from_millis(200), timeout)
+            .await;
+        assert!(matches!(result, Err(Error::Timeout(t)) if t == timeout));
+
+        // The slow response is dropped and the next request is answered.
+        let fast = Duration::from_millis(1);
+        assert_eq!(
+            handle
+                .request_timeout(fast, Duration::from_secs(5))
+                .await
+                .unwrap(),
+            fast
+        );
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn request_waits_for_room_when_queue_is_full() {
+        let gate = Arc::new(Semaphore::new(0));
+        let (started, mut started_rx) = mpsc::unbounded_channel();
+        let handle = Gated {
+            gate: gate.clone(),
+            started,
+        }
+        .spawn_with_capacity(1);
+
+        let requests: Vec<_> = (0..3)
+            .map(|_| {
+                let handle = handle.clone();
+                tokio::spawn(async move { handle.request(()).await })
+            })
+            .collect();
+        started_rx.recv().await.unwrap();
+
+        gate.add_permits(3);
+        for request in requests {
+            request.await.unwrap().unwrap();
+        }
+    }
+}
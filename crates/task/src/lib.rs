@@ -9,6 +9,7 @@ pub use constants::*;
 pub use error::Error;
 pub use handle::TaskHandle;
 pub use runner::{Runner, Service};
+pub use task_runner::RestartPolicy;
 pub use traits::Task;
 
 #[tokio::test]
@@ -1,4 +1,11 @@
+use std::{
+    collections::VecDeque,
+    panic::AssertUnwindSafe,
+    time::{Duration, Instant},
+};
+
 use crate::traits::Task;
+use futures::FutureExt;
 use tokio::sync::{mpsc, oneshot};
 
 pub type RequestSignal<T> = (
@@ -7,10 +14,32 @@ pub type RequestSignal<T> = (
 );
 pub type ShutdownSignal<T> = oneshot::Sender<Result<(), <T as Task>::Error>>;
 
+/// Supervision policy for a task whose `handle_request` panics.
+///
+/// The task is restarted, calling `on_shutdown` then `on_start`, as long as
+/// it panicked at most `max_restarts` times within `window`. Past that the
+/// task stops and its handles start failing.
+#[derive(Clone, Copy, Debug)]
+pub struct RestartPolicy {
+    pub max_restarts: usize,
+    pub window: Duration,
+}
+
+impl RestartPolicy {
+    pub fn new(max_restarts: usize, window: Duration) -> Self {
+        Self {
+            max_restarts,
+            window,
+        }
+    }
+}
+
 pub struct TaskRunner<T: Task + 'static> {
     request: mpsc::Receiver<RequestSignal<T>>,
     shutdown: mpsc::Receiver<ShutdownSignal<T>>,
     task: T,
+    restart_policy: Option<RestartPolicy>,
+    restarts: VecDeque<Instant>,
 }
 
 impl<T: Task + 'static> TaskRunner<T> {
@@ -23,25 +52,57 @@ impl<T: Task + 'static> TaskRunner<T> {
             request,
             shutdown,
             task,
+            restart_policy: None,
+            restarts: VecDeque::new(),
         }
     }
 
+    pub fn with_restart_policy(mut self, policy: Option<RestartPolicy>) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
     pub async fn listen(&mut self) {
-        if let Err(error) = self.task.on_start().await {
-            tracing::error!(
-                "Error while start task '{}'. Message: {}",
-                self.task.name(),
-                error
-            )
-        }
+        self.start().await;
         loop {
             tokio::select! {
                 request = self.request.recv() => {
                     if let Some((request, sender)) = request {
                         self.task.on_request_started(&request);
-                        let response = self.task.handle_request(request).await;
-                        self.task.on_request_finished(&response);
-                        let _ = sender.send(response);
+                        let response = AssertUnwindSafe(self.task.handle_request(request))
+                            .catch_unwind()
+                            .await;
+                        match response {
+                            Ok(response) => {
+                                self.task.on_request_finished(&response);
+                                let _ = sender.send(response);
+                            }
+                            Err(panic) => {
+                                // The caller sees the dropped sender as a receive error.
+                                drop(sender);
+                                let Some(policy) = self.restart_policy else {
+                                    std::panic::resume_unwind(panic);
+                                };
+                                if !self.record_restart(policy) {
+                                    tracing::error!(
+                                        "Task '{}' panicked more than {} times within {:?}, giving up",
+                                        self.task.name(),
+                                        policy.max_restarts,
+                                        policy.window
+                                    );
+                                    return;
+                                }
+                                tracing::warn!("Task '{}' panicked, restarting", self.task.name());
+                                if let Err(error) = self.task.on_shutdown().await {
+                                    tracing::error!(
+                                        "Error while shutting down task '{}' for restart. Message: {}",
+                                        self.task.name(),
+                                        error
+                                    )
+                                }
+                                self.start().await;
+                            }
+                        }
                     }
                 }
                 shutdown = self.shutdown.recv() => {
@@ -54,4 +115,113 @@ impl<T: Task + 'static> TaskRunner<T> {
             }
         }
     }
+
+    async fn start(&mut self) {
+        if let Err(error) = self.task.on_start().await {
+            tracing::error!(
+                "Error while start task '{}'. Message: {}",
+                self.task.name(),
+                error
+            )
+        }
+    }
+
+    /// Records a restart, returning `false` when the policy's budget for the
+    /// current window is spent.
+    fn record_restart(&mut self, policy: RestartPolicy) -> bool {
+        let now = Instant::now();
+        while self
+            .restarts
+            .front()
+            .is_some_and(|restart| now.duration_since(*restart) > policy.window)
+        {
+            self.restarts.pop_front();
+        }
+        if self.restarts.len() >= policy.max_restarts {
+            return false;
+        }
+        self.restarts.push_back(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Panics on its first `panics` requests and counts its starts.
+    struct Flaky {
+        panics: usize,
+        handled: usize,
+        starts: Arc<AtomicUsize>,
+    }
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("never")]
+    struct Never;
+
+    impl Task for Flaky {
+        type Request = ();
+        type Response = usize;
+        type Error = Never;
+
+        async fn on_start(&mut self) -> Result<(), Never> {
+            self.starts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn handle_request(&mut self, _request: ()) -> Result<usize, Never> {
+            self.handled += 1;
+            if self.handled <= self.panics {
+                panic!("flaky request {}", self.handled);
+            }
+            Ok(self.handled)
+        }
+    }
+
+    fn flaky(panics: usize) -> (Flaky, Arc<AtomicUsize>) {
+        let starts = Arc::new(AtomicUsize::new(0));
+        let task = Flaky {
+            panics,
+            handled: 0,
+            starts: starts.clone(),
+        };
+        (task, starts)
+    }
+
+    #[tokio::test]
+    async fn restarts_after_panic_and_recovers() {
+        let (task, starts) = flaky(1);
+        let handle = task.spawn_supervised(RestartPolicy::new(3, Duration::from_secs(60)));
+
+        assert!(matches!(handle.request(()).await, Err(Error::Receive(_))));
+        assert_eq!(handle.request(()).await.unwrap(), 2);
+        assert_eq!(starts.load(Ordering::SeqCst), 2);
+        handle.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_restarts() {
+        let (task, _) = flaky(usize::MAX);
+        let handle = task.spawn_supervised(RestartPolicy::new(1, Duration::from_secs(60)));
+
+        assert!(handle.request(()).await.is_err());
+        assert!(handle.request(()).await.is_err());
+        // The runner is gone, so the handle keeps failing.
+        assert!(handle.request(()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn panic_without_policy_stops_the_task() {
+        let (task, _) = flaky(1);
+        let handle = task.spawn();
+
+        assert!(handle.request(()).await.is_err());
+        assert!(handle.request(()).await.is_err());
+    }
 }
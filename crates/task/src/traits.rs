@@ -1,6 +1,10 @@
 use std::time::Duration;
 
-use crate::{constants::DEFAULT_TASK_CAPACITY, handle::TaskHandle, task_runner::TaskRunner};
+use crate::{
+    constants::DEFAULT_TASK_CAPACITY,
+    handle::TaskHandle,
+    task_runner::{RestartPolicy, TaskRunner},
+};
 use tokio::{
     sync::{mpsc, oneshot},
     time::{MissedTickBehavior, interval},
@@ -35,24 +39,27 @@ pub trait Task: Sized + 'static {
     }
 
     fn spawn_with_capacity(self, capacity: usize) -> TaskHandle<Self> {
-        let (request_sender, request_receiver) = mpsc::channel::<(
-            Self::Request,
-            oneshot::Sender<Result<Self::Response, Self::Error>>,
-        )>(capacity);
-        let (shutdown_sender, shutdown_receiver) =
-            mpsc::channel::<oneshot::Sender<Result<(), Self::Error>>>(capacity);
-
-        let mut runner = TaskRunner::new(request_receiver, shutdown_receiver, self);
-        tokio::spawn(async move {
-            runner.listen().await;
-        });
-        TaskHandle::new(request_sender, shutdown_sender)
+        spawn_runner(self, capacity, None)
     }
 
     fn spawn(self) -> TaskHandle<Self> {
         self.spawn_with_capacity(DEFAULT_TASK_CAPACITY)
     }
 
+    /// Spawn the task, restarting it according to `policy` whenever
+    /// `handle_request` panics. The panicking request fails with a receive error.
+    fn spawn_supervised_with_capacity(
+        self,
+        capacity: usize,
+        policy: RestartPolicy,
+    ) -> TaskHandle<Self> {
+        spawn_runner(self, capacity, Some(policy))
+    }
+
+    fn spawn_supervised(self, policy: RestartPolicy) -> TaskHandle<Self> {
+        self.spawn_supervised_with_capacity(DEFAULT_TASK_CAPACITY, policy)
+    }
+
     /// Spawn the task and also start a periodic job that submits a request every `every`.
     ///
     /// The `make_request` closure is called on each tick to build the request,
@@ -105,3 +112,21 @@ pub trait Task: Sized + 'static {
         self.spawn_with_capacity_periodic(DEFAULT_TASK_CAPACITY, every, make_request)
     }
 }
+
+fn spawn_runner<T: Task>(
+    task: T,
+    capacity: usize,
+    restart_policy: Option<RestartPolicy>,
+) -> TaskHandle<T> {
+    let (request_sender, request_receiver) =
+        mpsc::channel::<(T::Request, oneshot::Sender<Result<T::Response, T::Error>>)>(capacity);
+    let (shutdown_sender, shutdown_receiver) =
+        mpsc::channel::<oneshot::Sender<Result<(), T::Error>>>(capacity);
+
+    let mut runner = TaskRunner::new(request_receiver, shutdown_receiver, task)
+        .with_restart_policy(restart_policy);
+    tokio::spawn(async move {
+        runner.listen().await;
+    });
+    TaskHandle::new(request_sender, shutdown_sender)
+}
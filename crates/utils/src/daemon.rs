@@ -1,21 +1,36 @@
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, OwnedFd};
 use std::{
-    fs::OpenOptions,
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Read, Write},
     path::{Path, PathBuf},
     str::FromStr,
     time::Duration,
 };
 
 use anyhow::Result;
+#[cfg(unix)]
 use daemonize::Daemonize;
 use sysinfo::{Pid, System};
 use thiserror::Error;
 
 const PROCESS_KILL_TIMEOUT_SEC: u64 = 5;
 
+/// Size at which the binaries rotate their daemon log.
+pub const DEFAULT_MAX_LOG_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Rotated daemon logs kept next to the live one.
+pub const DEFAULT_MAX_LOG_BACKUPS: usize = 5;
+
 pub struct DaemonOptions {
     pub no_daemon: bool,
     pub pid_file_path: PathBuf,
     pub log_file_path: PathBuf,
+    /// Rotate the log once it would grow past this size. The log grows
+    /// without bound when unset.
+    pub max_log_bytes: Option<u64>,
+    /// Rotated logs kept as `<log>.1` (newest) to `<log>.<max_log_backups>`.
+    pub max_log_backups: usize,
 }
 
 type DynError = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -25,6 +40,7 @@ pub enum DaemonError {
     #[error("pid in pid file is already running. pid: {0}")]
     AlreadyRunning(Pid),
 
+    #[cfg(unix)]
     #[error("daemonize failed: {0}")]
     Daemonize(#[from] daemonize::Error),
 
@@ -54,6 +70,23 @@ where
         return run_main_task(proc);
     }
 
+    let log_pump = daemonize(&opts)?;
+
+    let result = run_main_task(proc);
+    if let Err(e) = &result {
+        tracing::error!("run_main_task failed: {e}");
+    }
+    if let Some(log_pump) = log_pump {
+        log_pump.finish();
+    }
+    result
+}
+
+/// Detaches the process, redirecting its output to the log file and writing
+/// the pid file. With log rotation on, returns the thread moving the output
+/// into the log.
+#[cfg(unix)]
+fn daemonize(opts: &DaemonOptions) -> Result<Option<LogPump>, DynError> {
     let log_path = resolve_path(&opts.log_file_path)?;
     let pid_path = resolve_path(&opts.pid_file_path)?;
 
@@ -74,14 +107,25 @@ where
         }
         _ => {}
     }
-    let log_file = OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(&log_path)
-        .map_err(|source| DaemonError::IoWithPath {
+    // With rotation on, output goes through a pipe so the rotating writer
+    // can swap files underneath it.
+    let mut rotation = None;
+    let log_file = match opts.max_log_bytes {
+        Some(max_bytes) => {
+            let writer = RotatingLogWriter::open(&log_path, max_bytes, opts.max_log_backups)
+                .map_err(|source| DaemonError::IoWithPath {
+                    path: log_path.clone(),
+                    source,
+                })?;
+            let (reader, pipe) = std::io::pipe()?;
+            rotation = Some((reader, writer));
+            File::from(OwnedFd::from(pipe))
+        }
+        None => open_append(&log_path).map_err(|source| DaemonError::IoWithPath {
             path: log_path.clone(),
             source,
-        })?;
+        })?,
+    };
     let log_file_err = log_file
         .try_clone()
         .map_err(|source| DaemonError::IoWithPath {
@@ -100,12 +144,164 @@ where
         .stderr(log_file_err);
     daemon.start()?;
 
-    if let Err(e) = run_main_task(proc) {
-        tracing::error!("run_main_task failed: {e}");
-        return Err(e);
+    // Threads do not survive the fork, so the log pump starts in the daemon.
+    Ok(rotation.map(|(reader, writer)| LogPump {
+        log_path,
+        join: std::thread::spawn(move || pump_log(reader, writer)),
+    }))
+}
+
+/// Windows has no fork-based daemonization, so the process keeps running in
+/// the foreground as if `no_daemon` were set.
+#[cfg(windows)]
+fn daemonize(_opts: &DaemonOptions) -> Result<Option<LogPump>, DynError> {
+    tracing::warn!("daemonization not supported on Windows; running in foreground");
+    Ok(None)
+}
+
+/// Thread copying the daemon's stdout and stderr into the rotating log.
+struct LogPump {
+    log_path: PathBuf,
+    join: std::thread::JoinHandle<()>,
+}
+
+impl LogPump {
+    /// Closes the pipe and waits for the output still in it to reach the log.
+    /// Output written afterwards goes to the log file directly.
+    #[cfg(unix)]
+    fn finish(self) {
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+        // Replacing the last write ends of the pipe lets the pump see EOF.
+        if let Ok(log_file) = open_append(&self.log_path) {
+            for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+                // SAFETY: both descriptors are valid for the duration of the call.
+                unsafe { libc::dup2(log_file.as_raw_fd(), fd) };
+            }
+        }
+        let _ = self.join.join();
+    }
+
+    #[cfg(windows)]
+    fn finish(self) {}
+}
+
+/// Copies `reader` into the log until every write end of the pipe is closed.
+///
+/// The daemon's output still goes to the pipe when the log can't be written,
+/// so the pipe is kept drained instead of letting the daemon block on a full
+/// pipe. Failures are noted in the log, since stderr is the pipe itself.
+fn pump_log(mut reader: impl Read, mut writer: RotatingLogWriter) {
+    let mut buf = [0u8; 8192];
+    let mut write_failed = false;
+    loop {
+        let read = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => {
+                writer.note(format_args!("daemon log pump stopped: {error}"));
+                break;
+            }
+        };
+        match writer.write_all(&buf[..read]) {
+            Ok(()) => write_failed = false,
+            Err(error) if !write_failed => {
+                write_failed = true;
+                writer.note(format_args!("failed to write the daemon log: {error}"));
+            }
+            Err(_) => {}
+        }
+    }
+    let _ = writer.flush();
+}
+
+/// Appends to a log file, moving it to `<path>.1` once a write would take it
+/// past `max_bytes` and shifting older backups up to `<path>.<max_backups>`.
+pub struct RotatingLogWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    max_backups: usize,
+    file: File,
+    len: u64,
+}
+
+impl RotatingLogWriter {
+    pub fn open(
+        path: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_backups: usize,
+    ) -> std::io::Result<Self> {
+        let path = path.into();
+        let file = open_append(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_backups,
+            file,
+            len,
+        })
+    }
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if self.max_backups == 0 {
+            self.file.set_len(0)?;
+        } else {
+            for index in (1..self.max_backups).rev() {
+                match std::fs::rename(self.backup_path(index), self.backup_path(index + 1)) {
+                    Err(error) if error.kind() != ErrorKind::NotFound => return Err(error),
+                    _ => {}
+                }
+            }
+            std::fs::rename(&self.path, self.backup_path(1))?;
+            self.file = open_append(&self.path)?;
+        }
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Appends a line about the log itself, bypassing rotation.
+    fn note(&mut self, message: std::fmt::Arguments<'_>) {
+        let line = format!("{message}\n");
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.len += line.len() as u64;
+        }
     }
+}
+
+impl Write for RotatingLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.len > 0
+            && self.len + buf.len() as u64 > self.max_bytes
+            && let Err(error) = self.rotate()
+        {
+            // Keep appending rather than dropping output; rotation is retried
+            // once another `max_bytes` were written.
+            self.len = 0;
+            self.note(format_args!(
+                "failed to rotate {}, appending to it: {error}",
+                self.path.display()
+            ));
+        }
+        let written = self.file.write(buf)?;
+        self.len += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
 
-    Ok(())
+fn open_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().append(true).create(true).open(path)
 }
 
 pub fn stop_daemonized<P: AsRef<Path>>(pid_file: P) -> Result<()> {
@@ -138,15 +334,14 @@ pub fn stop_daemonized<P: AsRef<Path>>(pid_file: P) -> Result<()> {
     }
 }
 
+/// Makes `path` absolute, resolving relative paths against the current working
+/// directory, and creates its parent directory.
 fn resolve_path<P: AsRef<Path>>(path: P) -> Result<PathBuf> {
-    if path.as_ref().is_absolute() {
-        return Ok(path.as_ref().to_path_buf());
-    }
-
-    let path_buf = match std::env::home_dir() {
-        Some(home) => home.join(path),
-        None => PathBuf::from(".").join(path),
-    };
+    let path_buf =
+        std::path::absolute(path.as_ref()).map_err(|source| DaemonError::IoWithPath {
+            path: path.as_ref().to_path_buf(),
+            source,
+        })?;
 
     if let Some(parent) = path_buf.parent().filter(|p| !p.exists()) {
         std::fs::create_dir_all(parent).map_err(|source| DaemonError::IoWithPath {
@@ -168,6 +363,11 @@ fn read_pid_from_file<P: AsRef<Path>>(path: P) -> Result<Pid> {
     Ok(pid)
 }
 
+/// Returns true if a process with the given pid is alive.
+pub fn is_process_running(pid: u32) -> bool {
+    is_pid_running(Pid::from_u32(pid))
+}
+
 fn is_pid_running(pid: Pid) -> bool {
     System::new_all().process(pid).is_some()
 }
@@ -222,6 +422,16 @@ mod tests {
         assert_eq!(resolved, abs);
     }
 
+    #[test]
+    fn resolve_path_roots_relative_path_at_cwd() {
+        let rel = PathBuf::from(format!("mojave_daemon_rel_{}", std::process::id()));
+        let resolved = resolve_path(rel.join("pidfile.pid")).unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(resolved, cwd.join(&rel).join("pidfile.pid"));
+
+        let _ = fs::remove_dir_all(cwd.join(rel));
+    }
+
     #[test]
     fn read_pid_from_file_parses_current_pid() {
         // Write the current process PID to a file and check if it is read correctly + if it is a running PID
@@ -270,6 +480,8 @@ mod tests {
             no_daemon: true,
             pid_file_path: unique_path("unused_pid3"),
             log_file_path: unique_path("unused_log3"),
+            max_log_bytes: None,
+            max_log_backups: 0,
         };
         let res = run_daemonized(opts, || async { Ok(()) });
 
@@ -282,6 +494,8 @@ mod tests {
             no_daemon: true,
             pid_file_path: unique_path("unused_pid4"),
             log_file_path: unique_path("unused_log4"),
+            max_log_bytes: None,
+            max_log_backups: 0,
         };
         let res = run_daemonized(opts, || async { Err::<(), _>("propagate".into()) });
 
@@ -289,6 +503,99 @@ mod tests {
         assert!(format!("{res:#?}").contains("propagate"));
     }
 
+    #[cfg(windows)]
+    #[test]
+    fn run_daemonized_runs_in_foreground_on_windows() {
+        let pid_file_path = unique_path("windows_pid");
+        let opts = DaemonOptions {
+            no_daemon: false,
+            pid_file_path: pid_file_path.clone(),
+            log_file_path: unique_path("windows_log"),
+            max_log_bytes: None,
+            max_log_backups: 0,
+        };
+        let res = run_daemonized(opts, || async { Ok(()) });
+
+        assert!(res.is_ok());
+        assert!(!pid_file_path.exists());
+    }
+
+    #[test]
+    fn rotating_log_writer_rotates_past_max_bytes() {
+        let dir = unique_path("rotate");
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("node.log");
+
+        let mut writer = RotatingLogWriter::open(&log, 10, 2).unwrap();
+        writer.write_all(b"hello\n").unwrap();
+        writer.write_all(b"world\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "world\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("node.log.1")).unwrap(),
+            "hello\n"
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn rotating_log_writer_keeps_max_backups() {
+        let dir = unique_path("rotate_backups");
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("node.log");
+        fs::write(&log, "a\n").unwrap();
+
+        let mut writer = RotatingLogWriter::open(&log, 2, 2).unwrap();
+        for line in ["b\n", "c\n", "d\n"] {
+            writer.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "d\n");
+        assert_eq!(fs::read_to_string(dir.join("node.log.1")).unwrap(), "c\n");
+        assert_eq!(fs::read_to_string(dir.join("node.log.2")).unwrap(), "b\n");
+        assert!(!dir.join("node.log.3").exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn rotating_log_writer_keeps_appending_when_rotation_fails() {
+        let dir = unique_path("rotate_fails");
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("node.log");
+        // The current log can't be moved over a directory.
+        fs::create_dir_all(dir.join("node.log.1").join("busy")).unwrap();
+
+        let mut writer = RotatingLogWriter::open(&log, 10, 1).unwrap();
+        writer.write_all(b"hello\n").unwrap();
+        writer.write_all(b"world\n").unwrap();
+
+        let content = fs::read_to_string(&log).unwrap();
+        assert!(content.starts_with("hello\nfailed to rotate"));
+        assert!(content.ends_with("world\n"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn pump_log_drains_the_pipe_until_closed() {
+        let dir = unique_path("pump");
+        fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("node.log");
+        let writer = RotatingLogWriter::open(&log, 1024, 1).unwrap();
+        let (reader, mut pipe) = std::io::pipe().unwrap();
+
+        let pump = std::thread::spawn(move || pump_log(reader, writer));
+        pipe.write_all(b"first\nsecond\n").unwrap();
+        drop(pipe);
+        pump.join().unwrap();
+
+        assert_eq!(fs::read_to_string(&log).unwrap(), "first\nsecond\n");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
     #[tokio::test]
     async fn stop_daemonized_returns_no_such_process_for_fake_pid() {
         let pid_file = unique_path("fake_pid");
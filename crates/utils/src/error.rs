@@ -6,4 +6,16 @@ pub enum NetworkError {
     Io(#[from] std::io::Error),
     #[error("{0}")]
     Custom(String),
+    #[error("Invalid bootnodes file {path:?}: {source}")]
+    InvalidBootnodes {
+        path: std::path::PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Failed to fetch bootnodes from {url}: {source}")]
+    FetchBootnodes {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
 }
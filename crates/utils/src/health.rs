@@ -13,7 +13,8 @@ pub type HealthProbeHandle = JoinHandle<std::io::Result<()>>;
 /// Spawn a lightweight HTTP server exposing a `/health` endpoint.
 ///
 /// The server binds the provided socket address (use port `0` to pick an ephemeral
-/// port) and serves `GET /health` with a 200 OK response. The returned handle can
+/// port) and serves `GET /health` with a 200 OK response, and `GET /metrics` with
+/// the process metrics from [`crate::metrics::encode`]. The returned handle can
 /// be awaited to surface server errors; the server stops when `shutdown_signal`
 /// resolves.
 pub async fn spawn_health_probe<F>(
@@ -36,7 +37,7 @@ where
                 _ = &mut shutdown_signal => break,
                 accept_res = listener.accept() => {
                     let (mut stream, _) = accept_res?;
-                    respond_ok(&mut stream).await?;
+                    respond(&mut stream).await?;
                 }
             }
         }
@@ -47,13 +48,21 @@ where
     Ok((bound_addr, handle))
 }
 
-async fn respond_ok(stream: &mut TcpStream) -> std::io::Result<()> {
+async fn respond(stream: &mut TcpStream) -> std::io::Result<()> {
     let mut buf = [0u8; 1024];
     let _ = stream.readable().await;
-    let _ = stream.try_read(&mut buf);
-
-    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\ncontent-type: text/plain\r\nconnection: close\r\n\r\nOK";
-    stream.write_all(RESPONSE).await?;
+    let read = stream.try_read(&mut buf).unwrap_or_default();
+
+    let (content_type, body) = if buf[..read].starts_with(b"GET /metrics ") {
+        (crate::metrics::CONTENT_TYPE, crate::metrics::encode())
+    } else {
+        ("text/plain", "OK".to_owned())
+    };
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\ncontent-type: {content_type}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
     stream.shutdown().await
 }
 
@@ -93,4 +102,45 @@ mod tests {
         let _ = shutdown_tx.send(());
         handle.await.unwrap().unwrap();
     }
+
+    #[tokio::test]
+    async fn health_probe_serves_metrics() {
+        let counter = prometheus::register_int_counter!(crate::metrics::opts(
+            "health_probe_test_total",
+            "Counter exported by the health probe test"
+        ))
+        .unwrap();
+        counter.inc();
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (addr, handle) = spawn_health_probe("127.0.0.1:0".parse().unwrap(), async {
+            let _ = shutdown_rx.await;
+        })
+        .await
+        .expect("start health probe");
+
+        let mut stream = tokio::net::TcpStream::connect(addr)
+            .await
+            .expect("connect to health probe");
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("write request");
+
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).await.expect("read response");
+
+        let resp = String::from_utf8_lossy(&buf);
+        assert!(
+            resp.starts_with("HTTP/1.1 200 OK"),
+            "unexpected response: {resp}"
+        );
+        assert!(
+            resp.contains("health_probe_test_total 1"),
+            "missing metric: {resp}"
+        );
+
+        let _ = shutdown_tx.send(());
+        handle.await.unwrap().unwrap();
+    }
 }
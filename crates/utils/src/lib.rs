@@ -6,6 +6,7 @@ pub mod error;
 pub mod hash;
 pub mod health;
 pub mod logging;
+pub mod metrics;
 pub mod network;
 pub mod ordered_block;
 pub mod p2p;
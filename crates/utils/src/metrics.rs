@@ -0,0 +1,72 @@
+//! Process-wide prefix for Prometheus metric names.
+//!
+//! The node, sequencer and prover may share a host and a Prometheus scrape
+//! config, so each process prefixes its metrics with its own namespace. Call
+//! [`init_namespace`] at startup, before any metric is first touched, and
+//! build metric options with [`opts`]. [`encode`] renders every registered
+//! metric for the `/metrics` endpoints.
+
+use prometheus::{Encoder, Opts, TextEncoder};
+use std::sync::OnceLock;
+
+static NAMESPACE: OnceLock<String> = OnceLock::new();
+
+/// Sets the namespace to `namespace`, or derives it from `binary_name`
+/// (`mojave-sequencer` becomes `mojave_sequencer`). An empty namespace
+/// disables the prefix. Only the first call has an effect.
+pub fn init_namespace(namespace: Option<&str>, binary_name: &str) {
+    let namespace = sanitize(namespace.unwrap_or(binary_name));
+    let _ = NAMESPACE.set(namespace);
+}
+
+pub fn namespace() -> Option<&'static str> {
+    NAMESPACE.get().map(String::as_str)
+}
+
+/// Builds metric options, prefixed with the process namespace if one is set.
+pub fn opts(name: &str, help: &str) -> Opts {
+    let opts = Opts::new(name, help);
+    match namespace() {
+        Some(namespace) if !namespace.is_empty() => opts.namespace(namespace),
+        _ => opts,
+    }
+}
+
+/// Content type of [`encode`]'s output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Renders the default registry in the Prometheus text format.
+pub fn encode() -> String {
+    let mut buffer = Vec::new();
+    if let Err(error) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        tracing::warn!(%error, "Failed to encode metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// Replaces characters Prometheus doesn't allow in metric names.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_is_derived_from_binary_name_and_prefixes_metrics() {
+        assert_eq!(sanitize("mojave-sequencer"), "mojave_sequencer");
+
+        init_namespace(None, "mojave-test");
+        // Later calls don't change the namespace.
+        init_namespace(Some("other"), "mojave-other");
+
+        assert_eq!(namespace(), Some("mojave_test"));
+        assert_eq!(
+            opts("rpc_requests_total", "help").fq_name(),
+            "mojave_test_rpc_requests_total"
+        );
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     fmt,
     net::SocketAddr,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use ethrex_common::types::{Genesis, GenesisError};
@@ -18,18 +19,48 @@ const TESTNET_BOOTNODES_PATH: &str = "cmd/mojave/networks/testnet/bootnodes.json
 pub const MAINNET_GENESIS_PATH: &str = "cmd/mojave/networks/mainnet/genesis.json";
 const MAINNET_BOOTNODES_PATH: &str = "cmd/mojave/networks/mainnet/bootnodes.json";
 
+/// How long fetching a published bootnodes list may take at startup.
+pub const BOOTNODES_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
 fn read_bootnodes(path: &str) -> Vec<Node> {
-    // ethrex_p2p::rlpx::Message
-    std::fs::File::open(path)
-        .map_err(|e| {
-            tracing::warn!(path, error = %e, "Failed to open bootnodes file; using empty list");
-        })
-        .and_then(|file| {
-            serde_json::from_reader(file).map_err(|e| {
-                tracing::warn!(path, error = %e, "Failed to parse bootnodes file; using empty list");
-            })
-        })
-        .unwrap_or_default()
+    try_read_bootnodes(path).unwrap_or_else(|e| {
+        tracing::warn!(path, error = %e, "Failed to read bootnodes file; using empty list");
+        Vec::new()
+    })
+}
+
+/// Reads a JSON array of bootnodes. A missing file yields an empty list, but
+/// a file that exists and cannot be read or parsed is an error.
+pub fn try_read_bootnodes(path: impl AsRef<Path>) -> Result<Vec<Node>> {
+    let path = path.as_ref();
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::Io(e)),
+    };
+    serde_json::from_reader(file).map_err(|source| Error::InvalidBootnodes {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Fetches a JSON array of bootnodes published at `url`.
+pub async fn fetch_bootnodes(url: &str, timeout: Duration) -> Result<Vec<Node>> {
+    let fetch = async {
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .build()?
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    };
+    fetch.await.map_err(|source| Error::FetchBootnodes {
+        url: url.to_owned(),
+        source,
+    })
 }
 
 /// Ensures a TCP port is available by attempting to bind to it and immediately
@@ -92,6 +123,28 @@ lazy_static! {
     pub static ref TESTNET_BOOTNODES: Vec<Node> = read_bootnodes(TESTNET_BOOTNODES_PATH);
 }
 
+/// Values applied on top of the default genesis when running `Network::DefaultNet`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenesisOverrides {
+    pub chain_id: Option<u64>,
+    pub genesis_time: Option<u64>,
+}
+
+impl GenesisOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.chain_id.is_none() && self.genesis_time.is_none()
+    }
+
+    pub fn apply(&self, genesis: &mut Genesis) {
+        if let Some(chain_id) = self.chain_id {
+            genesis.config.chain_id = chain_id;
+        }
+        if let Some(genesis_time) = self.genesis_time {
+            genesis.timestamp = genesis_time;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub enum Network {
     #[default]
@@ -138,6 +191,19 @@ impl Network {
         Genesis::try_from(self.get_genesis_path())
     }
 
+    /// Same as [`Network::get_genesis`], applying `overrides` when running `DefaultNet`.
+    /// Overrides are ignored for every other network.
+    pub fn get_genesis_with_overrides(
+        &self,
+        overrides: &GenesisOverrides,
+    ) -> core::result::Result<Genesis, GenesisError> {
+        let mut genesis = self.get_genesis()?;
+        if let Network::DefaultNet = self {
+            overrides.apply(&mut genesis);
+        }
+        Ok(genesis)
+    }
+
     pub fn get_bootnodes(&self) -> Vec<Node> {
         match self {
             Network::Mainnet => MAINNET_BOOTNODES.clone(),
@@ -145,6 +211,16 @@ impl Network {
             Network::DefaultNet | Network::GenesisPath(_) => Vec::new(),
         }
     }
+
+    /// Same as [`Network::get_bootnodes`], failing when the preset bootnodes
+    /// file exists but is malformed instead of falling back to no bootnodes.
+    pub fn try_get_bootnodes(&self) -> Result<Vec<Node>> {
+        match self {
+            Network::Mainnet => try_read_bootnodes(MAINNET_BOOTNODES_PATH),
+            Network::Testnet => try_read_bootnodes(TESTNET_BOOTNODES_PATH),
+            Network::DefaultNet | Network::GenesisPath(_) => Ok(Vec::new()),
+        }
+    }
 }
 
 impl fmt::Display for Network {
@@ -214,6 +290,143 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn defaultnet_applies_genesis_overrides() {
+        let overrides = GenesisOverrides {
+            chain_id: Some(4242),
+            genesis_time: Some(1_700_000_000),
+        };
+        let genesis = Network::DefaultNet
+            .get_genesis_with_overrides(&overrides)
+            .unwrap();
+
+        assert_eq!(genesis.config.chain_id, 4242);
+        assert_eq!(genesis.timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn empty_overrides_keep_default_genesis() {
+        let overrides = GenesisOverrides::default();
+        assert!(overrides.is_empty());
+
+        let genesis = Network::DefaultNet
+            .get_genesis_with_overrides(&overrides)
+            .unwrap();
+        let default = Genesis::default();
+
+        assert_eq!(genesis.config.chain_id, default.config.chain_id);
+        assert_eq!(genesis.timestamp, default.timestamp);
+    }
+
+    fn bootnodes_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "mojave_bootnodes_{name}_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn try_read_bootnodes_parses_valid_file() {
+        let node = Node::new(
+            "127.0.0.1".parse().unwrap(),
+            30303,
+            30303,
+            ethrex_common::H512::from_low_u64_be(1),
+        );
+        let path = bootnodes_file("valid", &serde_json::to_string(&[&node]).unwrap());
+
+        let bootnodes = try_read_bootnodes(&path).unwrap();
+        assert_eq!(bootnodes.len(), 1);
+        assert_eq!(bootnodes[0].enode_url(), node.enode_url());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn try_read_bootnodes_missing_file_is_empty() {
+        let path = std::env::temp_dir().join("mojave_bootnodes_does_not_exist.json");
+        assert!(try_read_bootnodes(path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn try_read_bootnodes_corrupt_file_errors() {
+        let path = bootnodes_file("corrupt", r#"["enode://not-a-node"]"#);
+
+        let err = try_read_bootnodes(&path).unwrap_err();
+        assert!(matches!(err, Error::InvalidBootnodes { .. }));
+        assert!(read_bootnodes(path.to_str().unwrap()).is_empty());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    /// Serves a single HTTP response on an ephemeral port, or never answers
+    /// when `response` is `None`.
+    async fn serve_once(response: Option<String>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+            match response {
+                Some(response) => {
+                    let _ = stream.write_all(response.as_bytes()).await;
+                }
+                None => std::future::pending().await,
+            }
+        });
+        format!("http://{addr}/bootnodes.json")
+    }
+
+    #[tokio::test]
+    async fn fetch_bootnodes_reads_published_list() {
+        let node = Node::new(
+            "127.0.0.1".parse().unwrap(),
+            30303,
+            30303,
+            ethrex_common::H512::from_low_u64_be(2),
+        );
+        let body = serde_json::to_string(&[&node]).unwrap();
+        let url = serve_once(Some(format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )))
+        .await;
+
+        let bootnodes = fetch_bootnodes(&url, BOOTNODES_FETCH_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(bootnodes.len(), 1);
+        assert_eq!(bootnodes[0].enode_url(), node.enode_url());
+    }
+
+    #[tokio::test]
+    async fn fetch_bootnodes_fails_on_error_status() {
+        let url = serve_once(Some(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+        ))
+        .await;
+
+        let err = fetch_bootnodes(&url, BOOTNODES_FETCH_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FetchBootnodes { .. }));
+    }
+
+    #[tokio::test]
+    async fn fetch_bootnodes_times_out() {
+        let url = serve_once(None).await;
+
+        let err = fetch_bootnodes(&url, Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::FetchBootnodes { ref source, .. } if source.is_timeout()));
+    }
+
     #[tokio::test]
     async fn parse_socket_addr_ok_and_helpers_delegate() {
         let socket_addr1 = parse_socket_addr("127.0.0.1", "18123").await.unwrap();
@@ -1,5 +1,7 @@
+#[cfg(unix)]
 use tokio::signal::unix::{SignalKind, signal};
 
+#[cfg(unix)]
 pub async fn wait_for_shutdown_signal() -> std::io::Result<()> {
     let mut sigterm = signal(SignalKind::terminate())?;
     let ctrl_c = tokio::signal::ctrl_c();
@@ -15,3 +17,12 @@ pub async fn wait_for_shutdown_signal() -> std::io::Result<()> {
 
     Ok(())
 }
+
+/// Windows has no SIGTERM, so only Ctrl+C stops the process.
+#[cfg(windows)]
+pub async fn wait_for_shutdown_signal() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await?;
+    tracing::info!("Received Ctrl+C");
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::{
     cmp::Ord,
-    collections::{BinaryHeap, HashSet},
+    collections::{BinaryHeap, HashMap},
     hash::Hash,
     sync::Arc,
 };
@@ -13,6 +13,15 @@ where
     fn key(&self) -> K;
 }
 
+/// Which item a bounded heap drops once a push takes it over its limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Drop the item that would be popped last.
+    Last,
+    /// Drop the item that was inserted first.
+    Oldest,
+}
+
 #[derive(Debug)]
 struct InnerHeap<T, K>
 where
@@ -20,7 +29,10 @@ where
     T: Ord + UniqueHeapItem<K> + Clone,
 {
     heap: BinaryHeap<T>,
-    keys: HashSet<K>,
+    /// Insertion sequence number of every key currently in the heap.
+    keys: HashMap<K, u64>,
+    next_seq: u64,
+    limit: Option<(usize, EvictionPolicy)>,
 }
 
 impl<T, K> InnerHeap<T, K>
@@ -29,18 +41,38 @@ where
     T: Ord + UniqueHeapItem<K> + Clone,
 {
     fn new() -> Self {
-        Self {
-            heap: BinaryHeap::new(),
-            keys: HashSet::new(),
-        }
+        Self::with_capacity(0)
     }
 
     fn with_capacity(capacity: usize) -> Self {
         Self {
             heap: BinaryHeap::with_capacity(capacity),
-            keys: HashSet::new(),
+            keys: HashMap::new(),
+            next_seq: 0,
+            limit: None,
         }
     }
+
+    /// Removes one item according to the eviction policy if the heap holds
+    /// more than its limit.
+    fn evict(&mut self) -> Option<T> {
+        let (max_len, policy) = self.limit?;
+        if self.heap.len() <= max_len {
+            return None;
+        }
+        let key = match policy {
+            EvictionPolicy::Last => self.heap.iter().min()?.key(),
+            EvictionPolicy::Oldest => self
+                .keys
+                .iter()
+                .min_by_key(|(_, seq)| **seq)
+                .map(|(key, _)| key.clone())?,
+        };
+        let evicted = self.heap.iter().find(|item| item.key() == key).cloned();
+        self.heap.retain(|item| item.key() != key);
+        self.keys.remove(&key);
+        evicted
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -72,16 +104,41 @@ where
         }
     }
 
+    /// Creates a heap that never holds more than `max_len` items, evicting
+    /// one item according to `policy` whenever a push goes over the limit.
+    pub fn bounded(max_len: usize, policy: EvictionPolicy) -> Self {
+        let mut inner = InnerHeap::with_capacity(max_len);
+        inner.limit = Some((max_len, policy));
+        Self {
+            inner: Arc::new(RwLock::new(inner)),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
     pub async fn push(&self, item: T) -> bool {
+        self.push_evicting(item).await.0
+    }
+
+    /// Pushes an item and returns whether it is now in the heap, along with
+    /// the item evicted to make room for it, if any. The pushed item itself
+    /// can be the one evicted.
+    pub async fn push_evicting(&self, item: T) -> (bool, Option<T>) {
         let key = item.key();
         let mut inner = self.inner.write().await;
-        if inner.keys.insert(key) {
-            inner.heap.push(item);
+        if inner.keys.contains_key(&key) {
+            return (false, None);
+        }
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.keys.insert(key.clone(), seq);
+        inner.heap.push(item);
+
+        let evicted = inner.evict();
+        let inserted = evicted.as_ref().is_none_or(|evicted| evicted.key() != key);
+        if inserted {
             self.notify.notify_one();
-            true
-        } else {
-            false
         }
+        (inserted, evicted)
     }
 
     pub async fn pop(&self) -> Option<T> {
@@ -472,4 +529,41 @@ mod tests {
         assert_eq!(popped_values, (0..10).rev().collect::<Vec<i32>>());
         assert!(heap.is_empty().await);
     }
+
+    #[tokio::test]
+    async fn test_bounded_evicts_last() {
+        let heap = AsyncUniqueHeap::bounded(2, EvictionPolicy::Last);
+        assert!(heap.push(NumericItem::new(5, 1)).await);
+        assert!(heap.push(NumericItem::new(3, 2)).await);
+
+        let (inserted, evicted) = heap.push_evicting(NumericItem::new(4, 3)).await;
+        assert!(inserted);
+        assert_eq!(evicted, Some(NumericItem::new(3, 2)));
+
+        // An item that would be popped last is dropped straight away.
+        let (inserted, evicted) = heap.push_evicting(NumericItem::new(1, 4)).await;
+        assert!(!inserted);
+        assert_eq!(evicted, Some(NumericItem::new(1, 4)));
+
+        assert_eq!(heap.len().await, 2);
+        assert_eq!(heap.pop().await, Some(NumericItem::new(5, 1)));
+        assert_eq!(heap.pop().await, Some(NumericItem::new(4, 3)));
+    }
+
+    #[tokio::test]
+    async fn test_bounded_evicts_oldest() {
+        let heap = AsyncUniqueHeap::bounded(2, EvictionPolicy::Oldest);
+        assert!(heap.push(NumericItem::new(5, 1)).await);
+        assert!(heap.push(NumericItem::new(3, 2)).await);
+
+        let (inserted, evicted) = heap.push_evicting(NumericItem::new(1, 3)).await;
+        assert!(inserted);
+        assert_eq!(evicted, Some(NumericItem::new(5, 1)));
+
+        // The evicted key can be pushed again.
+        assert!(heap.push(NumericItem::new(5, 1)).await);
+        assert_eq!(heap.len().await, 2);
+        assert_eq!(heap.pop().await, Some(NumericItem::new(5, 1)));
+        assert_eq!(heap.pop().await, Some(NumericItem::new(1, 3)));
+    }
 }